@@ -0,0 +1,256 @@
+//! Google Analytics Data API (GA4), for running reports on a property.
+
+use std::error::Error;
+use std::fmt;
+
+use futures_util::{Stream, stream};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{Google, Token};
+
+/// Scope granting read-only access to Google Analytics data.
+pub const SCOPE_ANALYTICS_READONLY: &str = "https://www.googleapis.com/auth/analytics.readonly";
+
+const ANALYTICS_DATA_API: &str = "https://analyticsdata.googleapis.com/v1beta";
+
+/// The largest number of rows the API returns per request.
+const MAX_LIMIT: u32 = 250_000;
+
+/// A client for the Analytics Data API.
+#[derive(Clone, Copy)]
+pub struct Analytics<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A report request, built with `ReportQuery::new` and the chained setters.
+#[derive(Debug, Clone, Default)]
+pub struct ReportQuery {
+    date_ranges: Vec<Value>,
+    dimensions: Vec<String>,
+    metrics: Vec<String>,
+    order_bys: Vec<Value>,
+    filters: Vec<Value>,
+    limit: Option<u32>,
+}
+
+/// A page of report rows.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Report {
+    pub dimension_headers: Vec<DimensionHeader>,
+    pub metric_headers: Vec<MetricHeader>,
+    pub rows: Vec<Row>,
+    /// The total number of rows of the report, across all pages.
+    pub row_count: u64,
+    /// Information about the report, e.g. whether data was sampled; kept as raw JSON.
+    pub metadata: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DimensionHeader {
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MetricHeader {
+    pub name: String,
+    /// The metric type, e.g. `TYPE_INTEGER`, `TYPE_FLOAT` or `TYPE_SECONDS`.
+    #[serde(rename = "type")]
+    pub metric_type: String,
+}
+
+/// A report row, with one value per dimension and metric header.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Row {
+    pub dimension_values: Vec<ReportValue>,
+    pub metric_values: Vec<ReportValue>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReportValue {
+    pub value: String,
+}
+
+impl ReportQuery {
+    /// Creates an empty query. At least one date range and one metric or dimension are
+    /// required.
+    pub fn new() -> ReportQuery {
+        ReportQuery::default()
+    }
+
+    /// Adds a date range. Dates are `YYYY-MM-DD`, e.g. a `NaiveDate`, or relative dates
+    /// such as `30daysAgo`, `yesterday` and `today`.
+    pub fn date_range(
+        mut self,
+        start_date: impl fmt::Display,
+        end_date: impl fmt::Display,
+    ) -> ReportQuery {
+        self.date_ranges.push(json!({
+            "startDate": start_date.to_string(),
+            "endDate": end_date.to_string(),
+        }));
+        self
+    }
+
+    /// Groups rows by a dimension, e.g. `country`, `date` or `pagePath`.
+    pub fn dimension(mut self, name: impl Into<String>) -> ReportQuery {
+        self.dimensions.push(name.into());
+        self
+    }
+
+    /// Adds a metric column, e.g. `activeUsers`, `sessions` or `screenPageViews`.
+    pub fn metric(mut self, name: impl Into<String>) -> ReportQuery {
+        self.metrics.push(name.into());
+        self
+    }
+
+    /// Sorts rows by a metric.
+    pub fn order_by_metric(mut self, name: impl Into<String>, descending: bool) -> ReportQuery {
+        self.order_bys.push(json!({
+            "metric": { "metricName": name.into() },
+            "desc": descending,
+        }));
+        self
+    }
+
+    /// Sorts rows by a dimension.
+    pub fn order_by_dimension(mut self, name: impl Into<String>, descending: bool) -> ReportQuery {
+        self.order_bys.push(json!({
+            "dimension": { "dimensionName": name.into() },
+            "desc": descending,
+        }));
+        self
+    }
+
+    /// Only keeps rows whose dimension exactly equals `value`. Several filters must all
+    /// match.
+    pub fn filter(mut self, dimension: impl Into<String>, value: impl Into<String>) -> ReportQuery {
+        self.filters.push(json!({
+            "filter": {
+                "fieldName": dimension.into(),
+                "stringFilter": { "matchType": "EXACT", "value": value.into() },
+            },
+        }));
+        self
+    }
+
+    /// Limits the number of rows per page, at most 250000.
+    pub fn limit(mut self, limit: u32) -> ReportQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn body(&self, offset: u64) -> Value {
+        let dimensions: Vec<_> = self
+            .dimensions
+            .iter()
+            .map(|name| json!({ "name": name }))
+            .collect();
+        let metrics: Vec<_> = self
+            .metrics
+            .iter()
+            .map(|name| json!({ "name": name }))
+            .collect();
+
+        let mut body = json!({
+            "dateRanges": self.date_ranges,
+            "dimensions": dimensions,
+            "metrics": metrics,
+            "orderBys": self.order_bys,
+            "offset": offset.to_string(),
+            "limit": self.limit.unwrap_or(MAX_LIMIT).to_string(),
+        });
+        match self.filters.as_slice() {
+            [] => {}
+            [filter] => body["dimensionFilter"] = filter.clone(),
+            filters => body["dimensionFilter"] = json!({ "andGroup": { "expressions": filters } }),
+        }
+        body
+    }
+}
+
+impl Report {
+    /// Returns the value of the dimension or metric named `name` in `row`.
+    pub fn value<'r>(&self, row: &'r Row, name: &str) -> Option<&'r str> {
+        if let Some(index) = self.dimension_headers.iter().position(|h| h.name == name) {
+            return row
+                .dimension_values
+                .get(index)
+                .map(|value| value.value.as_str());
+        }
+        let index = self.metric_headers.iter().position(|h| h.name == name)?;
+        row.metric_values
+            .get(index)
+            .map(|value| value.value.as_str())
+    }
+}
+
+impl Google {
+    /// Returns an Analytics Data API client that authorizes its requests with `token`.
+    pub fn analytics<'a>(&'a self, token: &'a Token) -> Analytics<'a> {
+        Analytics {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Analytics<'a> {
+    /// Runs a report and returns a single page of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `property_id` - The numeric id of the GA4 property.
+    /// * `query` - The report to run.
+    /// * `offset` - The index of the first row, `0` for the first page.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. for incompatible
+    /// dimensions and metrics.
+    pub async fn run_report(
+        &self,
+        property_id: &str,
+        query: &ReportQuery,
+        offset: u64,
+    ) -> Result<Report, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{ANALYTICS_DATA_API}/properties/{property_id}:runReport"
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&query.body(offset));
+
+        self.google.send_json(request).await
+    }
+
+    /// Runs a report and streams every page of rows.
+    pub fn reports<'s>(
+        self,
+        property_id: &'s str,
+        query: &'s ReportQuery,
+    ) -> impl Stream<Item = Result<Report, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        stream::try_unfold(Some(0), move |offset| async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+
+            let report = self.run_report(property_id, query, offset).await?;
+            let end = offset + report.rows.len() as u64;
+            let next = (!report.rows.is_empty() && end < report.row_count).then_some(end);
+
+            Ok(Some((report, next)))
+        })
+    }
+}
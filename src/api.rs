@@ -0,0 +1,405 @@
+// Helpers shared by the API clients. Those only some APIs use are compiled with their features.
+
+use std::error::Error;
+use std::future::Future;
+
+use reqwest::header::ACCEPT_LANGUAGE;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::de::DeserializeOwned;
+use tracing::Instrument;
+
+use crate::Google;
+use crate::error::{GoogleError, MAX_BACKOFF};
+
+/// The header carrying the correlation id of a request.
+const CORRELATION_HEADER: &str = "X-Request-Id";
+
+impl Google {
+    /// Sends an API request, turning any non-successful response into a `GoogleError`.
+    ///
+    /// Retryable errors are retried up to the number of times set with
+    /// `Google::with_max_retries`, waiting `GoogleError::retry_delay` in between. Server
+    /// errors are only retried for idempotent methods, since a `POST` or `PATCH` may have
+    /// taken effect before failing; rate limit errors are retried for every method.
+    /// Requests with a streaming body cannot be resent and are never retried, nor are
+    /// errors whose `Retry-After` exceeds the maximum backoff.
+    ///
+    /// The correlation id, if any, is sent in the `X-Request-Id` header of every attempt,
+    /// attached to the returned `GoogleError` and recorded on a `google_request` tracing
+    /// span around all attempts. The timeout set with `Google::with_timeout`
+    /// bounds all attempts together.
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<Response, Box<dyn Error>> {
+        self.with_deadline(self.send_with_retries(request, |status| status.is_success()))
+            .await
+    }
+
+    /// Like `send`, but also returns responses whose status `accepted` allows instead of
+    /// turning them into a `GoogleError`, e.g. the `308 Resume Incomplete` of an upload.
+    #[cfg(feature = "upload")]
+    pub(crate) async fn send_accepting(
+        &self,
+        request: RequestBuilder,
+        accepted: fn(StatusCode) -> bool,
+    ) -> Result<Response, Box<dyn Error>> {
+        self.with_deadline(self.send_with_retries(request, accepted))
+            .await
+    }
+
+    async fn send_with_retries(
+        &self,
+        mut request: RequestBuilder,
+        accepted: fn(StatusCode) -> bool,
+    ) -> Result<Response, Box<dyn Error>> {
+        let correlation_id = self.correlation_id();
+        if let Some(id) = &correlation_id {
+            request = request.header(CORRELATION_HEADER, id.as_str());
+        }
+        if let Some(language) = &self.accept_language {
+            request = request.header(ACCEPT_LANGUAGE, language.as_ref());
+        }
+
+        let (client, request) = request.build_split();
+        let mut request = request.map_err(reqwest::Error::without_url)?;
+        let idempotent = is_idempotent(request.method());
+        let span =
+            tracing::info_span!("google_request", correlation_id = correlation_id.as_deref());
+
+        async {
+            let mut attempt = 0;
+            loop {
+                let retry = if attempt < self.max_retries {
+                    request.try_clone()
+                } else {
+                    None
+                };
+                let response = client
+                    .execute(request)
+                    .await
+                    .map_err(reqwest::Error::without_url)?;
+                if accepted(response.status()) {
+                    return Ok(response);
+                }
+
+                let error = GoogleError::from_response(response)
+                    .await
+                    .with_correlation_id(correlation_id.clone());
+                let delay = error
+                    .retry_delay(attempt)
+                    .filter(|_| idempotent || !error.status().is_server_error());
+                match (retry, delay) {
+                    (Some(next), Some(delay)) if delay <= MAX_BACKOFF => {
+                        tracing::debug!(status = %error.status(), ?delay, "retrying request");
+                        tokio::time::sleep(delay).await;
+                        request = next;
+                        attempt += 1;
+                    }
+                    _ => return Err(error.into()),
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// The correlation id to send with a request: the one set with
+    /// `Google::with_correlation_id`, or a random one when generation is enabled.
+    fn correlation_id(&self) -> Option<String> {
+        if let Some(id) = &self.correlation_id {
+            return Some(id.to_string());
+        }
+        if !self.generate_correlation_ids {
+            return None;
+        }
+
+        let mut bytes = [0; 16];
+        SystemRandom::new().fill(&mut bytes).ok()?;
+        Some(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+
+    /// Sends an API request and deserializes the JSON response body into `T`.
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Box<dyn Error>> {
+        self.with_deadline(async {
+            let response = self
+                .send_with_retries(request, |status| status.is_success())
+                .await?;
+
+            Ok(response
+                .json::<T>()
+                .await
+                .map_err(reqwest::Error::without_url)?)
+        })
+        .await
+    }
+
+    /// Runs `call`, failing it once the timeout set with `Google::with_timeout` elapses.
+    /// The request in flight is aborted when `call` is dropped.
+    pub(crate) async fn with_deadline<T>(
+        &self,
+        call: impl Future<Output = Result<T, Box<dyn Error>>>,
+    ) -> Result<T, Box<dyn Error>> {
+        let Some(timeout) = self.timeout else {
+            return call.await;
+        };
+
+        tokio::time::timeout(timeout, call)
+            .await
+            .map_err(|_| format!("Request timed out after {timeout:?}"))?
+    }
+}
+
+/// Returns `true` for the methods that can be sent again without changing the outcome, as
+/// defined by RFC 9110.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+/// A page of a paginated list response.
+#[cfg(any(
+    feature = "admin-reports",
+    feature = "business-profile",
+    feature = "calendar",
+    feature = "classroom",
+    feature = "directory",
+    feature = "drive",
+    feature = "firestore",
+    feature = "forms",
+    feature = "gmail",
+    feature = "meet",
+    feature = "people",
+    feature = "photos",
+    feature = "tasks",
+    feature = "youtube"
+))]
+pub(crate) trait Page {
+    /// The token to request the following page with, `None` on the last page.
+    fn next_page_token(&self) -> Option<&str>;
+}
+
+/// Turns a page fetching function into a stream of pages, following `nextPageToken` until
+/// the last page has been returned.
+#[cfg(any(
+    feature = "admin-reports",
+    feature = "business-profile",
+    feature = "calendar",
+    feature = "classroom",
+    feature = "directory",
+    feature = "drive",
+    feature = "firestore",
+    feature = "forms",
+    feature = "gmail",
+    feature = "meet",
+    feature = "people",
+    feature = "photos",
+    feature = "tasks",
+    feature = "youtube"
+))]
+pub(crate) fn paginate<P, F, Fut>(
+    fetch: F,
+) -> impl futures_util::Stream<Item = Result<P, Box<dyn Error>>>
+where
+    P: Page,
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<P, Box<dyn Error>>>,
+{
+    futures_util::stream::try_unfold((Some(None), fetch), |(page_token, mut fetch)| async move {
+        let Some(page_token) = page_token else {
+            return Ok(None);
+        };
+
+        let page = fetch(page_token).await?;
+        let next = page.next_page_token().map(|token| Some(token.to_string()));
+
+        Ok(Some((page, (next, fetch))))
+    })
+}
+
+/// Percent-encodes a value for use as a single URL path segment, e.g. a calendar id
+/// containing `@` or `#`.
+#[cfg(any(
+    feature = "admin-reports",
+    feature = "apps-script",
+    feature = "bigquery",
+    feature = "calendar",
+    feature = "directory",
+    feature = "drive",
+    feature = "fcm",
+    feature = "firestore",
+    feature = "gmail",
+    feature = "identity-platform",
+    feature = "places",
+    feature = "play",
+    feature = "play-integrity",
+    feature = "pubsub",
+    feature = "recaptcha",
+    feature = "search-console",
+    feature = "sheets",
+    feature = "speech",
+    feature = "storage",
+    feature = "translate",
+    feature = "wallet"
+))]
+pub(crate) fn encode_path(segment: &str) -> String {
+    use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+
+    utf8_percent_encode(segment, NON_ALPHANUMERIC).to_string()
+}
+
+/// Compares two byte strings in time independent of where they differ, for checking
+/// secrets such as OAuth2 states and channel tokens.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// How a request is authorized: with a user's OAuth2 access token or with an API key, for
+/// APIs that accept both for public data.
+#[cfg(any(
+    feature = "language",
+    feature = "places",
+    feature = "speech",
+    feature = "text-to-speech",
+    feature = "translate",
+    feature = "vision",
+    feature = "youtube"
+))]
+#[derive(Clone, Copy)]
+pub(crate) enum Auth<'a> {
+    Bearer(&'a str),
+    Key(&'a str),
+}
+
+#[cfg(any(
+    feature = "language",
+    feature = "places",
+    feature = "speech",
+    feature = "text-to-speech",
+    feature = "translate",
+    feature = "vision",
+    feature = "youtube"
+))]
+impl Auth<'_> {
+    /// Adds the credentials to `request`.
+    pub(crate) fn apply(self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::Bearer(token) => request.bearer_auth(token),
+            Auth::Key(key) => request.query(&[("key", key)]),
+        }
+    }
+}
+
+/// Formats a byte range as an HTTP `Range` header value, `None` for the whole content.
+///
+/// # Errors
+///
+/// This function returns an error if `range` is empty, e.g. `5..5`, as no `Range` header
+/// can express it.
+#[cfg(any(feature = "drive", feature = "storage"))]
+pub(crate) fn range_header(
+    range: &impl std::ops::RangeBounds<u64>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    use std::ops::Bound;
+
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(1).ok_or("Byte range is empty")?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => Some(*end),
+        Bound::Excluded(end) => Some(end.checked_sub(1).ok_or("Byte range is empty")?),
+        Bound::Unbounded => None,
+    };
+
+    match (start, end) {
+        (_, Some(end)) if end < start => Err("Byte range is empty".into()),
+        (0, None) => Ok(None),
+        (start, None) => Ok(Some(format!("bytes={start}-"))),
+        (start, Some(end)) => Ok(Some(format!("bytes={start}-{end}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestServer, response};
+
+    fn google() -> Google {
+        Google::new_public("appid".to_string(), "http://localhost".to_string()).with_max_retries(1)
+    }
+
+    #[cfg(any(feature = "drive", feature = "storage"))]
+    #[test]
+    fn formats_range_headers() {
+        assert_eq!(range_header(&..).unwrap(), None);
+        assert_eq!(range_header(&(0..)).unwrap(), None);
+        assert_eq!(range_header(&(5..)).unwrap().unwrap(), "bytes=5-");
+        assert_eq!(range_header(&(5..10)).unwrap().unwrap(), "bytes=5-9");
+        assert_eq!(range_header(&(5..=5)).unwrap().unwrap(), "bytes=5-5");
+        assert_eq!(range_header(&..1).unwrap().unwrap(), "bytes=0-0");
+    }
+
+    #[cfg(any(feature = "drive", feature = "storage"))]
+    #[test]
+    fn rejects_empty_ranges() {
+        assert!(range_header(&..0).is_err());
+        assert!(range_header(&(5..5)).is_err());
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_of_idempotent_requests() {
+        let server = TestServer::start(vec![
+            response("503 Service Unavailable", &[("Retry-After", "0")], ""),
+            response("200 OK", &[], ""),
+        ])
+        .await;
+        let google = google();
+
+        let response = google.send(google.http.get(&server.url)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_server_errors_of_posts() {
+        let server = TestServer::start(vec![
+            response("503 Service Unavailable", &[("Retry-After", "0")], ""),
+            response("200 OK", &[], ""),
+        ])
+        .await;
+        let google = google();
+
+        let err = google
+            .send(google.http.post(&server.url).body("message"))
+            .await
+            .unwrap_err();
+
+        let err = err.downcast_ref::<GoogleError>().unwrap();
+        assert_eq!(err.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limited_posts() {
+        let server = TestServer::start(vec![
+            response("429 Too Many Requests", &[("Retry-After", "0")], ""),
+            response("200 OK", &[], ""),
+        ])
+        .await;
+        let google = google();
+
+        let response = google
+            .send(google.http.post(&server.url).body("message"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(server.requests().len(), 2);
+    }
+}
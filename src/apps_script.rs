@@ -0,0 +1,154 @@
+//! Apps Script API, for running functions of deployed Apps Script projects.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::encode_path;
+use crate::{Google, Token};
+
+const SCRIPT_API: &str = "https://script.googleapis.com/v1";
+
+/// A client for the Apps Script API.
+///
+/// The token must carry every scope the script itself uses, and be issued for an OAuth
+/// client of the same Cloud project as the script, which must be deployed as an API
+/// executable.
+#[derive(Clone, Copy)]
+pub struct AppsScript<'a> {
+    google: &'a Google,
+    token: &'a Token,
+    dev_mode: bool,
+}
+
+/// An exception thrown by a script function.
+///
+/// `AppsScript::run` returns it as a `Box<dyn Error>`, to be inspected with
+/// `err.downcast_ref::<ScriptError>()`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScriptError {
+    pub error_message: String,
+    /// The JavaScript error type, e.g. `TypeError` or `ScriptError`.
+    pub error_type: String,
+    /// The stack trace, the innermost call first.
+    #[serde(rename = "scriptStackTraceElements")]
+    pub stack_trace: Vec<StackTraceElement>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StackTraceElement {
+    pub function: String,
+    pub line_number: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Operation {
+    response: Option<ExecutionResponse>,
+    error: Option<OperationError>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ExecutionResponse {
+    result: Value,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct OperationError {
+    message: String,
+    details: Vec<ScriptError>,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.error_type, self.error_message)?;
+        for element in &self.stack_trace {
+            match element.line_number {
+                Some(line) => write!(f, "\n    at {}:{line}", element.function)?,
+                None => write!(f, "\n    at {}", element.function)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for ScriptError {}
+
+impl Google {
+    /// Returns an Apps Script API client that authorizes its requests with `token`.
+    pub fn apps_script<'a>(&'a self, token: &'a Token) -> AppsScript<'a> {
+        AppsScript {
+            google: self,
+            token,
+            dev_mode: false,
+        }
+    }
+}
+
+impl AppsScript<'_> {
+    /// Runs the most recently saved code instead of the deployed version. Only works for
+    /// the owner of the script.
+    pub fn dev_mode(mut self) -> Self {
+        self.dev_mode = true;
+        self
+    }
+
+    /// Runs a function of a script and returns its result.
+    ///
+    /// # Arguments
+    ///
+    /// * `script_id` - The deployment id of the API executable.
+    /// * `function` - The name of the function to run.
+    /// * `parameters` - The arguments, as anything serializing to a JSON array, e.g. a
+    ///   tuple like `("sheet", 42)` or `()` for none. Only primitive types, arrays and
+    ///   plain objects can be passed to Apps Script.
+    ///
+    /// # Errors
+    ///
+    /// This function returns a `ScriptError` if the function throws, and other errors if
+    /// the request fails, the parameters are not an array, or the result cannot be
+    /// deserialized into `T`.
+    pub async fn run<P: Serialize, T: DeserializeOwned>(
+        &self,
+        script_id: &str,
+        function: &str,
+        parameters: &P,
+    ) -> Result<T, Box<dyn Error>> {
+        let parameters = match serde_json::to_value(parameters)? {
+            Value::Null => Vec::new(),
+            Value::Array(parameters) => parameters,
+            _ => return Err("Script parameters must serialize to an array".into()),
+        };
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{SCRIPT_API}/scripts/{}:run",
+                encode_path(script_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({
+                "function": function,
+                "parameters": parameters,
+                "devMode": self.dev_mode,
+            }));
+        let operation: Operation = self.google.send_json(request).await?;
+
+        if let Some(error) = operation.error {
+            return Err(match error.details.into_iter().next() {
+                Some(script_error) => script_error.into(),
+                None => error.message.into(),
+            });
+        }
+        let result = operation.response.map(|response| response.result);
+
+        Ok(serde_json::from_value(result.unwrap_or_default())?)
+    }
+}
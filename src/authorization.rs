@@ -0,0 +1,299 @@
+use std::error::Error;
+use std::fmt;
+
+use oauth2::{CsrfToken, PkceCodeChallenge, Scope};
+use serde::{Deserialize, Serialize};
+
+use crate::api::constant_time_eq;
+use crate::{Google, REDACTED, Scopes, UserInfo};
+
+/// Builds an authorization URL with parameters `get_redirect_url` does not set, started
+/// with `Google::authorization`.
+///
+/// The state, PKCE challenge and nonce are generated by the builder and returned in the
+/// `Authorization`, to be kept in the user's session until the callback arrives. Any other
+/// parameter Google supports can be added with `param`.
+pub struct AuthorizationBuilder<'a> {
+    google: &'a Google,
+    state: Option<String>,
+    scopes: Option<Vec<String>>,
+    params: Vec<(String, String)>,
+    pkce: bool,
+    nonce: bool,
+}
+
+/// An authorization URL together with the values to check when the user comes back.
+#[derive(Clone)]
+pub struct Authorization {
+    /// The URL to redirect the user to.
+    pub url: String,
+    /// The state to check with `AuthCallback::verify`.
+    pub state: String,
+    /// The scopes requested, assumed granted by `Google::get_token_for_authorization` when
+    /// Google does not list the granted scopes.
+    pub scopes: Scopes,
+    /// The PKCE code verifier, used by `Google::get_token_for_authorization` or passed to
+    /// `Google::get_token_with_verifier`, when PKCE was enabled.
+    pub pkce_verifier: Option<String>,
+    /// The nonce to check the ID token against with `Google::verify_id_token_with_nonce`,
+    /// when requested.
+    pub nonce: Option<String>,
+}
+
+/// The authorization code of a PKCE flow together with its verifier, as sent by a browser
+/// app to the backend exchanging it with `Google::get_token_with_verifier`.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PkceCallback {
+    pub code: String,
+    pub verifier: String,
+}
+
+impl Google {
+    /// Starts building an authorization URL, for parameters this crate does not model or
+    /// to add PKCE and a nonce.
+    pub fn authorization(&self) -> AuthorizationBuilder<'_> {
+        AuthorizationBuilder {
+            google: self,
+            state: None,
+            scopes: None,
+            params: Vec::new(),
+            pkce: false,
+            nonce: false,
+        }
+    }
+}
+
+impl Google {
+    /// Verifies an ID token like `verify_id_token`, and checks that its `nonce` claim is
+    /// the nonce of the authorization it was issued for, so an ID token captured from
+    /// another sign-in cannot be replayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `id_token` - The ID token, e.g. `Token::id_token` of the exchanged code.
+    /// * `expected_nonce` - The nonce returned in `Authorization::nonce`, kept in the
+    ///   user's session until the callback arrives.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error as described for `verify_id_token`, or if the token
+    /// has no nonce or another one.
+    pub async fn verify_id_token_with_nonce(
+        &self,
+        id_token: &str,
+        expected_nonce: &str,
+    ) -> Result<UserInfo, Box<dyn Error>> {
+        let userinfo = self.verify_id_token(id_token).await?;
+        check_nonce(&userinfo, expected_nonce)?;
+
+        Ok(userinfo)
+    }
+}
+
+impl AuthorizationBuilder<'_> {
+    /// Uses `state` instead of a random state.
+    pub fn state(mut self, state: &str) -> Self {
+        self.state = Some(state.to_string());
+        self
+    }
+
+    /// Requests `scopes` instead of the client's default scopes.
+    pub fn scopes<S: AsRef<str>>(mut self, scopes: &[S]) -> Self {
+        self.scopes = Some(
+            scopes
+                .iter()
+                .map(|scope| scope.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Adds a query parameter, e.g. `login_hint`, `prompt` or `access_type`.
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.params.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Makes Google show the account chooser even when the user is signed in to a single
+    /// account, e.g. to link another Google account, by setting `prompt=select_account`.
+    pub fn select_account(self) -> Self {
+        self.param("prompt", "select_account")
+    }
+
+    /// Adds a PKCE challenge using SHA-256. Its verifier is returned in
+    /// `Authorization::pkce_verifier`.
+    pub fn pkce(mut self) -> Self {
+        self.pkce = true;
+        self
+    }
+
+    /// Adds a random nonce, which Google echoes in the ID token to protect against replay.
+    /// Check it with `Google::verify_id_token_with_nonce`.
+    pub fn nonce(mut self) -> Self {
+        self.nonce = true;
+        self
+    }
+
+    /// Generates the authorization URL.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a scope is outside the allow-list set with
+    /// `Google::with_allowed_scopes`.
+    pub fn build(self) -> Result<Authorization, Box<dyn Error>> {
+        let scopes = match self.scopes {
+            Some(scopes) => scopes,
+            None => self
+                .google
+                .default_scopes
+                .iter()
+                .map(str::to_string)
+                .collect(),
+        };
+        self.google.check_scopes(&scopes)?;
+
+        let state = self
+            .state
+            .map_or_else(CsrfToken::new_random, CsrfToken::new);
+        let mut request = self
+            .google
+            .authorization_request(state)
+            .add_scopes(scopes.iter().cloned().map(Scope::new));
+        for (name, value) in self.params {
+            request = request.add_extra_param(name, value);
+        }
+
+        let mut pkce_verifier = None;
+        if self.pkce {
+            let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+            request = request.set_pkce_challenge(challenge);
+            pkce_verifier = Some(verifier.secret().clone());
+        }
+
+        let nonce = self.nonce.then(|| CsrfToken::new_random().secret().clone());
+        if let Some(nonce) = &nonce {
+            request = request.add_extra_param("nonce", nonce.clone());
+        }
+
+        let (url, state) = request.url();
+
+        Ok(Authorization {
+            url: url.to_string(),
+            state: state.secret().clone(),
+            scopes: scopes.into_iter().collect(),
+            pkce_verifier,
+            nonce,
+        })
+    }
+}
+
+impl fmt::Debug for Authorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Authorization")
+            .field("url", &self.url)
+            .field("state", &self.state)
+            .field("scopes", &self.scopes)
+            .field(
+                "pkce_verifier",
+                &self.pkce_verifier.as_ref().map(|_| REDACTED),
+            )
+            .field("nonce", &self.nonce)
+            .finish()
+    }
+}
+
+/// Compares the `nonce` claim of a verified ID token with `expected` in constant time.
+fn check_nonce(userinfo: &UserInfo, expected: &str) -> Result<(), Box<dyn Error>> {
+    let nonce = userinfo
+        .extra
+        .get("nonce")
+        .and_then(|nonce| nonce.as_str())
+        .ok_or("ID token has no nonce")?;
+    if !constant_time_eq(nonce.as_bytes(), expected.as_bytes()) {
+        return Err("ID token nonce does not match".into());
+    }
+
+    Ok(())
+}
+
+impl fmt::Debug for PkceCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PkceCallback")
+            .field("code", &REDACTED)
+            .field("verifier", &REDACTED)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_server::{TestServer, token_response, with_token_server};
+
+    fn userinfo(nonce: Option<&str>) -> UserInfo {
+        let mut claims = json!({
+            "sub": "1234",
+            "name": "Ada",
+            "picture": "",
+            "email": "ada@example.com",
+            "email_verified": true,
+        });
+        if let Some(nonce) = nonce {
+            claims["nonce"] = json!(nonce);
+        }
+        serde_json::from_value(claims).unwrap()
+    }
+
+    #[test]
+    fn accepts_matching_nonce() {
+        assert!(check_nonce(&userinfo(Some("n-0S6_WzA2Mj")), "n-0S6_WzA2Mj").is_ok());
+    }
+
+    #[test]
+    fn rejects_other_nonce() {
+        let err = check_nonce(&userinfo(Some("n-0S6_WzA2Mj")), "n-0S6_WzA2Mk").unwrap_err();
+        assert_eq!(err.to_string(), "ID token nonce does not match");
+        assert!(check_nonce(&userinfo(Some("n-0S6")), "n-0S6_WzA2Mj").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_nonce() {
+        let err = check_nonce(&userinfo(None), "n-0S6_WzA2Mj").unwrap_err();
+        assert_eq!(err.to_string(), "ID token has no nonce");
+    }
+
+    #[test]
+    fn sends_generated_nonce() {
+        let google = Google::new(
+            "client-id".to_string(),
+            "secret".to_string(),
+            "https://app.example.com/callback".to_string(),
+        );
+        let authorization = google.authorization().nonce().build().unwrap();
+
+        let nonce = authorization.nonce.unwrap();
+        assert!(authorization.url.contains(&format!("nonce={nonce}")));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_requested_scopes() {
+        let server = TestServer::start(vec![token_response("access")]).await;
+        let google = Google::new(
+            "client-id".to_string(),
+            "secret".to_string(),
+            "https://app.example.com/callback".to_string(),
+        );
+        let google = with_token_server(google, &server);
+        let scope = "https://www.googleapis.com/auth/drive.file";
+        let authorization = google.authorization().scopes(&[scope]).build().unwrap();
+
+        let token = google
+            .get_token_for_authorization("code".to_string(), &authorization)
+            .await
+            .unwrap();
+
+        assert_eq!(token.scopes(), &Scopes::new([scope]));
+    }
+}
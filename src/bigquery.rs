@@ -0,0 +1,529 @@
+//! BigQuery API, for running SQL queries and reading their results.
+
+use std::error::Error;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::{Stream, TryStreamExt, stream};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::api::encode_path;
+use crate::de::option_number_from_string;
+use crate::{Google, Token};
+
+/// Scope granting access to BigQuery data and jobs.
+pub const SCOPE_BIGQUERY: &str = "https://www.googleapis.com/auth/bigquery";
+/// Scope granting read-only access to BigQuery data.
+pub const SCOPE_BIGQUERY_READONLY: &str = "https://www.googleapis.com/auth/bigquery.readonly";
+
+const BIGQUERY_API: &str = "https://bigquery.googleapis.com/bigquery/v2";
+
+/// How long a single request waits for the query to complete, in milliseconds.
+const WAIT_TIMEOUT_MS: u32 = 10_000;
+
+/// A client for the BigQuery API.
+#[derive(Clone, Copy)]
+pub struct BigQuery<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A GoogleSQL query, built with `SqlQuery::new` and the chained setters.
+#[derive(Debug, Clone)]
+pub struct SqlQuery {
+    query: String,
+    parameters: Vec<(String, ParameterValue)>,
+    default_dataset: Option<String>,
+    location: Option<String>,
+    max_results: Option<u32>,
+    dry_run: bool,
+}
+
+/// The value of a named query parameter, referenced as `@name` in the SQL text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterValue {
+    String(String),
+    Int64(i64),
+    Float64(f64),
+    Bool(bool),
+    Date(NaiveDate),
+    Timestamp(DateTime<Utc>),
+    /// An array of values of the same type, usable with `IN UNNEST(@name)`.
+    Array(Vec<ParameterValue>),
+}
+
+/// Identifies a query job, used to fetch further pages of its results.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JobReference {
+    pub project_id: String,
+    pub job_id: String,
+    pub location: Option<String>,
+}
+
+/// The columns of a result set.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TableSchema {
+    pub fields: Vec<TableField>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TableField {
+    pub name: String,
+    /// The column type, e.g. `STRING`, `INTEGER`, `TIMESTAMP` or `RECORD`.
+    #[serde(rename = "type")]
+    pub field_type: String,
+    /// `NULLABLE`, `REQUIRED` or `REPEATED`.
+    pub mode: Option<String>,
+    /// The nested columns of a `RECORD`.
+    pub fields: Vec<TableField>,
+}
+
+/// A row in BigQuery's wire format, one cell per schema field.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TableRow {
+    pub f: Vec<TableCell>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TableCell {
+    pub v: Value,
+}
+
+/// A page of query results, returned by `jobs.query` and `jobs.getQueryResults`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueryResults {
+    pub job_reference: JobReference,
+    /// `false` while the query is still running, in which case there are no rows yet.
+    pub job_complete: bool,
+    pub schema: Option<TableSchema>,
+    pub rows: Vec<TableRow>,
+    pub page_token: Option<String>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub total_rows: Option<u64>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub total_bytes_processed: Option<i64>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub num_dml_affected_rows: Option<i64>,
+    pub cache_hit: Option<bool>,
+}
+
+/// Where a paginated query currently stands.
+enum QueryState {
+    Start,
+    Next(JobReference, Option<String>, Option<TableSchema>),
+    Done,
+}
+
+impl SqlQuery {
+    /// Creates a query from its GoogleSQL text.
+    pub fn new(query: impl Into<String>) -> SqlQuery {
+        SqlQuery {
+            query: query.into(),
+            parameters: Vec::new(),
+            default_dataset: None,
+            location: None,
+            max_results: None,
+            dry_run: false,
+        }
+    }
+
+    /// Binds a named parameter, referenced as `@name` in the query.
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<ParameterValue>) -> SqlQuery {
+        self.parameters.push((name.into(), value.into()));
+        self
+    }
+
+    /// Resolves unqualified table names against a dataset, given as `project.dataset`
+    /// or `dataset`.
+    pub fn default_dataset(mut self, dataset: impl Into<String>) -> SqlQuery {
+        self.default_dataset = Some(dataset.into());
+        self
+    }
+
+    /// Runs the query in a location other than the default, e.g. `EU`.
+    pub fn location(mut self, location: impl Into<String>) -> SqlQuery {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Limits the number of rows per page.
+    pub fn max_results(mut self, max_results: u32) -> SqlQuery {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Validates the query and estimates `total_bytes_processed` without running it.
+    pub fn dry_run(mut self, dry_run: bool) -> SqlQuery {
+        self.dry_run = dry_run;
+        self
+    }
+
+    fn body(&self, project_id: &str) -> Value {
+        let parameters: Vec<Value> = self
+            .parameters
+            .iter()
+            .map(|(name, value)| {
+                json!({
+                    "name": name,
+                    "parameterType": value.parameter_type(),
+                    "parameterValue": value.parameter_value(),
+                })
+            })
+            .collect();
+
+        let mut body = json!({
+            "query": self.query,
+            "useLegacySql": false,
+            "parameterMode": "NAMED",
+            "queryParameters": parameters,
+            "timeoutMs": WAIT_TIMEOUT_MS,
+            "dryRun": self.dry_run,
+            "formatOptions": { "useInt64Timestamp": true },
+        });
+        if let Some(dataset) = &self.default_dataset {
+            let (dataset_project, dataset_id) =
+                dataset.split_once('.').unwrap_or((project_id, dataset));
+            body["defaultDataset"] = json!({
+                "projectId": dataset_project,
+                "datasetId": dataset_id,
+            });
+        }
+        if let Some(location) = &self.location {
+            body["location"] = json!(location);
+        }
+        if let Some(max_results) = self.max_results {
+            body["maxResults"] = json!(max_results);
+        }
+        body
+    }
+}
+
+impl ParameterValue {
+    fn type_name(&self) -> &'static str {
+        match self {
+            ParameterValue::String(_) => "STRING",
+            ParameterValue::Int64(_) => "INT64",
+            ParameterValue::Float64(_) => "FLOAT64",
+            ParameterValue::Bool(_) => "BOOL",
+            ParameterValue::Date(_) => "DATE",
+            ParameterValue::Timestamp(_) => "TIMESTAMP",
+            ParameterValue::Array(_) => "ARRAY",
+        }
+    }
+
+    fn parameter_type(&self) -> Value {
+        match self {
+            ParameterValue::Array(values) => json!({
+                "type": "ARRAY",
+                "arrayType": values
+                    .first()
+                    .map(ParameterValue::parameter_type)
+                    .unwrap_or_else(|| json!({ "type": "STRING" })),
+            }),
+            value => json!({ "type": value.type_name() }),
+        }
+    }
+
+    fn parameter_value(&self) -> Value {
+        match self {
+            ParameterValue::String(value) => json!({ "value": value }),
+            ParameterValue::Int64(value) => json!({ "value": value.to_string() }),
+            ParameterValue::Float64(value) => json!({ "value": value.to_string() }),
+            ParameterValue::Bool(value) => json!({ "value": value.to_string() }),
+            ParameterValue::Date(value) => json!({ "value": value.to_string() }),
+            ParameterValue::Timestamp(value) => json!({ "value": value.to_rfc3339() }),
+            ParameterValue::Array(values) => json!({
+                "arrayValues": values
+                    .iter()
+                    .map(ParameterValue::parameter_value)
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+impl From<&str> for ParameterValue {
+    fn from(value: &str) -> ParameterValue {
+        ParameterValue::String(value.to_string())
+    }
+}
+
+impl From<String> for ParameterValue {
+    fn from(value: String) -> ParameterValue {
+        ParameterValue::String(value)
+    }
+}
+
+impl From<i64> for ParameterValue {
+    fn from(value: i64) -> ParameterValue {
+        ParameterValue::Int64(value)
+    }
+}
+
+impl From<f64> for ParameterValue {
+    fn from(value: f64) -> ParameterValue {
+        ParameterValue::Float64(value)
+    }
+}
+
+impl From<bool> for ParameterValue {
+    fn from(value: bool) -> ParameterValue {
+        ParameterValue::Bool(value)
+    }
+}
+
+impl From<NaiveDate> for ParameterValue {
+    fn from(value: NaiveDate) -> ParameterValue {
+        ParameterValue::Date(value)
+    }
+}
+
+impl From<DateTime<Utc>> for ParameterValue {
+    fn from(value: DateTime<Utc>) -> ParameterValue {
+        ParameterValue::Timestamp(value)
+    }
+}
+
+impl<T: Into<ParameterValue>> From<Vec<T>> for ParameterValue {
+    fn from(values: Vec<T>) -> ParameterValue {
+        ParameterValue::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl QueryResults {
+    /// Converts the rows of this page into `T`, e.g. a `#[derive(Deserialize)]` struct
+    /// whose fields are named after the columns, or `serde_json::Value` for dynamic access.
+    ///
+    /// Rows are first turned into JSON objects keyed by column name: integers, floats and
+    /// booleans become JSON numbers and booleans, timestamps become RFC 3339 strings,
+    /// records become nested objects and repeated columns arrays. Other types, such as
+    /// `NUMERIC` or `DATE`, keep BigQuery's string representation.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a row cannot be deserialized into `T`.
+    pub fn rows<T: DeserializeOwned>(&self) -> Result<Vec<T>, Box<dyn Error>> {
+        let Some(schema) = &self.schema else {
+            return Ok(Vec::new());
+        };
+
+        self.rows
+            .iter()
+            .map(|row| Ok(serde_json::from_value(record(&schema.fields, &row.f))?))
+            .collect()
+    }
+}
+
+impl Google {
+    /// Returns a BigQuery API client that authorizes its requests with `token`.
+    pub fn bigquery<'a>(&'a self, token: &'a Token) -> BigQuery<'a> {
+        BigQuery {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> BigQuery<'a> {
+    /// Runs a query and returns the first page of its results.
+    ///
+    /// Queries still running after about ten seconds return with `job_complete` unset; the
+    /// results can then be awaited with `get_query_results`. `query_pages` and `query_rows`
+    /// take care of this.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The project the query job is billed to.
+    /// * `query` - The query to run.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, including a `GoogleError` for
+    /// invalid SQL.
+    pub async fn query(
+        &self,
+        project_id: &str,
+        query: &SqlQuery,
+    ) -> Result<QueryResults, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{BIGQUERY_API}/projects/{}/queries",
+                encode_path(project_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&query.body(project_id));
+
+        self.google.send_json(request).await
+    }
+
+    /// Fetches a page of the results of a query job, waiting for the job to complete for
+    /// about ten seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The `job_reference` of a previous `query` response.
+    /// * `page_token` - The `page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails or the job failed.
+    pub async fn get_query_results(
+        &self,
+        job: &JobReference,
+        page_token: Option<&str>,
+    ) -> Result<QueryResults, Box<dyn Error>> {
+        let mut params = vec![
+            ("timeoutMs", WAIT_TIMEOUT_MS.to_string()),
+            ("formatOptions.useInt64Timestamp", "true".to_string()),
+        ];
+        if let Some(location) = &job.location {
+            params.push(("location", location.clone()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!(
+                "{BIGQUERY_API}/projects/{}/queries/{}",
+                encode_path(&job.project_id),
+                encode_path(&job.job_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Runs a query and streams every page of its results, waiting for the query to
+    /// complete first.
+    pub fn query_pages<'s>(
+        self,
+        project_id: &'s str,
+        query: &'s SqlQuery,
+    ) -> impl Stream<Item = Result<QueryResults, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        stream::try_unfold(QueryState::Start, move |mut state| async move {
+            loop {
+                let results = match state {
+                    QueryState::Start => self.query(project_id, query).await?,
+                    QueryState::Next(job, page_token, schema) => {
+                        let mut results =
+                            self.get_query_results(&job, page_token.as_deref()).await?;
+                        if results.schema.is_none() {
+                            results.schema = schema;
+                        }
+                        results
+                    }
+                    QueryState::Done => return Ok(None),
+                };
+
+                if !results.job_complete {
+                    state = QueryState::Next(results.job_reference, None, None);
+                    continue;
+                }
+
+                let next = match &results.page_token {
+                    Some(page_token) => QueryState::Next(
+                        results.job_reference.clone(),
+                        Some(page_token.clone()),
+                        results.schema.clone(),
+                    ),
+                    None => QueryState::Done,
+                };
+
+                return Ok(Some((results, next)));
+            }
+        })
+    }
+
+    /// Runs a query and streams its rows converted into `T`, as described for
+    /// `QueryResults::rows`, fetching further pages as needed.
+    pub fn query_rows<'s, T: DeserializeOwned + 's>(
+        self,
+        project_id: &'s str,
+        query: &'s SqlQuery,
+    ) -> impl Stream<Item = Result<T, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        self.query_pages(project_id, query)
+            .and_then(|page| async move { page.rows::<T>() })
+            .map_ok(|rows| stream::iter(rows.into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+/// Converts the cells of a row or record into a JSON object keyed by field name.
+fn record(fields: &[TableField], cells: &[TableCell]) -> Value {
+    let object: Map<String, Value> = fields
+        .iter()
+        .zip(cells)
+        .map(|(field, cell)| (field.name.clone(), cell_value(field, &cell.v)))
+        .collect();
+
+    Value::Object(object)
+}
+
+/// Converts a cell from BigQuery's wire format, where every scalar is a string.
+fn cell_value(field: &TableField, value: &Value) -> Value {
+    if field.mode.as_deref() == Some("REPEATED") {
+        let Value::Array(items) = value else {
+            return Value::Array(Vec::new());
+        };
+        return Value::Array(
+            items
+                .iter()
+                .map(|item| scalar_value(field, item.get("v").unwrap_or(&Value::Null)))
+                .collect(),
+        );
+    }
+
+    scalar_value(field, value)
+}
+
+fn scalar_value(field: &TableField, value: &Value) -> Value {
+    if let Some(cells) = value.get("f").and_then(Value::as_array) {
+        let cells: Vec<TableCell> = cells
+            .iter()
+            .map(|cell| TableCell {
+                v: cell.get("v").cloned().unwrap_or(Value::Null),
+            })
+            .collect();
+        return record(&field.fields, &cells);
+    }
+
+    let Value::String(text) = value else {
+        return value.clone();
+    };
+    let converted = match field.field_type.as_str() {
+        "INTEGER" | "INT64" => text.parse::<i64>().ok().map(Value::from),
+        "FLOAT" | "FLOAT64" => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        "BOOLEAN" | "BOOL" => text.parse::<bool>().ok().map(Value::Bool),
+        "TIMESTAMP" => text
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .map(|timestamp| Value::String(timestamp.to_rfc3339())),
+        _ => None,
+    };
+
+    converted.unwrap_or_else(|| value.clone())
+}
@@ -0,0 +1,328 @@
+//! Google Business Profile APIs, for managing business locations and their reviews.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::{Page, paginate};
+use crate::{Google, Token};
+
+/// Scope granting access to the Business Profiles the user manages.
+pub const SCOPE_BUSINESS_MANAGE: &str = "https://www.googleapis.com/auth/business.manage";
+
+const ACCOUNT_MANAGEMENT_API: &str = "https://mybusinessaccountmanagement.googleapis.com/v1";
+const BUSINESS_INFORMATION_API: &str = "https://mybusinessbusinessinformation.googleapis.com/v1";
+const MY_BUSINESS_API: &str = "https://mybusiness.googleapis.com/v4";
+
+/// A client for the Business Profile APIs.
+#[derive(Clone, Copy)]
+pub struct BusinessProfile<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// An account owning business locations, either the user's personal account or a
+/// location group.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Account {
+    /// The resource name, `accounts/{account_id}`.
+    pub name: String,
+    pub account_name: String,
+    /// E.g. `PERSONAL` or `LOCATION_GROUP`.
+    #[serde(rename = "type")]
+    pub account_type: String,
+}
+
+/// A page of accounts returned by `accounts.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccountsPage {
+    pub accounts: Vec<Account>,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for AccountsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// A business location. Only the fields listed in the read mask are populated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Location {
+    /// The resource name, `locations/{location_id}`.
+    pub name: String,
+    pub title: String,
+    pub store_code: Option<String>,
+    pub website_uri: Option<String>,
+    /// The postal address, kept as raw JSON.
+    pub storefront_address: Option<Value>,
+    /// The phone numbers, kept as raw JSON.
+    pub phone_numbers: Option<Value>,
+    /// Information such as the Maps URI and place id, kept as raw JSON.
+    pub metadata: Option<Value>,
+}
+
+/// A page of locations returned by `accounts.locations.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocationsPage {
+    pub locations: Vec<Location>,
+    pub next_page_token: Option<String>,
+    pub total_size: Option<u32>,
+}
+
+impl Page for LocationsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// A customer review of a location.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Review {
+    /// The resource name, `accounts/{account_id}/locations/{location_id}/reviews/{id}`.
+    pub name: String,
+    pub review_id: String,
+    pub reviewer: Reviewer,
+    pub star_rating: StarRating,
+    /// The review text, absent for ratings without text.
+    pub comment: Option<String>,
+    pub create_time: Option<DateTime<Utc>>,
+    pub update_time: Option<DateTime<Utc>>,
+    /// The owner's reply, if any.
+    pub review_reply: Option<ReviewReply>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Reviewer {
+    pub display_name: Option<String>,
+    pub profile_photo_url: Option<String>,
+    pub is_anonymous: bool,
+}
+
+/// The star rating of a review.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StarRating {
+    #[default]
+    StarRatingUnspecified,
+    One,
+    Two,
+    Three,
+    Four,
+    Five,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReviewReply {
+    pub comment: String,
+    pub update_time: Option<DateTime<Utc>>,
+}
+
+/// A page of reviews returned by `accounts.locations.reviews.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReviewsPage {
+    pub reviews: Vec<Review>,
+    pub average_rating: f64,
+    pub total_review_count: u32,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for ReviewsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl StarRating {
+    /// The number of stars, `None` when unspecified.
+    pub fn stars(self) -> Option<u8> {
+        match self {
+            StarRating::StarRatingUnspecified => None,
+            StarRating::One => Some(1),
+            StarRating::Two => Some(2),
+            StarRating::Three => Some(3),
+            StarRating::Four => Some(4),
+            StarRating::Five => Some(5),
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Business Profile API client that authorizes its requests with `token`.
+    pub fn business_profile<'a>(&'a self, token: &'a Token) -> BusinessProfile<'a> {
+        BusinessProfile {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> BusinessProfile<'a> {
+    /// Lists a single page of the accounts the user has access to.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_accounts(
+        &self,
+        page_token: Option<&str>,
+    ) -> Result<AccountsPage, Box<dyn Error>> {
+        let mut request = self
+            .google
+            .http
+            .get(format!("{ACCOUNT_MANAGEMENT_API}/accounts"))
+            .bearer_auth(self.token.access_token());
+        if let Some(page_token) = page_token {
+            request = request.query(&[("pageToken", page_token)]);
+        }
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the accounts the user has access to.
+    pub fn accounts(self) -> impl Stream<Item = Result<AccountsPage, Box<dyn Error>>> + 'a {
+        paginate(move |page_token| async move { self.list_accounts(page_token.as_deref()).await })
+    }
+
+    /// Lists a single page of the locations of an account.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The resource name of the account, `accounts/{account_id}`.
+    /// * `read_mask` - The location fields to return, e.g. `["name", "title",
+    ///   "storefrontAddress"]`.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_locations(
+        &self,
+        account: &str,
+        read_mask: &[&str],
+        page_token: Option<&str>,
+    ) -> Result<LocationsPage, Box<dyn Error>> {
+        let mut params = vec![("readMask", read_mask.join(","))];
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{BUSINESS_INFORMATION_API}/{account}/locations"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the locations of an account.
+    pub fn locations<'s>(
+        self,
+        account: &'s str,
+        read_mask: &'s [&'s str],
+    ) -> impl Stream<Item = Result<LocationsPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_locations(account, read_mask, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Lists a single page of the reviews of a location, the newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The resource name of the location including its account,
+    ///   `accounts/{account_id}/locations/{location_id}`.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_reviews(
+        &self,
+        location: &str,
+        page_token: Option<&str>,
+    ) -> Result<ReviewsPage, Box<dyn Error>> {
+        let mut request = self
+            .google
+            .http
+            .get(format!("{MY_BUSINESS_API}/{location}/reviews"))
+            .bearer_auth(self.token.access_token());
+        if let Some(page_token) = page_token {
+            request = request.query(&[("pageToken", page_token)]);
+        }
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the reviews of a location.
+    pub fn reviews<'s>(
+        self,
+        location: &'s str,
+    ) -> impl Stream<Item = Result<ReviewsPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_reviews(location, page_token.as_deref()).await
+        })
+    }
+
+    /// Replies to a review, replacing any previous reply.
+    ///
+    /// # Arguments
+    ///
+    /// * `review` - The resource name of the review, `Review::name`.
+    /// * `comment` - The text of the reply.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn reply_to_review(
+        &self,
+        review: &str,
+        comment: &str,
+    ) -> Result<ReviewReply, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .put(format!("{MY_BUSINESS_API}/{review}/reply"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "comment": comment }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Deletes the reply to a review.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn delete_review_reply(&self, review: &str) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .delete(format!("{MY_BUSINESS_API}/{review}/reply"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+}
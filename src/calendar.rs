@@ -0,0 +1,472 @@
+//! Google Calendar API.
+
+use std::error::Error;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{Page, encode_path, paginate};
+use crate::channel::{Channel, NewChannel};
+use crate::{Google, Token};
+
+const CALENDAR_API: &str = "https://www.googleapis.com/calendar/v3";
+
+/// Scope granting read-only access to events.
+pub const SCOPE_EVENTS_READONLY: &str = "https://www.googleapis.com/auth/calendar.events.readonly";
+
+/// Scope granting read and write access to events.
+pub const SCOPE_EVENTS: &str = "https://www.googleapis.com/auth/calendar.events";
+
+/// A client for the Calendar API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Calendar<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A calendar event.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Event {
+    pub id: String,
+    /// `confirmed`, `tentative` or `cancelled`. Deleted events show up as `cancelled` in
+    /// incremental syncs.
+    pub status: String,
+    pub html_link: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub start: EventDateTime,
+    pub end: EventDateTime,
+    /// RRULE, EXRULE, RDATE and EXDATE lines of a recurring event.
+    pub recurrence: Vec<String>,
+    /// For an instance of a recurring event, the id of the recurring event.
+    pub recurring_event_id: Option<String>,
+    pub organizer: Option<EventPerson>,
+    pub creator: Option<EventPerson>,
+    pub attendees: Vec<Attendee>,
+    pub reminders: Option<Reminders>,
+    pub conference_data: Option<ConferenceData>,
+    /// The Meet link of the event, also available in `conference_data`.
+    pub hangout_link: Option<String>,
+    pub created: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// An event to be created with `Calendar::insert_event`, or the fields to change with
+/// `Calendar::patch_event`. Unset fields are left out of the request.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NewEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<EventDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<EventDateTime>,
+    /// Recurrence rules, e.g. `RRULE:FREQ=WEEKLY;COUNT=10`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recurrence: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<Attendee>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<Reminders>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conference_data: Option<ConferenceData>,
+}
+
+/// The reminders of an event.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Reminders {
+    /// Use the calendar's default reminders instead of `overrides`.
+    pub use_default: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<Reminder>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Reminder {
+    /// `email` or `popup`.
+    pub method: String,
+    /// Minutes before the start of the event.
+    pub minutes: u32,
+}
+
+/// The conference, such as a Google Meet call, attached to an event.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConferenceData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_request: Option<CreateConferenceRequest>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub entry_points: Vec<EntryPoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conference_id: Option<String>,
+}
+
+/// A request to generate a new conference for an event.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CreateConferenceRequest {
+    /// A unique id for the request; resending the same id does not create another
+    /// conference.
+    pub request_id: String,
+    pub conference_solution_key: ConferenceSolutionKey,
+    /// Set by Google; `pending` while the conference is being created, then `success` or
+    /// `failure`.
+    #[serde(skip_serializing)]
+    pub status: Option<ConferenceRequestStatus>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConferenceSolutionKey {
+    /// `hangoutsMeet` for Google Meet.
+    #[serde(rename = "type")]
+    pub solution_type: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConferenceRequestStatus {
+    pub status_code: String,
+}
+
+/// A way of joining a conference.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntryPoint {
+    /// `video`, `phone`, `sip` or `more`.
+    pub entry_point_type: String,
+    pub uri: String,
+    pub label: Option<String>,
+    pub pin: Option<String>,
+}
+
+impl Event {
+    /// Returns the URL to join the event's video conference, e.g. its Google Meet link.
+    pub fn join_url(&self) -> Option<&str> {
+        self.conference_data
+            .iter()
+            .flat_map(|data| &data.entry_points)
+            .find(|entry| entry.entry_point_type == "video")
+            .map(|entry| entry.uri.as_str())
+            .or(self.hangout_link.as_deref())
+    }
+}
+
+impl NewEvent {
+    /// Requests a Google Meet conference to be created with the event.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - A unique id for the conference request, e.g. a UUID.
+    pub fn with_meet(mut self, request_id: impl Into<String>) -> NewEvent {
+        self.conference_data = Some(ConferenceData {
+            create_request: Some(CreateConferenceRequest {
+                request_id: request_id.into(),
+                conference_solution_key: ConferenceSolutionKey {
+                    solution_type: "hangoutsMeet".to_string(),
+                },
+                status: None,
+            }),
+            ..ConferenceData::default()
+        });
+        self
+    }
+}
+
+/// The start or end of an event. All-day events only carry a `date`, timed events a
+/// `date_time`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventDateTime {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_time: Option<DateTime<FixedOffset>>,
+    /// An IANA time zone name, required for recurring events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+}
+
+/// The organizer or creator of an event.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventPerson {
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    #[serde(rename = "self")]
+    pub is_self: bool,
+}
+
+/// An attendee of an event.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Attendee {
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// `needsAction`, `declined`, `tentative` or `accepted`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub organizer: bool,
+    #[serde(rename = "self", skip_serializing_if = "std::ops::Not::not")]
+    pub is_self: bool,
+}
+
+/// Options for listing events.
+#[derive(Debug, Clone, Default)]
+pub struct EventQuery {
+    /// Only events ending after this time. Not supported with a sync token.
+    pub time_min: Option<DateTime<Utc>>,
+    /// Only events starting before this time. Not supported with a sync token.
+    pub time_max: Option<DateTime<Utc>>,
+    /// Expand recurring events into their single instances.
+    pub single_events: bool,
+    /// `startTime` (requires `single_events`) or `updated`.
+    pub order_by: Option<String>,
+    /// Free text search over summary, description, location and attendees.
+    pub q: Option<String>,
+    /// The number of events per page, at most 2500.
+    pub max_results: Option<u32>,
+    /// Include cancelled events. Always the case for incremental syncs.
+    pub show_deleted: bool,
+    /// A sync token from a previous listing. Only events changed since then are returned.
+    pub sync_token: Option<String>,
+}
+
+/// A page of events returned by `events.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventList {
+    pub items: Vec<Event>,
+    pub next_page_token: Option<String>,
+    /// Present on the last page; pass it as `EventQuery::sync_token` on the next sync.
+    pub next_sync_token: Option<String>,
+    pub summary: Option<String>,
+    pub time_zone: Option<String>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+impl Page for EventList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Google {
+    /// Returns a Calendar API client that authorizes its requests with `token`.
+    pub fn calendar<'a>(&'a self, token: &'a Token) -> Calendar<'a> {
+        Calendar {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Calendar<'a> {
+    /// Lists a single page of events.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar_id` - The calendar to list, `primary` for the user's main calendar.
+    /// * `query` - Time range, expansion and sync options.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. A `GoogleError` with a `410`
+    /// status means the sync token expired and a full sync is required.
+    pub async fn list_events(
+        &self,
+        calendar_id: &str,
+        query: &EventQuery,
+        page_token: Option<&str>,
+    ) -> Result<EventList, Box<dyn Error>> {
+        let mut params = Vec::new();
+        if let Some(time_min) = query.time_min {
+            params.push((
+                "timeMin",
+                time_min.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ));
+        }
+        if let Some(time_max) = query.time_max {
+            params.push((
+                "timeMax",
+                time_max.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ));
+        }
+        if query.single_events {
+            params.push(("singleEvents", "true".to_string()));
+        }
+        if let Some(order_by) = &query.order_by {
+            params.push(("orderBy", order_by.clone()));
+        }
+        if let Some(q) = &query.q {
+            params.push(("q", q.clone()));
+        }
+        if let Some(max_results) = query.max_results {
+            params.push(("maxResults", max_results.to_string()));
+        }
+        if query.show_deleted {
+            params.push(("showDeleted", "true".to_string()));
+        }
+        if let Some(sync_token) = &query.sync_token {
+            params.push(("syncToken", sync_token.clone()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let calendar_id = encode_path(calendar_id);
+        let request = self
+            .google
+            .http
+            .get(format!("{CALENDAR_API}/calendars/{calendar_id}/events"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of events matching `query`. The last page carries the
+    /// `next_sync_token` for the following incremental sync.
+    pub fn events<'s>(
+        self,
+        calendar_id: &'s str,
+        query: &'s EventQuery,
+    ) -> impl Stream<Item = Result<EventList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_events(calendar_id, query, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Creates an event.
+    ///
+    /// Conference data is always sent, so an event built with `NewEvent::with_meet` gets a
+    /// Google Meet link whose URL is available through `Event::join_url`. Meet creation
+    /// can be asynchronous; while `create_request.status` is `pending`, fetch the event
+    /// again later to obtain the link.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar_id` - The calendar to create the event in, `primary` for the user's
+    ///   main calendar.
+    /// * `event` - The event to create.
+    /// * `send_updates` - Who to email invitations to: `all`, `externalOnly` or `none`.
+    pub async fn insert_event(
+        &self,
+        calendar_id: &str,
+        event: &NewEvent,
+        send_updates: Option<&str>,
+    ) -> Result<Event, Box<dyn Error>> {
+        let calendar_id = encode_path(calendar_id);
+        let request = self
+            .google
+            .http
+            .post(format!("{CALENDAR_API}/calendars/{calendar_id}/events"))
+            .bearer_auth(self.token.access_token())
+            .query(&write_params(send_updates))
+            .json(event);
+
+        self.google.send_json(request).await
+    }
+
+    /// Updates the fields of an event that are set in `event`, leaving all others as they
+    /// are.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar_id` - The calendar containing the event.
+    /// * `event_id` - The id of the event to update.
+    /// * `event` - The fields to change.
+    /// * `send_updates` - Who to email the changes to: `all`, `externalOnly` or `none`.
+    pub async fn patch_event(
+        &self,
+        calendar_id: &str,
+        event_id: &str,
+        event: &NewEvent,
+        send_updates: Option<&str>,
+    ) -> Result<Event, Box<dyn Error>> {
+        let calendar_id = encode_path(calendar_id);
+        let event_id = encode_path(event_id);
+        let request = self
+            .google
+            .http
+            .patch(format!(
+                "{CALENDAR_API}/calendars/{calendar_id}/events/{event_id}"
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&write_params(send_updates))
+            .json(event);
+
+        self.google.send_json(request).await
+    }
+
+    /// Creates a notification channel receiving a webhook call whenever an event on the
+    /// calendar changes.
+    ///
+    /// Notifications carry no event data: verify incoming calls with
+    /// `ChannelNotification::from_verified_headers`, then run an incremental `list_events`
+    /// with the stored sync token. Channels expire after at most a month and have to be
+    /// replaced before then.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar_id` - The calendar to watch, `primary` for the user's main calendar.
+    /// * `channel` - The channel to create, see `NewChannel::web_hook`.
+    pub async fn watch_events(
+        &self,
+        calendar_id: &str,
+        channel: &NewChannel,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let calendar_id = encode_path(calendar_id);
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{CALENDAR_API}/calendars/{calendar_id}/events/watch"
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(channel);
+
+        self.google.send_json(request).await
+    }
+
+    /// Stops a notification channel.
+    pub async fn stop_channel(&self, channel: &Channel) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{CALENDAR_API}/channels/stop"))
+            .bearer_auth(self.token.access_token())
+            .json(&serde_json::json!({ "id": channel.id, "resourceId": channel.resource_id }));
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+}
+
+fn write_params(send_updates: Option<&str>) -> Vec<(&'static str, &str)> {
+    let mut params = vec![("conferenceDataVersion", "1")];
+    if let Some(send_updates) = send_updates {
+        params.push(("sendUpdates", send_updates));
+    }
+    params
+}
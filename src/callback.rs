@@ -0,0 +1,248 @@
+use std::error::Error;
+use std::fmt;
+
+use oauth2::url::form_urlencoded;
+use serde::Deserialize;
+
+use crate::REDACTED;
+use crate::api::constant_time_eq;
+
+/// The parameters Google sends to the redirect URI once the user authorized, or refused to
+/// authorize, the application.
+///
+/// It can be parsed with `AuthCallback::from_query` or `AuthCallback::from_form`, or
+/// deserialized by a web framework's query or form extractor. Either way, `verify` checks
+/// the state and returns the authorization code.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AuthCallback {
+    /// The authorization code to exchange with `Google::get_token`.
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// The space-separated scopes the user granted.
+    pub scope: Option<String>,
+    /// The error code when authorization failed, e.g. `access_denied`. See `auth_error`.
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+    pub error_uri: Option<String>,
+}
+
+/// The reason authorization failed, from the `error` parameter of the callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthErrorKind {
+    /// The user declined to authorize the application, e.g. by cancelling the sign-in.
+    AccessDenied,
+    /// `prompt=none` was requested but the user has to interact with Google.
+    InteractionRequired,
+    /// `prompt=none` was requested but the user is not signed in.
+    LoginRequired,
+    /// `prompt=none` was requested but the user has not consented yet.
+    ConsentRequired,
+    /// `prompt=none` was requested but the user has to pick one of several accounts.
+    AccountSelectionRequired,
+    /// A requested scope is unknown or not allowed for the application.
+    InvalidScope,
+    InvalidRequest,
+    UnauthorizedClient,
+    UnsupportedResponseType,
+    /// The Workspace administrator blocked the application or a requested scope.
+    AdminPolicyEnforced,
+    ServerError,
+    TemporarilyUnavailable,
+    /// Another error code, kept in `AuthError::code`.
+    Unknown,
+}
+
+/// An authorization failure reported to the redirect URI, returned boxed by
+/// `AuthCallback::verify` and `AuthCallback::into_code`.
+///
+/// It can be inspected by downcasting with `err.downcast_ref::<AuthError>()`, e.g. to tell
+/// the user they cancelled the sign-in rather than show a generic failure.
+#[derive(Debug, Clone)]
+pub struct AuthError {
+    pub kind: AuthErrorKind,
+    /// The `error` parameter as sent by Google.
+    pub code: String,
+    pub description: Option<String>,
+    /// A page explaining the error.
+    pub uri: Option<String>,
+}
+
+impl AuthCallback {
+    /// Parses the query string of a callback delivered with `ResponseMode::Query`, with or
+    /// without the leading `?`.
+    pub fn from_query(query: &str) -> AuthCallback {
+        AuthCallback::parse(query.strip_prefix('?').unwrap_or(query).as_bytes())
+    }
+
+    /// Parses the form-encoded body of a callback delivered with
+    /// `ResponseMode::FormPost`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the body is not valid UTF-8 form data.
+    pub fn from_form(body: &[u8]) -> Result<AuthCallback, Box<dyn Error>> {
+        std::str::from_utf8(body)?;
+
+        Ok(AuthCallback::parse(body))
+    }
+
+    /// Checks that the callback carries the state the authorization URL was generated with,
+    /// see `Google::get_redirect_url_with_state`, and returns the authorization code.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if either state is missing or empty or they do not
+    /// match, and otherwise as described for `into_code`.
+    pub fn verify(self, expected_state: &str) -> Result<String, Box<dyn Error>> {
+        if expected_state.is_empty() {
+            return Err("Expected state is empty".into());
+        }
+        let state = self
+            .state
+            .as_deref()
+            .filter(|state| !state.is_empty())
+            .ok_or("Callback has no state")?;
+        if !constant_time_eq(state.as_bytes(), expected_state.as_bytes()) {
+            return Err("Callback state does not match".into());
+        }
+
+        self.into_code()
+    }
+
+    /// Returns the authorization code without checking the state.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AuthError` if authorization failed, and another error if
+    /// the callback has no code.
+    pub fn into_code(self) -> Result<String, Box<dyn Error>> {
+        if let Some(error) = self.auth_error() {
+            return Err(error.into());
+        }
+
+        self.code
+            .ok_or_else(|| "Callback has no authorization code".into())
+    }
+
+    /// Returns the authorization failure reported by the callback, if any.
+    pub fn auth_error(&self) -> Option<AuthError> {
+        let code = self.error.clone()?;
+
+        Some(AuthError {
+            kind: AuthErrorKind::from(code.as_str()),
+            code,
+            description: self.error_description.clone(),
+            uri: self.error_uri.clone(),
+        })
+    }
+
+    fn parse(input: &[u8]) -> AuthCallback {
+        let mut callback = AuthCallback::default();
+        for (name, value) in form_urlencoded::parse(input) {
+            let field = match name.as_ref() {
+                "code" => &mut callback.code,
+                "state" => &mut callback.state,
+                "scope" => &mut callback.scope,
+                "error" => &mut callback.error,
+                "error_description" => &mut callback.error_description,
+                "error_uri" => &mut callback.error_uri,
+                _ => continue,
+            };
+            *field = Some(value.into_owned());
+        }
+
+        callback
+    }
+}
+
+impl fmt::Debug for AuthCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthCallback")
+            .field("code", &self.code.as_ref().map(|_| REDACTED))
+            .field("state", &self.state)
+            .field("scope", &self.scope)
+            .field("error", &self.error)
+            .field("error_description", &self.error_description)
+            .field("error_uri", &self.error_uri)
+            .finish()
+    }
+}
+
+impl AuthError {
+    /// Returns `true` if the user declined to authorize the application.
+    pub fn is_cancelled(&self) -> bool {
+        self.kind == AuthErrorKind::AccessDenied
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Authorization failed ({})", self.code)?;
+        if let Some(description) = &self.description {
+            write!(f, ": {description}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AuthError {}
+
+impl From<&str> for AuthErrorKind {
+    fn from(code: &str) -> AuthErrorKind {
+        match code {
+            "access_denied" => AuthErrorKind::AccessDenied,
+            "interaction_required" => AuthErrorKind::InteractionRequired,
+            "login_required" => AuthErrorKind::LoginRequired,
+            "consent_required" => AuthErrorKind::ConsentRequired,
+            "account_selection_required" => AuthErrorKind::AccountSelectionRequired,
+            "invalid_scope" => AuthErrorKind::InvalidScope,
+            "invalid_request" => AuthErrorKind::InvalidRequest,
+            "unauthorized_client" => AuthErrorKind::UnauthorizedClient,
+            "unsupported_response_type" => AuthErrorKind::UnsupportedResponseType,
+            "admin_policy_enforced" => AuthErrorKind::AdminPolicyEnforced,
+            "server_error" => AuthErrorKind::ServerError,
+            "temporarily_unavailable" => AuthErrorKind::TemporarilyUnavailable,
+            _ => AuthErrorKind::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_the_code_for_a_matching_state() {
+        let callback = AuthCallback::from_query("?state=s3cr3t&code=4%2F0abc");
+
+        assert_eq!(callback.verify("s3cr3t").unwrap(), "4/0abc");
+    }
+
+    #[test]
+    fn rejects_another_state() {
+        let callback = AuthCallback::from_query("state=other&code=abc");
+
+        assert!(callback.verify("s3cr3t").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_states() {
+        assert!(AuthCallback::from_query("code=abc").verify("").is_err());
+        assert!(
+            AuthCallback::from_query("state=&code=abc")
+                .verify("")
+                .is_err()
+        );
+        assert!(
+            AuthCallback::from_query("code=abc")
+                .verify("s3cr3t")
+                .is_err()
+        );
+        assert!(
+            AuthCallback::from_query("state=s3cr3t&code=abc")
+                .verify("")
+                .is_err()
+        );
+    }
+}
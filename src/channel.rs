@@ -0,0 +1,188 @@
+//! Push notification channels, shared by the Drive and Calendar watch APIs.
+//!
+//! Gmail delivers its watch notifications through Pub/Sub instead; see
+//! `Google::verify_push`.
+
+use std::error::Error;
+
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+use crate::api::constant_time_eq;
+use crate::de::option_number_from_string;
+
+/// A notification channel to be created by a `watch` call.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NewChannel {
+    /// A unique id for the channel, e.g. a UUID, echoed in `X-Goog-Channel-ID`.
+    pub id: String,
+    /// Always `web_hook`.
+    #[serde(rename = "type")]
+    pub channel_type: String,
+    /// The HTTPS URL notifications are delivered to.
+    pub address: String,
+    /// An arbitrary secret echoed in `X-Goog-Channel-Token`, used to verify notifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// The requested expiration in milliseconds since the epoch. Google caps the lifetime
+    /// depending on the API, so check `Channel::expiration` of the created channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<i64>,
+}
+
+/// A notification channel as created by Google.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Channel {
+    pub id: String,
+    /// The opaque id of the watched resource, required to stop the channel.
+    pub resource_id: String,
+    pub resource_uri: Option<String>,
+    pub token: Option<String>,
+    /// The expiration in milliseconds since the epoch.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub expiration: Option<i64>,
+}
+
+/// The state of the watched resource reported by a notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceState {
+    /// Sent once when the channel is created, before any change.
+    Sync,
+    /// Calendar: the resource changed.
+    Exists,
+    /// Calendar: the resource was deleted.
+    NotExists,
+    /// Drive: a resource was created or shared.
+    Add,
+    /// Drive: a resource was deleted or unshared.
+    Remove,
+    /// Drive: a resource's properties or content changed.
+    Update,
+    /// Drive: a resource was moved to the trash.
+    Trash,
+    /// Drive: a resource was restored from the trash.
+    Untrash,
+    /// Drive: changes to the changes collection.
+    Change,
+    Other(String),
+}
+
+/// The `X-Goog-*` headers of a push notification delivered to a channel's address.
+#[derive(Debug, Clone)]
+pub struct ChannelNotification {
+    pub channel_id: String,
+    pub channel_token: Option<String>,
+    pub channel_expiration: Option<String>,
+    pub message_number: Option<u64>,
+    pub resource_id: String,
+    pub resource_uri: Option<String>,
+    pub resource_state: ResourceState,
+    /// Drive: which aspects of the resource changed, e.g. `content` or `permissions`.
+    pub changed: Vec<String>,
+}
+
+impl NewChannel {
+    /// Creates a webhook channel delivering notifications to `address`.
+    pub fn web_hook(id: impl Into<String>, address: impl Into<String>) -> NewChannel {
+        NewChannel {
+            id: id.into(),
+            channel_type: "web_hook".to_string(),
+            address: address.into(),
+            token: None,
+            expiration: None,
+        }
+    }
+}
+
+impl From<&str> for ResourceState {
+    fn from(state: &str) -> Self {
+        match state {
+            "sync" => ResourceState::Sync,
+            "exists" => ResourceState::Exists,
+            "not_exists" => ResourceState::NotExists,
+            "add" => ResourceState::Add,
+            "remove" => ResourceState::Remove,
+            "update" => ResourceState::Update,
+            "trash" => ResourceState::Trash,
+            "untrash" => ResourceState::Untrash,
+            "change" => ResourceState::Change,
+            other => ResourceState::Other(other.to_string()),
+        }
+    }
+}
+
+impl ChannelNotification {
+    /// Parses the `X-Goog-Channel-*` and `X-Goog-Resource-*` headers of an incoming
+    /// notification request.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the channel id, resource id or resource state
+    /// header is missing, i.e. the request is not a channel notification.
+    pub fn from_headers(headers: &HeaderMap) -> Result<ChannelNotification, Box<dyn Error>> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let required = |name: &str| header(name).ok_or(format!("Missing {name} header"));
+
+        Ok(ChannelNotification {
+            channel_id: required("X-Goog-Channel-ID")?,
+            channel_token: header("X-Goog-Channel-Token"),
+            channel_expiration: header("X-Goog-Channel-Expiration"),
+            message_number: header("X-Goog-Message-Number").and_then(|n| n.parse().ok()),
+            resource_id: required("X-Goog-Resource-ID")?,
+            resource_uri: header("X-Goog-Resource-URI"),
+            resource_state: ResourceState::from(required("X-Goog-Resource-State")?.as_str()),
+            changed: header("X-Goog-Changed")
+                .map(|changed| changed.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Parses the headers of an incoming notification and checks that it belongs to
+    /// `channel`, for use in a web framework handler of a Drive or Calendar watch.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error as described for `from_headers`, or if the
+    /// notification fails `verify`. Handlers should answer errors with `401` or `400` so
+    /// that forged requests are not processed.
+    pub fn from_verified_headers(
+        headers: &HeaderMap,
+        channel: &Channel,
+    ) -> Result<ChannelNotification, Box<dyn Error>> {
+        let notification = ChannelNotification::from_headers(headers)?;
+        if !notification.verify(channel) {
+            return Err(format!("Notification does not belong to channel {}", channel.id).into());
+        }
+
+        Ok(notification)
+    }
+
+    /// Returns `true` if the notification belongs to `channel`, i.e. the channel id,
+    /// resource id and channel token all match. Notifications failing this check should be
+    /// rejected, as the webhook address is public.
+    pub fn verify(&self, channel: &Channel) -> bool {
+        let token_matches = match (&self.channel_token, &channel.token) {
+            (Some(_), Some(expected)) => self.verify_token(expected),
+            (None, None) => true,
+            _ => false,
+        };
+
+        self.channel_id == channel.id && self.resource_id == channel.resource_id && token_matches
+    }
+
+    /// Returns `true` if the notification carries `token` in `X-Goog-Channel-Token`. The
+    /// comparison takes the same time wherever the tokens differ, so the token cannot be
+    /// guessed by timing failed requests.
+    pub fn verify_token(&self, token: &str) -> bool {
+        self.channel_token
+            .as_deref()
+            .is_some_and(|received| constant_time_eq(received.as_bytes(), token.as_bytes()))
+    }
+}
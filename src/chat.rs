@@ -0,0 +1,370 @@
+//! Google Chat API, for Chat apps posting messages and receiving interaction events.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{AUTHORIZATION, HeaderMap};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::jwt::{GOOGLE_CERTS_URL, GOOGLE_ISSUERS};
+use crate::{Google, Token};
+
+const CHAT_API: &str = "https://chat.googleapis.com/v1";
+
+/// The account Google Chat signs the tokens of interaction events with.
+const CHAT_ISSUER: &str = "chat@system.gserviceaccount.com";
+/// The public keys of `CHAT_ISSUER`.
+const CHAT_CERTS_URL: &str =
+    "https://www.googleapis.com/service_accounts/v1/jwk/chat@system.gserviceaccount.com";
+
+/// Scope granting a Chat app access to the spaces it belongs to, for service accounts.
+pub const SCOPE_CHAT_BOT: &str = "https://www.googleapis.com/auth/chat.bot";
+
+/// A client for the Google Chat API, usually authorized as the Chat app with a service
+/// account token with `SCOPE_CHAT_BOT`.
+#[derive(Clone, Copy)]
+pub struct Chat<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A message to post, built with `NewMessage::text` or `NewMessage::card` and the chained
+/// setters.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NewMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cards_v2: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread: Option<Thread>,
+}
+
+/// A message in a space.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Message {
+    /// The resource name, `spaces/{space}/messages/{message}`.
+    pub name: String,
+    pub sender: Option<ChatUser>,
+    pub create_time: Option<DateTime<Utc>>,
+    pub text: Option<String>,
+    /// The text without the mention of the app, for messages addressed to it.
+    pub argument_text: Option<String>,
+    pub thread: Option<Thread>,
+    pub space: Option<Space>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Thread {
+    /// The resource name, `spaces/{space}/threads/{thread}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The key an app chose to group its messages into a thread.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Space {
+    /// The resource name, `spaces/{space}`.
+    pub name: String,
+    /// `SPACE`, `GROUP_CHAT` or `DIRECT_MESSAGE`.
+    pub space_type: Option<String>,
+    pub display_name: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChatUser {
+    /// The resource name, `users/{user}`.
+    pub name: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    /// `HUMAN` or `BOT`.
+    #[serde(rename = "type")]
+    pub user_type: Option<String>,
+}
+
+/// An interaction event Google Chat sends to the app's HTTP endpoint.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChatEvent {
+    #[serde(rename = "type")]
+    pub event_type: ChatEventType,
+    pub event_time: Option<DateTime<Utc>>,
+    pub message: Option<Message>,
+    pub user: Option<ChatUser>,
+    pub space: Option<Space>,
+    /// The action of a clicked card button, for `CARD_CLICKED` events.
+    pub action: Option<Value>,
+    /// Client information and form inputs, kept as raw JSON.
+    pub common: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChatEventType {
+    Message,
+    AddedToSpace,
+    RemovedFromSpace,
+    CardClicked,
+    WidgetUpdated,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// The audience Chat issues event tokens for, as set in the app's connection settings.
+#[derive(Debug, Clone, Copy)]
+pub enum ChatAudience<'a> {
+    /// The Cloud project number of the app.
+    ProjectNumber(&'a str),
+    /// The URL of the app's HTTP endpoint.
+    EndpointUrl(&'a str),
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct IdTokenClaims {
+    email: String,
+    email_verified: bool,
+}
+
+impl NewMessage {
+    /// Creates a plain text message, supporting Chat's basic formatting and mentions.
+    pub fn text(text: impl Into<String>) -> NewMessage {
+        NewMessage {
+            text: Some(text.into()),
+            ..NewMessage::default()
+        }
+    }
+
+    /// Creates a message holding a single card.
+    ///
+    /// # Arguments
+    ///
+    /// * `card_id` - An id identifying the card within the message.
+    /// * `card` - The card, as Chat's card JSON with `header` and `sections`.
+    pub fn card(card_id: impl Into<String>, card: Value) -> NewMessage {
+        NewMessage::default().add_card(card_id, card)
+    }
+
+    /// Adds a card below the text or previous cards.
+    pub fn add_card(mut self, card_id: impl Into<String>, card: Value) -> NewMessage {
+        self.cards_v2
+            .push(json!({ "cardId": card_id.into(), "card": card }));
+        self
+    }
+
+    /// Posts the message in the thread with this key, starting it if it does not exist.
+    pub fn thread_key(mut self, thread_key: impl Into<String>) -> NewMessage {
+        self.thread = Some(Thread {
+            name: None,
+            thread_key: Some(thread_key.into()),
+        });
+        self
+    }
+
+    /// Replies in an existing thread, by its resource name.
+    pub fn thread(mut self, thread_name: impl Into<String>) -> NewMessage {
+        self.thread = Some(Thread {
+            name: Some(thread_name.into()),
+            thread_key: None,
+        });
+        self
+    }
+
+    fn reply_option(&self) -> Vec<(&'static str, &'static str)> {
+        match self.thread {
+            Some(_) => vec![("messageReplyOption", "REPLY_MESSAGE_FALLBACK_TO_NEW_THREAD")],
+            None => Vec::new(),
+        }
+    }
+
+    fn update_mask(&self) -> String {
+        let mut fields = Vec::new();
+        if self.text.is_some() {
+            fields.push("text");
+        }
+        if !self.cards_v2.is_empty() {
+            fields.push("cardsV2");
+        }
+        fields.join(",")
+    }
+}
+
+impl Google {
+    /// Returns a Google Chat API client that authorizes its requests with `token`.
+    pub fn chat<'a>(&'a self, token: &'a Token) -> Chat<'a> {
+        Chat {
+            google: self,
+            token,
+        }
+    }
+
+    /// Posts a message through an incoming webhook, which needs no credentials beyond the
+    /// key and token embedded in its URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook_url` - The URL of the webhook, as copied from the space's settings.
+    /// * `message` - The message to post.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn send_chat_webhook(
+        &self,
+        webhook_url: &str,
+        message: &NewMessage,
+    ) -> Result<Message, Box<dyn Error>> {
+        let request = self
+            .http
+            .post(webhook_url)
+            .query(&message.reply_option())
+            .json(message);
+
+        self.send_json(request).await
+    }
+
+    /// Verifies the bearer token Google Chat attaches to interaction events.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization` - The value of the request's `Authorization` header,
+    ///   `Bearer <token>`.
+    /// * `audience` - The audience configured in the app's connection settings.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the token is missing, malformed, not issued by
+    /// Google Chat for `audience` or expired, or if the signing keys cannot be fetched.
+    pub async fn verify_chat_token(
+        &self,
+        authorization: &str,
+        audience: ChatAudience<'_>,
+    ) -> Result<(), Box<dyn Error>> {
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .ok_or("Chat event has no bearer token")?;
+
+        match audience {
+            ChatAudience::ProjectNumber(project_number) => {
+                self.verify_jwt::<Value>(token, CHAT_CERTS_URL, &[CHAT_ISSUER], project_number)
+                    .await?;
+            }
+            ChatAudience::EndpointUrl(url) => {
+                let claims: IdTokenClaims = self
+                    .verify_jwt(token, GOOGLE_CERTS_URL, &GOOGLE_ISSUERS, url)
+                    .await?;
+                if !claims.email_verified || claims.email != CHAT_ISSUER {
+                    return Err(
+                        format!("Chat event is from unexpected account {}", claims.email).into(),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies an interaction event and decodes its body, for use in a web framework
+    /// handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the event request.
+    /// * `body` - The raw body of the event request.
+    /// * `audience` - The audience configured in the app's connection settings.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error as described for `verify_chat_token`, or if the body
+    /// is not a Chat event. Handlers should answer errors with `401` or `400`.
+    pub async fn verify_chat_event(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        audience: ChatAudience<'_>,
+    ) -> Result<ChatEvent, Box<dyn Error>> {
+        let authorization = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or("Chat event has no Authorization header")?;
+        self.verify_chat_token(authorization, audience).await?;
+
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+impl Chat<'_> {
+    /// Posts a message in a space the app belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `space` - The resource name of the space, `spaces/{space}`.
+    /// * `message` - The message to post.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn create_message(
+        &self,
+        space: &str,
+        message: &NewMessage,
+    ) -> Result<Message, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{CHAT_API}/{space}/messages"))
+            .bearer_auth(self.token.access_token())
+            .query(&message.reply_option())
+            .json(message);
+
+        self.google.send_json(request).await
+    }
+
+    /// Replaces the text and cards of a message the app posted with those set in
+    /// `message`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn update_message(
+        &self,
+        name: &str,
+        message: &NewMessage,
+    ) -> Result<Message, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .patch(format!("{CHAT_API}/{name}"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("updateMask", message.update_mask())])
+            .json(message);
+
+        self.google.send_json(request).await
+    }
+
+    /// Deletes a message the app posted.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn delete_message(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .delete(format!("{CHAT_API}/{name}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+//! Google Classroom API.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{Page, paginate};
+use crate::{Google, Token};
+
+const CLASSROOM_API: &str = "https://classroom.googleapis.com/v1";
+
+/// Scope granting read-only access to courses.
+pub const SCOPE_COURSES_READONLY: &str =
+    "https://www.googleapis.com/auth/classroom.courses.readonly";
+
+/// Scope granting read-only access to course rosters.
+pub const SCOPE_ROSTERS_READONLY: &str =
+    "https://www.googleapis.com/auth/classroom.rosters.readonly";
+
+/// Scope granting read-only access to coursework and submissions of all students.
+pub const SCOPE_COURSEWORK_STUDENTS_READONLY: &str =
+    "https://www.googleapis.com/auth/classroom.coursework.students.readonly";
+
+/// A client for the Classroom API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Classroom<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A course.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Course {
+    pub id: String,
+    pub name: String,
+    pub section: Option<String>,
+    pub description_heading: Option<String>,
+    pub description: Option<String>,
+    pub room: Option<String>,
+    pub owner_id: String,
+    /// `ACTIVE`, `ARCHIVED`, `PROVISIONED`, `DECLINED` or `SUSPENDED`.
+    pub course_state: String,
+    pub alternate_link: Option<String>,
+    pub enrollment_code: Option<String>,
+    pub creation_time: Option<DateTime<Utc>>,
+    pub update_time: Option<DateTime<Utc>>,
+}
+
+/// A student or teacher enrolled in a course.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Member {
+    pub course_id: String,
+    pub user_id: String,
+    pub profile: UserProfile,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserProfile {
+    pub id: String,
+    pub name: UserName,
+    pub email_address: Option<String>,
+    pub photo_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserName {
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub full_name: Option<String>,
+}
+
+/// An assignment, question or material posted to a course.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CourseWork {
+    pub id: String,
+    pub course_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// `PUBLISHED`, `DRAFT` or `DELETED`.
+    pub state: String,
+    /// `ASSIGNMENT`, `SHORT_ANSWER_QUESTION` or `MULTIPLE_CHOICE_QUESTION`.
+    pub work_type: String,
+    pub max_points: Option<f64>,
+    pub due_date: Option<Date>,
+    pub due_time: Option<TimeOfDay>,
+    pub alternate_link: Option<String>,
+    pub creator_user_id: Option<String>,
+    pub creation_time: Option<DateTime<Utc>>,
+    pub update_time: Option<DateTime<Utc>>,
+}
+
+/// A calendar date in UTC.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+/// A time of day in UTC.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimeOfDay {
+    pub hours: u32,
+    pub minutes: u32,
+}
+
+/// A student's submission for a piece of coursework.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StudentSubmission {
+    pub id: String,
+    pub course_id: String,
+    pub course_work_id: String,
+    pub user_id: String,
+    /// `NEW`, `CREATED`, `TURNED_IN`, `RETURNED` or `RECLAIMED_BY_STUDENT`.
+    pub state: String,
+    pub late: bool,
+    pub draft_grade: Option<f64>,
+    pub assigned_grade: Option<f64>,
+    pub alternate_link: Option<String>,
+    pub creation_time: Option<DateTime<Utc>>,
+    pub update_time: Option<DateTime<Utc>>,
+}
+
+/// Options for listing courses.
+#[derive(Debug, Clone, Default)]
+pub struct CourseQuery {
+    /// Only courses this user, an id, email or `me`, is a student in.
+    pub student_id: Option<String>,
+    /// Only courses this user, an id, email or `me`, is a teacher in.
+    pub teacher_id: Option<String>,
+    /// Only courses in these states, e.g. `ACTIVE`.
+    pub course_states: Vec<String>,
+}
+
+/// A page of courses returned by `courses.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CourseList {
+    pub courses: Vec<Course>,
+    pub next_page_token: Option<String>,
+}
+
+/// A page of course members returned by `students.list` or `teachers.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MemberList {
+    #[serde(alias = "students", alias = "teachers")]
+    pub members: Vec<Member>,
+    pub next_page_token: Option<String>,
+}
+
+/// A page of coursework returned by `courseWork.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CourseWorkList {
+    pub course_work: Vec<CourseWork>,
+    pub next_page_token: Option<String>,
+}
+
+/// A page of submissions returned by `studentSubmissions.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubmissionList {
+    pub student_submissions: Vec<StudentSubmission>,
+    pub next_page_token: Option<String>,
+}
+
+/// The role a course member is listed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberRole {
+    Student,
+    Teacher,
+}
+
+impl Page for CourseList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for MemberList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for CourseWorkList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for SubmissionList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Google {
+    /// Returns a Classroom API client that authorizes its requests with `token`.
+    pub fn classroom<'a>(&'a self, token: &'a Token) -> Classroom<'a> {
+        Classroom {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Classroom<'a> {
+    /// Lists a single page of the courses visible to the user.
+    pub async fn list_courses(
+        &self,
+        query: &CourseQuery,
+        page_token: Option<&str>,
+    ) -> Result<CourseList, Box<dyn Error>> {
+        let mut params = Vec::new();
+        if let Some(student_id) = &query.student_id {
+            params.push(("studentId", student_id.as_str()));
+        }
+        if let Some(teacher_id) = &query.teacher_id {
+            params.push(("teacherId", teacher_id.as_str()));
+        }
+        for state in &query.course_states {
+            params.push(("courseStates", state.as_str()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{CLASSROOM_API}/courses"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the courses matching `query`.
+    pub fn courses<'s>(
+        self,
+        query: &'s CourseQuery,
+    ) -> impl Stream<Item = Result<CourseList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(
+            move |page_token| async move { self.list_courses(query, page_token.as_deref()).await },
+        )
+    }
+
+    /// Lists a single page of a course's students or teachers.
+    pub async fn list_members(
+        &self,
+        course_id: &str,
+        role: MemberRole,
+        page_token: Option<&str>,
+    ) -> Result<MemberList, Box<dyn Error>> {
+        let collection = match role {
+            MemberRole::Student => "students",
+            MemberRole::Teacher => "teachers",
+        };
+        let request = self
+            .google
+            .http
+            .get(format!("{CLASSROOM_API}/courses/{course_id}/{collection}"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of a course's students or teachers.
+    pub fn members<'s>(
+        self,
+        course_id: &'s str,
+        role: MemberRole,
+    ) -> impl Stream<Item = Result<MemberList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_members(course_id, role, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Lists a single page of a course's coursework.
+    pub async fn list_course_work(
+        &self,
+        course_id: &str,
+        page_token: Option<&str>,
+    ) -> Result<CourseWorkList, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{CLASSROOM_API}/courses/{course_id}/courseWork"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of a course's coursework.
+    pub fn course_work<'s>(
+        self,
+        course_id: &'s str,
+    ) -> impl Stream<Item = Result<CourseWorkList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_course_work(course_id, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Lists a single page of student submissions.
+    ///
+    /// # Arguments
+    ///
+    /// * `course_id` - The course the coursework belongs to.
+    /// * `course_work_id` - The coursework, or `-` for submissions to all coursework.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    pub async fn list_submissions(
+        &self,
+        course_id: &str,
+        course_work_id: &str,
+        page_token: Option<&str>,
+    ) -> Result<SubmissionList, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!(
+                "{CLASSROOM_API}/courses/{course_id}/courseWork/{course_work_id}/studentSubmissions"
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of student submissions.
+    pub fn submissions<'s>(
+        self,
+        course_id: &'s str,
+        course_work_id: &'s str,
+    ) -> impl Stream<Item = Result<SubmissionList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_submissions(course_id, course_work_id, page_token.as_deref())
+                .await
+        })
+    }
+}
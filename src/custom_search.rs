@@ -0,0 +1,194 @@
+//! Custom Search JSON API, for querying a Programmable Search Engine.
+
+use std::error::Error;
+
+use futures_util::{Stream, stream};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Google;
+use crate::de::option_number_from_string;
+
+const CUSTOM_SEARCH_API: &str = "https://customsearch.googleapis.com/customsearch/v1";
+
+/// A client for one Programmable Search Engine, authorized with an API key.
+#[derive(Clone, Copy)]
+pub struct CustomSearch<'a> {
+    google: &'a Google,
+    api_key: &'a str,
+    engine_id: &'a str,
+}
+
+/// Options for a search.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// The number of results per page, between 1 and 10.
+    pub num: Option<u32>,
+    /// Whether SafeSearch filtering is enabled.
+    pub safe: bool,
+    /// Searches for images instead of web pages.
+    pub image_search: bool,
+    /// Restricts results to a language, e.g. `lang_de`.
+    pub lr: Option<String>,
+    /// Boosts results from a country, e.g. `de`.
+    pub gl: Option<String>,
+    /// Restricts results to a site, e.g. `example.com`.
+    pub site_search: Option<String>,
+    /// Restricts results by date, e.g. `d7` for the last seven days or `m6` for six months.
+    pub date_restrict: Option<String>,
+}
+
+/// A page of search results.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchResults {
+    pub items: Vec<SearchItem>,
+    pub search_information: SearchInformation,
+    pub queries: Queries,
+}
+
+/// A search result.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchItem {
+    pub title: String,
+    pub html_title: String,
+    pub link: String,
+    pub display_link: String,
+    pub snippet: String,
+    pub html_snippet: String,
+    pub mime: Option<String>,
+    pub file_format: Option<String>,
+    /// Image details, for image searches.
+    pub image: Option<ImageInfo>,
+    /// Structured data found on the page, kept as raw JSON.
+    pub pagemap: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImageInfo {
+    pub context_link: String,
+    pub height: u32,
+    pub width: u32,
+    pub thumbnail_link: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchInformation {
+    /// The estimated total number of results.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub total_results: Option<u64>,
+    pub search_time: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Queries {
+    /// Present unless this is the last page.
+    pub next_page: Vec<QueryInfo>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueryInfo {
+    /// The 1-based index of the first result of the page.
+    pub start_index: u32,
+    pub count: u32,
+}
+
+impl SearchResults {
+    /// The `start` of the following page, `None` on the last page.
+    pub fn next_start(&self) -> Option<u32> {
+        self.queries.next_page.first().map(|page| page.start_index)
+    }
+}
+
+impl Google {
+    /// Returns a Custom Search client for the search engine `engine_id` (the `cx`
+    /// parameter) that authorizes its requests with `api_key`.
+    pub fn custom_search<'a>(&'a self, api_key: &'a str, engine_id: &'a str) -> CustomSearch<'a> {
+        CustomSearch {
+            google: self,
+            api_key,
+            engine_id,
+        }
+    }
+}
+
+impl<'a> CustomSearch<'a> {
+    /// Fetches a single page of results.
+    ///
+    /// # Arguments
+    ///
+    /// * `q` - The search terms.
+    /// * `query` - Filtering and paging options.
+    /// * `start` - The 1-based index of the first result, `None` for the first page.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. when the daily quota is
+    /// exhausted.
+    pub async fn search(
+        &self,
+        q: &str,
+        query: &SearchQuery,
+        start: Option<u32>,
+    ) -> Result<SearchResults, Box<dyn Error>> {
+        let mut params = vec![
+            ("key", self.api_key.to_string()),
+            ("cx", self.engine_id.to_string()),
+            ("q", q.to_string()),
+        ];
+        if let Some(num) = query.num {
+            params.push(("num", num.to_string()));
+        }
+        if query.safe {
+            params.push(("safe", "active".to_string()));
+        }
+        if query.image_search {
+            params.push(("searchType", "image".to_string()));
+        }
+        if let Some(lr) = &query.lr {
+            params.push(("lr", lr.clone()));
+        }
+        if let Some(gl) = &query.gl {
+            params.push(("gl", gl.clone()));
+        }
+        if let Some(site_search) = &query.site_search {
+            params.push(("siteSearch", site_search.clone()));
+        }
+        if let Some(date_restrict) = &query.date_restrict {
+            params.push(("dateRestrict", date_restrict.clone()));
+        }
+        if let Some(start) = start {
+            params.push(("start", start.to_string()));
+        }
+
+        let request = self.google.http.get(CUSTOM_SEARCH_API).query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of results. The API returns at most the first 100 results.
+    pub fn results<'s>(
+        self,
+        q: &'s str,
+        query: &'s SearchQuery,
+    ) -> impl Stream<Item = Result<SearchResults, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        stream::try_unfold(Some(None), move |start| async move {
+            let Some(start) = start else {
+                return Ok(None);
+            };
+
+            let page = self.search(q, query, start).await?;
+            let next = page.next_start().map(Some);
+
+            Ok(Some((page, next)))
+        })
+    }
+}
@@ -0,0 +1,43 @@
+// Deserialization helpers shared by the API clients.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes an optional number that Google APIs encode as a JSON string, as they do for
+/// all 64-bit integers. Plain JSON numbers are accepted as well.
+pub(crate) fn option_number_from_string<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber<T> {
+        String(String),
+        Number(T),
+    }
+
+    match Option::<StringOrNumber<T>>::deserialize(deserializer)? {
+        Some(StringOrNumber::String(value)) => {
+            value.parse().map(Some).map_err(serde::de::Error::custom)
+        }
+        Some(StringOrNumber::Number(value)) => Ok(Some(value)),
+        None => Ok(None),
+    }
+}
+
+/// Deserializes a number that Google APIs encode as a JSON string, like
+/// `option_number_from_string` but rejecting `null`.
+#[cfg(feature = "gmail")]
+pub(crate) fn number_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    T::Err: Display,
+{
+    option_number_from_string(deserializer)?
+        .ok_or_else(|| serde::de::Error::custom("expected a number, found null"))
+}
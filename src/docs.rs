@@ -0,0 +1,260 @@
+//! Google Docs API.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{Google, Token};
+
+const DOCS_API: &str = "https://docs.googleapis.com/v1/documents";
+
+/// Scope granting read-only access to documents.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/documents.readonly";
+
+/// Scope granting read and write access to documents.
+pub const SCOPE_DOCUMENTS: &str = "https://www.googleapis.com/auth/documents";
+
+/// A client for the Docs API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Docs<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A document and its structure.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Document {
+    pub document_id: String,
+    pub title: String,
+    pub revision_id: Option<String>,
+    pub body: Body,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Body {
+    pub content: Vec<StructuralElement>,
+}
+
+/// A top-level element of the body: a paragraph, table, section break or table of
+/// contents. Elements other than paragraphs are kept as raw JSON.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StructuralElement {
+    pub start_index: u32,
+    pub end_index: u32,
+    pub paragraph: Option<Paragraph>,
+    pub table: Option<Value>,
+    pub section_break: Option<Value>,
+    pub table_of_contents: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Paragraph {
+    pub elements: Vec<ParagraphElement>,
+    pub paragraph_style: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParagraphElement {
+    pub start_index: u32,
+    pub end_index: u32,
+    pub text_run: Option<TextRun>,
+}
+
+/// A run of text sharing the same style.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextRun {
+    pub content: String,
+    pub text_style: TextStyle,
+}
+
+/// Character formatting. When updating, only the fields named in the request's `fields`
+/// mask are applied.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextStyle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub underline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Dimension {
+    pub magnitude: f64,
+    /// Always `PT`.
+    pub unit: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Link {
+    pub url: String,
+}
+
+/// A range of the document body, `end_index` exclusive.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Range {
+    pub start_index: u32,
+    pub end_index: u32,
+}
+
+/// A single edit applied by `Docs::batch_update`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Request {
+    /// Inserts text at an index of the body.
+    InsertText { text: String, location: Location },
+    /// Replaces every occurrence of a text, e.g. a `{{name}}` placeholder.
+    ReplaceAllText {
+        contains_text: SubstringMatchCriteria,
+        replace_text: String,
+    },
+    /// Applies the fields of `text_style` named in `fields` (e.g. `bold,italic`) to a
+    /// range.
+    UpdateTextStyle {
+        range: Range,
+        text_style: TextStyle,
+        fields: String,
+    },
+    /// Deletes a range of content.
+    DeleteContentRange { range: Range },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub index: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstringMatchCriteria {
+    pub text: String,
+    pub match_case: bool,
+}
+
+/// The result of `Docs::batch_update`, with one reply per request.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BatchUpdateResponse {
+    pub document_id: String,
+    /// Replies in request order; `{}` for requests without a reply, e.g.
+    /// `{"replaceAllText": {"occurrencesChanged": 2}}` for `ReplaceAllText`.
+    pub replies: Vec<Value>,
+}
+
+impl Document {
+    /// Returns the text of all top-level paragraphs, ignoring tables.
+    pub fn plain_text(&self) -> String {
+        self.body
+            .content
+            .iter()
+            .filter_map(|element| element.paragraph.as_ref())
+            .flat_map(|paragraph| &paragraph.elements)
+            .filter_map(|element| element.text_run.as_ref())
+            .map(|run| run.content.as_str())
+            .collect()
+    }
+}
+
+impl Request {
+    /// Inserts `text` at `index`. Index `1` is the start of the body.
+    pub fn insert_text(index: u32, text: impl Into<String>) -> Request {
+        Request::InsertText {
+            text: text.into(),
+            location: Location { index },
+        }
+    }
+
+    /// Replaces every case-sensitive occurrence of `placeholder` with `value`.
+    pub fn replace_all_text(placeholder: impl Into<String>, value: impl Into<String>) -> Request {
+        Request::ReplaceAllText {
+            contains_text: SubstringMatchCriteria {
+                text: placeholder.into(),
+                match_case: true,
+            },
+            replace_text: value.into(),
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Docs API client that authorizes its requests with `token`.
+    pub fn docs<'a>(&'a self, token: &'a Token) -> Docs<'a> {
+        Docs {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl Docs<'_> {
+    /// Fetches a document with its full structure.
+    pub async fn get_document(&self, document_id: &str) -> Result<Document, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{DOCS_API}/{document_id}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Applies `requests` to a document atomically: if one request fails, none is applied.
+    ///
+    /// Requests are applied in order, so indexes of later requests must account for the
+    /// changes made by earlier ones.
+    pub async fn batch_update(
+        &self,
+        document_id: &str,
+        requests: &[Request],
+    ) -> Result<BatchUpdateResponse, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{DOCS_API}/{document_id}:batchUpdate"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "requests": requests }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Fills a templated document by replacing each placeholder with its value.
+    ///
+    /// To keep the template intact, copy it first with `Drive::copy_file` and fill the
+    /// copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `document_id` - The document to fill.
+    /// * `replacements` - Pairs of placeholder and value, e.g. `("{{name}}", "Alice")`.
+    pub async fn replace_placeholders(
+        &self,
+        document_id: &str,
+        replacements: &[(&str, &str)],
+    ) -> Result<BatchUpdateResponse, Box<dyn Error>> {
+        let requests: Vec<Request> = replacements
+            .iter()
+            .map(|(placeholder, value)| Request::replace_all_text(*placeholder, *value))
+            .collect();
+
+        self.batch_update(document_id, &requests).await
+    }
+}
@@ -0,0 +1,693 @@
+//! Google Drive API.
+
+use std::error::Error;
+use std::ops::RangeBounds;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use reqwest::StatusCode;
+use reqwest::header::RANGE;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::api::{Page, encode_path, paginate, range_header};
+use crate::channel::{Channel, NewChannel};
+use crate::de::option_number_from_string;
+use crate::upload::UploadSession;
+use crate::{Google, Token};
+
+const DRIVE_API: &str = "https://www.googleapis.com/drive/v3";
+const DRIVE_UPLOAD_API: &str = "https://www.googleapis.com/upload/drive/v3";
+
+/// The file fields requested by default by `Drive::list_files`.
+const FILE_FIELDS: &str =
+    "id,name,mimeType,size,owners,parents,modifiedTime,createdTime,webViewLink,trashed";
+
+/// Scope granting read-only access to file metadata and content.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/drive.readonly";
+
+/// Scope granting access to files created or opened by the app.
+pub const SCOPE_FILE: &str = "https://www.googleapis.com/auth/drive.file";
+
+/// Scope granting full access to the user's Drive.
+pub const SCOPE_FULL: &str = "https://www.googleapis.com/auth/drive";
+
+/// A client for the Drive API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Drive<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// File metadata as returned by the Drive API.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DriveFile {
+    pub id: String,
+    pub name: String,
+    pub mime_type: String,
+    /// The size in bytes. Not set for folders and Google Workspace documents.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub size: Option<u64>,
+    pub owners: Vec<User>,
+    pub parents: Vec<String>,
+    pub modified_time: Option<DateTime<Utc>>,
+    pub created_time: Option<DateTime<Utc>>,
+    pub web_view_link: Option<String>,
+    pub trashed: bool,
+}
+
+/// A Drive user, e.g. a file owner.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct User {
+    pub display_name: String,
+    pub email_address: Option<String>,
+    pub permission_id: Option<String>,
+    pub photo_link: Option<String>,
+    pub me: bool,
+}
+
+/// Metadata of a file to be created.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NewFile {
+    pub name: String,
+    /// The MIME type of the file. Setting a Google Workspace type converts the upload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Options for listing files.
+#[derive(Debug, Clone, Default)]
+pub struct FileQuery {
+    /// A Drive search query, e.g. `name contains 'report' and trashed = false`.
+    pub q: Option<String>,
+    /// A comma-separated sort order, e.g. `modifiedTime desc,name`.
+    pub order_by: Option<String>,
+    /// The bodies of items to search: `user`, `drive`, `domain` or `allDrives`.
+    pub corpora: Option<String>,
+    /// The shared drive to search when `corpora` is `drive`.
+    pub drive_id: Option<String>,
+    /// Include items from shared drives in the results.
+    pub include_items_from_all_drives: bool,
+    /// The number of files per page, at most 1000.
+    pub page_size: Option<u32>,
+    /// The spaces to search, e.g. `drive` or `appDataFolder`.
+    pub spaces: Option<String>,
+}
+
+/// A page of files returned by `files.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileList {
+    pub files: Vec<DriveFile>,
+    pub next_page_token: Option<String>,
+    /// Set when the search could not cover all corpora and results may be missing.
+    pub incomplete_search: bool,
+}
+
+impl Page for FileList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// A single change to a file or shared drive.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Change {
+    /// `file` or `drive`.
+    pub change_type: String,
+    pub file_id: Option<String>,
+    pub file: Option<DriveFile>,
+    pub drive_id: Option<String>,
+    /// Whether the file or drive was removed or the user lost access to it.
+    pub removed: bool,
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// A page of changes returned by `changes.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChangeList {
+    pub changes: Vec<Change>,
+    pub next_page_token: Option<String>,
+    /// Present on the last page; the page token to use for the next round of changes.
+    pub new_start_page_token: Option<String>,
+}
+
+impl Page for ChangeList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// Who a permission grants access to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionType {
+    #[default]
+    User,
+    Group,
+    Domain,
+    Anyone,
+}
+
+/// The access level granted by a permission.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    Owner,
+    Organizer,
+    FileOrganizer,
+    Writer,
+    Commenter,
+    #[default]
+    Reader,
+}
+
+/// A permission granting access to a file.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Permission {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub permission_type: PermissionType,
+    pub role: Role,
+    pub email_address: Option<String>,
+    pub domain: Option<String>,
+    pub display_name: Option<String>,
+    pub allow_file_discovery: Option<bool>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    /// Set while an ownership transfer to this user awaits acceptance.
+    pub pending_owner: bool,
+    pub deleted: bool,
+}
+
+/// A permission to be created on a file. Use the `user`, `group`, `domain` and `anyone`
+/// constructors.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPermission {
+    #[serde(rename = "type")]
+    pub permission_type: PermissionType,
+    pub role: Role,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// For `domain` and `anyone` permissions, whether the file can be found through search
+    /// rather than only through its link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_file_discovery: Option<bool>,
+}
+
+/// Options for creating a permission.
+#[derive(Debug, Clone, Default)]
+pub struct PermissionOptions {
+    /// Whether to email the new grantee. Drive defaults to `true` for users and groups,
+    /// and always notifies on ownership transfers.
+    pub send_notification_email: Option<bool>,
+    /// A plain text message added to the notification email.
+    pub email_message: Option<String>,
+    /// Transfer ownership to the grantee, demoting the current owner to `writer`. Requires
+    /// `Role::Owner`.
+    pub transfer_ownership: bool,
+}
+
+/// A page of permissions returned by `permissions.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PermissionList {
+    pub permissions: Vec<Permission>,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for PermissionList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl NewPermission {
+    /// Grants `role` to the user with the given email address.
+    pub fn user(email_address: impl Into<String>, role: Role) -> NewPermission {
+        NewPermission {
+            permission_type: PermissionType::User,
+            role,
+            email_address: Some(email_address.into()),
+            ..NewPermission::default()
+        }
+    }
+
+    /// Grants `role` to the Google group with the given email address.
+    pub fn group(email_address: impl Into<String>, role: Role) -> NewPermission {
+        NewPermission {
+            permission_type: PermissionType::Group,
+            role,
+            email_address: Some(email_address.into()),
+            ..NewPermission::default()
+        }
+    }
+
+    /// Grants `role` to everyone in a Google Workspace domain.
+    pub fn domain(domain: impl Into<String>, role: Role) -> NewPermission {
+        NewPermission {
+            permission_type: PermissionType::Domain,
+            role,
+            domain: Some(domain.into()),
+            ..NewPermission::default()
+        }
+    }
+
+    /// Grants `role` to anyone with the link.
+    pub fn anyone(role: Role) -> NewPermission {
+        NewPermission {
+            permission_type: PermissionType::Anyone,
+            role,
+            ..NewPermission::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartPageToken {
+    start_page_token: String,
+}
+
+impl Google {
+    /// Returns a Drive API client that authorizes its requests with `token`.
+    pub fn drive<'a>(&'a self, token: &'a Token) -> Drive<'a> {
+        Drive {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Drive<'a> {
+    /// Lists a single page of files.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The search query, ordering and corpora to list from.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    pub async fn list_files(
+        &self,
+        query: &FileQuery,
+        page_token: Option<&str>,
+    ) -> Result<FileList, Box<dyn Error>> {
+        let mut params = vec![(
+            "fields",
+            format!("nextPageToken,incompleteSearch,files({FILE_FIELDS})"),
+        )];
+        if let Some(q) = &query.q {
+            params.push(("q", q.clone()));
+        }
+        if let Some(order_by) = &query.order_by {
+            params.push(("orderBy", order_by.clone()));
+        }
+        if let Some(corpora) = &query.corpora {
+            params.push(("corpora", corpora.clone()));
+        }
+        if let Some(drive_id) = &query.drive_id {
+            params.push(("driveId", drive_id.clone()));
+        }
+        if query.include_items_from_all_drives || query.drive_id.is_some() {
+            params.push(("includeItemsFromAllDrives", "true".to_string()));
+            params.push(("supportsAllDrives", "true".to_string()));
+        }
+        if let Some(page_size) = query.page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        if let Some(spaces) = &query.spaces {
+            params.push(("spaces", spaces.clone()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{DRIVE_API}/files"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of files matching `query`.
+    pub fn files<'s>(
+        self,
+        query: &'s FileQuery,
+    ) -> impl Stream<Item = Result<FileList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(
+            move |page_token| async move { self.list_files(query, page_token.as_deref()).await },
+        )
+    }
+
+    /// Returns the page token to start listing or watching future changes from.
+    pub async fn get_start_page_token(&self) -> Result<String, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{DRIVE_API}/changes/startPageToken"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("supportsAllDrives", "true")]);
+
+        let token: StartPageToken = self.google.send_json(request).await?;
+
+        Ok(token.start_page_token)
+    }
+
+    /// Lists a single page of changes, starting at `page_token`.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_token` - A token from `get_start_page_token`, a previous page's
+    ///   `next_page_token` or the last page's `new_start_page_token`.
+    pub async fn list_changes(&self, page_token: &str) -> Result<ChangeList, Box<dyn Error>> {
+        let fields = format!(
+            "nextPageToken,newStartPageToken,\
+             changes(changeType,fileId,driveId,removed,time,file({FILE_FIELDS}))"
+        );
+        let request = self
+            .google
+            .http
+            .get(format!("{DRIVE_API}/changes"))
+            .bearer_auth(self.token.access_token())
+            .query(&[
+                ("pageToken", page_token),
+                ("supportsAllDrives", "true"),
+                ("includeItemsFromAllDrives", "true"),
+                ("fields", &fields),
+            ]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Creates a notification channel receiving a webhook call whenever a change happens
+    /// after `page_token`.
+    ///
+    /// Verify incoming calls with `ChannelNotification::from_verified_headers`, then fetch
+    /// the changes with `list_changes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_token` - The token to watch changes from, as for `list_changes`.
+    /// * `channel` - The channel to create, see `NewChannel::web_hook`.
+    pub async fn watch_changes(
+        &self,
+        page_token: &str,
+        channel: &NewChannel,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{DRIVE_API}/changes/watch"))
+            .bearer_auth(self.token.access_token())
+            .query(&[
+                ("pageToken", page_token),
+                ("supportsAllDrives", "true"),
+                ("includeItemsFromAllDrives", "true"),
+            ])
+            .json(channel);
+
+        self.google.send_json(request).await
+    }
+
+    /// Replaces `channel`, which is about to expire, with `new_channel`.
+    ///
+    /// Drive channels cannot be extended, so the new channel is created first and the old
+    /// one stopped afterwards, ensuring no change goes unnoticed in between.
+    pub async fn renew_channel(
+        &self,
+        channel: &Channel,
+        page_token: &str,
+        new_channel: &NewChannel,
+    ) -> Result<Channel, Box<dyn Error>> {
+        let renewed = self.watch_changes(page_token, new_channel).await?;
+        self.stop_channel(channel).await?;
+
+        Ok(renewed)
+    }
+
+    /// Stops a notification channel.
+    pub async fn stop_channel(&self, channel: &Channel) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{DRIVE_API}/channels/stop"))
+            .bearer_auth(self.token.access_token())
+            .json(&serde_json::json!({ "id": channel.id, "resourceId": channel.resource_id }));
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Shares a file by creating a permission on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file to share.
+    /// * `permission` - The grantee and role, e.g. `NewPermission::user(email, Role::Writer)`.
+    /// * `options` - Notification and ownership transfer options.
+    pub async fn create_permission(
+        &self,
+        file_id: &str,
+        permission: &NewPermission,
+        options: &PermissionOptions,
+    ) -> Result<Permission, Box<dyn Error>> {
+        let mut params = vec![("supportsAllDrives", "true".to_string())];
+        if let Some(send) = options.send_notification_email {
+            params.push(("sendNotificationEmail", send.to_string()));
+        }
+        if let Some(message) = &options.email_message {
+            params.push(("emailMessage", message.clone()));
+        }
+        if options.transfer_ownership {
+            params.push(("transferOwnership", "true".to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{DRIVE_API}/files/{}/permissions",
+                encode_path(file_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&params)
+            .json(permission);
+
+        self.google.send_json(request).await
+    }
+
+    /// Lists a single page of the permissions on a file.
+    pub async fn list_permissions(
+        &self,
+        file_id: &str,
+        page_token: Option<&str>,
+    ) -> Result<PermissionList, Box<dyn Error>> {
+        let mut params = vec![
+            ("supportsAllDrives", "true"),
+            ("fields", "nextPageToken,permissions"),
+        ];
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!(
+                "{DRIVE_API}/files/{}/permissions",
+                encode_path(file_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the permissions on a file.
+    pub fn permissions<'s>(
+        self,
+        file_id: &'s str,
+    ) -> impl Stream<Item = Result<PermissionList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_permissions(file_id, page_token.as_deref()).await
+        })
+    }
+
+    /// Removes a permission from a file, revoking the grantee's access.
+    pub async fn delete_permission(
+        &self,
+        file_id: &str,
+        permission_id: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .delete(format!(
+                "{DRIVE_API}/files/{}/permissions/{}",
+                encode_path(file_id),
+                encode_path(permission_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&[("supportsAllDrives", "true")]);
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Copies a file, e.g. a Docs or Slides template before filling it.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file to copy.
+    /// * `file` - The metadata of the copy, such as its name and parent folders.
+    pub async fn copy_file(
+        &self,
+        file_id: &str,
+        file: &NewFile,
+    ) -> Result<DriveFile, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{DRIVE_API}/files/{}/copy", encode_path(file_id)))
+            .bearer_auth(self.token.access_token())
+            .query(&[("supportsAllDrives", "true"), ("fields", FILE_FIELDS)])
+            .json(file);
+
+        self.google.send_json(request).await
+    }
+
+    /// Starts a resumable upload session for a new file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The metadata of the file to create.
+    /// * `content_type` - The MIME type of the uploaded content.
+    /// * `size` - The total size in bytes, if known up front.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the session cannot be initiated.
+    pub async fn create_upload_session(
+        &self,
+        file: &NewFile,
+        content_type: &str,
+        size: Option<u64>,
+    ) -> Result<UploadSession<DriveFile>, Box<dyn Error>> {
+        let mut request = self
+            .google
+            .http
+            .post(format!("{DRIVE_UPLOAD_API}/files"))
+            .bearer_auth(self.token.access_token())
+            .query(&[
+                ("uploadType", "resumable"),
+                ("supportsAllDrives", "true"),
+                ("fields", FILE_FIELDS),
+            ])
+            .header("X-Upload-Content-Type", content_type)
+            .json(file);
+
+        if let Some(size) = size {
+            request = request.header("X-Upload-Content-Length", size);
+        }
+
+        UploadSession::start(self.google, request, size).await
+    }
+
+    /// Downloads the content of a file as a stream of chunks, without buffering the whole
+    /// file in memory.
+    ///
+    /// Only files with binary content can be downloaded; Google Workspace documents have to
+    /// be exported instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file to download.
+    /// * `range` - The byte range to download, e.g. `..` for the whole file or `offset..` to
+    ///   resume an interrupted download.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `range` is empty, if the download cannot be started,
+    /// or if `range` is not the whole file and the server sends the whole file instead.
+    /// Errors occurring while the content is transferred are yielded by the stream.
+    pub async fn download<R: RangeBounds<u64>>(
+        &self,
+        file_id: &str,
+        range: R,
+    ) -> Result<impl Stream<Item = Result<Bytes, Box<dyn Error>>> + use<R>, Box<dyn Error>> {
+        let mut request = self
+            .google
+            .http
+            .get(format!("{DRIVE_API}/files/{}", encode_path(file_id)))
+            .bearer_auth(self.token.access_token())
+            .query(&[("alt", "media"), ("supportsAllDrives", "true")]);
+
+        let range = range_header(&range)?;
+        if let Some(range) = &range {
+            request = request.header(RANGE, range);
+        }
+
+        let response = self.google.send(request).await?;
+        if range.is_some() && response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err("Server ignored the requested range and sent the whole file".into());
+        }
+
+        Ok(response.bytes_stream().map_err(|err| err.into()))
+    }
+
+    /// Downloads the content of a file into `writer`, returning the number of bytes written.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file to download.
+    /// * `writer` - The destination, e.g. a `tokio::fs::File`.
+    /// * `offset` - The byte offset to start at, `0` for the whole file. Pass the number of
+    ///   bytes already written to resume an interrupted download.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a request or a write fails. Nothing is written
+    /// when resuming at a non-zero `offset` and the server does not honour it, so that the
+    /// file is not corrupted; start over from `0` in that case.
+    pub async fn download_to<W: AsyncWrite + Unpin>(
+        &self,
+        file_id: &str,
+        writer: &mut W,
+        offset: u64,
+    ) -> Result<u64, Box<dyn Error>> {
+        let mut stream = Box::pin(self.download(file_id, offset..).await?);
+        let mut written = 0;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+}
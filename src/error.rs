@@ -0,0 +1,437 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{RETRY_AFTER, WWW_AUTHENTICATE};
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::REDACTED;
+
+/// Reasons Google attaches to errors caused by a token lacking the required scopes.
+const INSUFFICIENT_SCOPE_REASONS: [&str; 2] =
+    ["insufficientPermissions", "ACCESS_TOKEN_SCOPE_INSUFFICIENT"];
+/// Reasons Google attaches to errors caused by exceeding a rate limit or quota.
+const RATE_LIMIT_REASONS: [&str; 4] = [
+    "rateLimitExceeded",
+    "userRateLimitExceeded",
+    "RATE_LIMIT_EXCEEDED",
+    "RESOURCE_EXHAUSTED",
+];
+
+/// Prefixes of Google access tokens, refresh tokens, authorization codes and API keys, and
+/// of JWTs such as ID tokens.
+const SECRET_PREFIXES: [&str; 5] = ["ya29.", "1//", "4/0", "AIza", "eyJ"];
+
+/// The delay before the first retry, doubled on each further attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The longest delay waited before a retry, as recommended by Google's backoff guidance.
+pub(crate) const MAX_BACKOFF: Duration = Duration::from_secs(64);
+
+/// An error response returned by a Google API.
+///
+/// All API calls in this crate return errors as `Box<dyn Error>`; failed responses can be
+/// inspected by downcasting with `err.downcast_ref::<GoogleError>()`.
+///
+/// Tokens, authorization codes and API keys echoed back by Google are redacted from the
+/// error, so its `Display` and `Debug` output can be logged:
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use async_google_auth::GoogleError;
+///
+/// let body = r#"{"error": "invalid_grant", "error_description": "Bad token 1//0gAbc-Xyz"}"#;
+/// let response = http::Response::builder().status(400).body(body).unwrap();
+/// let error = GoogleError::from_response(response.into()).await;
+///
+/// assert_eq!(
+///     error.to_string(),
+///     "Google API error 400 Bad Request (invalid_grant): Bad token [redacted]"
+/// );
+/// assert!(!format!("{error:?}").contains("1//0gAbc-Xyz"));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct GoogleError {
+    status: StatusCode,
+    code: Option<String>,
+    message: String,
+    reasons: Vec<String>,
+    required_scopes: Vec<String>,
+    retry_after: Option<Duration>,
+    correlation_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: ErrorField,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorField {
+    Api(ApiError),
+    OAuth(String),
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    #[serde(default)]
+    message: String,
+    status: Option<String>,
+    #[serde(default)]
+    errors: Vec<Reason>,
+    #[serde(default)]
+    details: Vec<Reason>,
+}
+
+#[derive(Deserialize)]
+struct Reason {
+    /// The reason of `errors` entries and `ErrorInfo` details, or the `errorCode` of
+    /// API-specific details such as FCM's.
+    #[serde(alias = "errorCode")]
+    reason: Option<String>,
+}
+
+impl GoogleError {
+    /// Builds a `GoogleError` from a non-successful response, parsing both the Google API
+    /// error envelope and the plain OAuth2 `error`/`error_description` form.
+    ///
+    /// This is useful for requests to Google APIs the application sends itself.
+    pub async fn from_response(response: Response) -> GoogleError {
+        let status = response.status();
+        let required_scopes = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .map(parse_required_scopes)
+            .unwrap_or_default();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+
+        let mut error = GoogleError {
+            status,
+            code: None,
+            message: status.canonical_reason().unwrap_or_default().to_string(),
+            reasons: Vec::new(),
+            required_scopes,
+            retry_after,
+            correlation_id: None,
+        };
+
+        let body = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<ErrorBody>(&body) {
+            Ok(ErrorBody {
+                error: ErrorField::Api(api),
+                ..
+            }) => {
+                error.code = api.status;
+                error.message = api.message;
+                error.reasons = api
+                    .errors
+                    .into_iter()
+                    .chain(api.details)
+                    .filter_map(|reason| reason.reason)
+                    .collect();
+            }
+            Ok(ErrorBody {
+                error: ErrorField::OAuth(code),
+                error_description,
+            }) => {
+                error.message = error_description.unwrap_or_else(|| code.clone());
+                error.code = Some(code);
+            }
+            Err(_) => {
+                if let Ok(Value::String(message)) = serde_json::from_str::<Value>(&body) {
+                    error.message = message;
+                }
+            }
+        }
+
+        error.code = error.code.as_deref().map(redact_secrets);
+        error.message = redact_secrets(&error.message);
+        error
+    }
+
+    /// Attaches the correlation id the failed request was sent with.
+    pub(crate) fn with_correlation_id(mut self, correlation_id: Option<String>) -> GoogleError {
+        self.correlation_id = correlation_id;
+        self
+    }
+
+    /// The HTTP status code of the failed response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The error code reported by Google, e.g. `PERMISSION_DENIED` or `invalid_token`.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// The human readable error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The machine readable reasons attached to the error, e.g. `rateLimitExceeded`.
+    pub fn reasons(&self) -> &[String] {
+        &self.reasons
+    }
+
+    /// Returns `true` if the request failed because the access token lacks a scope the API
+    /// requires. The user can be sent through `Google::get_incremental_redirect_url` to
+    /// grant the missing scopes.
+    pub fn is_insufficient_scope(&self) -> bool {
+        self.status == StatusCode::FORBIDDEN
+            && (!self.required_scopes.is_empty()
+                || self
+                    .reasons
+                    .iter()
+                    .any(|reason| INSUFFICIENT_SCOPE_REASONS.contains(&reason.as_str())))
+    }
+
+    /// The scopes the API reported as required in its `WWW-Authenticate` header, if any.
+    pub fn required_scopes(&self) -> &[String] {
+        &self.required_scopes
+    }
+
+    /// Returns `true` if the request may succeed when sent again later: on `429`, on `500`,
+    /// `502`, `503` and `504`, and on rate limit or quota errors such as
+    /// `rateLimitExceeded` and `RESOURCE_EXHAUSTED`.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::INTERNAL_SERVER_ERROR
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        ) || self.code.as_deref() == Some("RESOURCE_EXHAUSTED")
+            || self
+                .reasons
+                .iter()
+                .any(|reason| RATE_LIMIT_REASONS.contains(&reason.as_str()))
+    }
+
+    /// The correlation id sent in the `X-Request-Id` header of the failed request, to match
+    /// it against application logs or quote it in a support ticket.
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    /// The delay the response asked to wait before retrying, from its `Retry-After` header.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    /// The delay to wait before retrying after `attempt` previous retries, or `None` if the
+    /// error is not retryable.
+    ///
+    /// This is the `Retry-After` delay when the response carries one, and otherwise an
+    /// exponential backoff starting at one second, capped at 64 seconds.
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        if !self.is_retryable() {
+            return None;
+        }
+
+        Some(self.retry_after.unwrap_or_else(|| {
+            INITIAL_BACKOFF
+                .saturating_mul(2u32.saturating_pow(attempt))
+                .min(MAX_BACKOFF)
+        }))
+    }
+}
+
+impl fmt::Display for GoogleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code {
+            Some(code) => write!(
+                f,
+                "Google API error {} ({}): {}",
+                self.status, code, self.message
+            )?,
+            None => write!(f, "Google API error {}: {}", self.status, self.message)?,
+        }
+        if let Some(correlation_id) = &self.correlation_id {
+            write!(f, " [request id {correlation_id}]")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for GoogleError {}
+
+/// Replaces anything looking like a token, authorization code or API key in `text`.
+fn redact_secrets(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = find_secret(rest) {
+        redacted.push_str(&rest[..start]);
+        redacted.push_str(REDACTED);
+        let len = rest[start..]
+            .find(|c| !is_token_char(c))
+            .unwrap_or(rest.len() - start);
+        rest = &rest[start + len..];
+    }
+    redacted.push_str(rest);
+
+    redacted
+}
+
+/// Returns the start of the first secret in `text`, which must begin at a word boundary.
+fn find_secret(text: &str) -> Option<usize> {
+    text.char_indices().map(|(i, _)| i).find(|&i| {
+        !text[..i].ends_with(is_token_char)
+            && SECRET_PREFIXES
+                .iter()
+                .any(|prefix| text[i..].starts_with(prefix))
+    })
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-_./~+=".contains(c)
+}
+
+/// Parses a `Retry-After` header, given either in seconds or as an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Extracts the `scope` parameter of a `Bearer error="insufficient_scope"` challenge.
+fn parse_required_scopes(challenge: &str) -> Vec<String> {
+    if !challenge.contains("insufficient_scope") {
+        return Vec::new();
+    }
+
+    challenge
+        .split(',')
+        .filter_map(|param| param.trim().trim_start_matches("Bearer ").split_once('='))
+        .find(|(name, _)| name.trim() == "scope")
+        .map(|(_, value)| {
+            value
+                .trim_matches('"')
+                .split_whitespace()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn error_from(status: u16, body: &str) -> GoogleError {
+        let response = http::Response::builder()
+            .status(status)
+            .body(body.to_string())
+            .unwrap();
+        GoogleError::from_response(response.into()).await
+    }
+
+    #[test]
+    fn redacts_every_secret_prefix() {
+        for secret in [
+            "ya29.a0AfH6SMBx-Yz_1",
+            "1//0gAbc-Xyz",
+            "4/0AX4XfWh-code",
+            "AIzaSyD-key_123",
+            "eyJhbGciOiJSUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln",
+        ] {
+            let redacted = redact_secrets(&format!("Bad token {secret}, try again"));
+            assert_eq!(redacted, "Bad token [redacted], try again", "{secret}");
+        }
+    }
+
+    #[test]
+    fn redacts_several_secrets() {
+        assert_eq!(
+            redact_secrets("ya29.abc and 1//def"),
+            "[redacted] and [redacted]"
+        );
+    }
+
+    #[test]
+    fn keeps_text_without_secrets() {
+        let text = "Request had invalid authentication credentials (key a1//b in projects/4/0).";
+        assert_eq!(redact_secrets(text), text);
+    }
+
+    #[tokio::test]
+    async fn redacts_oauth_error_output() {
+        let body = r#"{"error": "invalid_grant", "error_description": "Bad 1//0gAbc-Xyz"}"#;
+        let error = error_from(400, body).await;
+
+        assert_eq!(error.code(), Some("invalid_grant"));
+        assert_eq!(
+            error.to_string(),
+            "Google API error 400 Bad Request (invalid_grant): Bad [redacted]"
+        );
+        assert!(!format!("{error:?}").contains("1//0gAbc-Xyz"));
+    }
+
+    #[tokio::test]
+    async fn redacts_api_error_output() {
+        let body = r#"{"error": {"code": 401, "status": "UNAUTHENTICATED",
+            "message": "Invalid token ya29.a0AfH6SMBx", "errors": [{"reason": "authError"}]}}"#;
+        let error = error_from(401, body)
+            .await
+            .with_correlation_id(Some("abc".into()));
+
+        assert_eq!(error.reasons(), ["authError"]);
+        assert_eq!(
+            error.to_string(),
+            "Google API error 401 Unauthorized (UNAUTHENTICATED): Invalid token [redacted] \
+             [request id abc]"
+        );
+        assert!(!format!("{error:?}").contains("ya29."));
+    }
+
+    #[tokio::test]
+    async fn redacts_plain_string_body() {
+        let error = error_from(403, r#""API key AIzaSyD-key_123 is invalid""#).await;
+
+        assert_eq!(error.message(), "API key [redacted] is invalid");
+    }
+
+    #[test]
+    fn parses_retry_after_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn parses_retry_after_date() {
+        let date = (Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let delay = parse_retry_after(&date).unwrap();
+        assert!(delay > Duration::from_secs(80) && delay <= Duration::from_secs(90));
+
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_retry_after() {
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("-5"), None);
+    }
+}
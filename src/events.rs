@@ -0,0 +1,33 @@
+use std::error::Error;
+
+use crate::{Google, RefreshedToken, Token};
+
+/// Callbacks invoked on authentication lifecycle events, e.g. to write audit logs or raise
+/// alerts without wrapping every method of `Google`.
+///
+/// Set with `Google::with_auth_events`. Every method does nothing by default, so
+/// implementations only override the events they care about. The callbacks run inline and
+/// should return quickly.
+pub trait AuthEvents: Send + Sync {
+    /// Called when `Google::get_token` exchanged an authorization code, which also
+    /// happens through `get_userinfo` and `authenticate`.
+    fn on_login(&self, _token: &Token) {}
+
+    /// Called when `Google::refresh_token` obtained a new access token.
+    fn on_token_refreshed(&self, _refreshed: &RefreshedToken) {}
+
+    /// Called when `Google::refresh_token` failed, e.g. because the user revoked access.
+    fn on_refresh_failed(&self, _error: &dyn Error) {}
+
+    /// Called when `Google::revoke_token` revoked a token.
+    fn on_revoked(&self, _token: &Token) {}
+}
+
+impl Google {
+    /// Invokes `event` on the configured `AuthEvents`, if any.
+    pub(crate) fn emit(&self, event: impl FnOnce(&dyn AuthEvents)) {
+        if let Some(events) = &self.auth_events {
+            event(events.as_ref());
+        }
+    }
+}
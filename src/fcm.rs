@@ -0,0 +1,266 @@
+//! Firebase Cloud Messaging HTTP v1 API, for sending push notifications.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::encode_path;
+use crate::{Google, GoogleError, Token};
+
+/// Scope granting permission to send messages.
+pub const SCOPE_MESSAGING: &str = "https://www.googleapis.com/auth/firebase.messaging";
+
+const FCM_API: &str = "https://fcm.googleapis.com/v1";
+
+/// A client for the FCM v1 API.
+///
+/// FCM only accepts service account credentials, e.g. a token from
+/// `Google::get_service_account_token` for the Firebase project's service account.
+#[derive(Clone, Copy)]
+pub struct Fcm<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A message to send, created with `Message::to_token`, `to_topic` or `to_condition`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    #[serde(flatten)]
+    pub target: Target,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification: Option<Notification>,
+    /// Key/value pairs delivered to the app.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub data: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub android: Option<AndroidConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apns: Option<ApnsConfig>,
+}
+
+/// Who a message is sent to.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Target {
+    /// A single app instance, by its registration token.
+    Token(String),
+    /// All instances subscribed to a topic, by its name without the `/topics/` prefix.
+    Topic(String),
+    /// A boolean expression over topics, e.g. `'news' in topics && 'sports' in topics`.
+    Condition(String),
+}
+
+/// The notification shown by the system on all platforms.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// The URL of an image to show with the notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+/// Android specific options.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AndroidConfig {
+    /// Messages with the same collapse key replace each other while the device is offline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapse_key: Option<String>,
+    /// `NORMAL` or `HIGH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// How long the message is kept while the device is offline, e.g. `3600s`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<String>,
+    /// Overrides `Message::data` on Android.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub data: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification: Option<AndroidNotification>,
+}
+
+/// Android specific notification options, overriding `Message::notification`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AndroidNotification {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// The icon color as `#rrggbb`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound: Option<String>,
+    /// Notifications with the same tag replace each other in the notification drawer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click_action: Option<String>,
+}
+
+/// Apple Push Notification service specific options.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApnsConfig {
+    /// APNs request headers, e.g. `apns-priority` or `apns-expiration`.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// The APNs payload, including the `aps` dictionary, e.g.
+    /// `json!({ "aps": { "badge": 1, "sound": "default" } })`.
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub payload: Value,
+}
+
+/// The FCM specific error codes, as reported by `GoogleError::fcm_error_code`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FcmErrorCode {
+    /// The registration token is no longer valid, e.g. because the app was uninstalled.
+    /// The token should be removed from storage.
+    Unregistered,
+    InvalidArgument,
+    /// The token belongs to a different Firebase project.
+    SenderIdMismatch,
+    QuotaExceeded,
+    Unavailable,
+    Internal,
+    /// The APNs certificate or web push key is invalid or missing.
+    ThirdPartyAuthError,
+}
+
+#[derive(Deserialize)]
+struct SendResponse {
+    name: String,
+}
+
+impl Message {
+    /// Creates a message to a single app instance.
+    pub fn to_token(token: impl Into<String>) -> Message {
+        Message::new(Target::Token(token.into()))
+    }
+
+    /// Creates a message to all instances subscribed to `topic`.
+    pub fn to_topic(topic: impl Into<String>) -> Message {
+        Message::new(Target::Topic(topic.into()))
+    }
+
+    /// Creates a message to all instances matching a topic condition.
+    pub fn to_condition(condition: impl Into<String>) -> Message {
+        Message::new(Target::Condition(condition.into()))
+    }
+
+    fn new(target: Target) -> Message {
+        Message {
+            target,
+            notification: None,
+            data: HashMap::new(),
+            android: None,
+            apns: None,
+        }
+    }
+}
+
+impl FcmErrorCode {
+    fn from_reason(reason: &str) -> Option<FcmErrorCode> {
+        match reason {
+            "UNREGISTERED" => Some(FcmErrorCode::Unregistered),
+            "INVALID_ARGUMENT" => Some(FcmErrorCode::InvalidArgument),
+            "SENDER_ID_MISMATCH" => Some(FcmErrorCode::SenderIdMismatch),
+            "QUOTA_EXCEEDED" => Some(FcmErrorCode::QuotaExceeded),
+            "UNAVAILABLE" => Some(FcmErrorCode::Unavailable),
+            "INTERNAL" => Some(FcmErrorCode::Internal),
+            "THIRD_PARTY_AUTH_ERROR" => Some(FcmErrorCode::ThirdPartyAuthError),
+            _ => None,
+        }
+    }
+}
+
+impl GoogleError {
+    /// The FCM error code of a failed `Fcm::send`, if FCM reported one.
+    pub fn fcm_error_code(&self) -> Option<FcmErrorCode> {
+        self.reasons()
+            .iter()
+            .find_map(|reason| FcmErrorCode::from_reason(reason))
+    }
+
+    /// Returns `true` if the message could not be delivered because its registration token
+    /// is no longer valid.
+    pub fn is_unregistered(&self) -> bool {
+        self.fcm_error_code() == Some(FcmErrorCode::Unregistered)
+    }
+}
+
+impl Google {
+    /// Returns an FCM API client that authorizes its requests with `token`.
+    pub fn fcm<'a>(&'a self, token: &'a Token) -> Fcm<'a> {
+        Fcm {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl Fcm<'_> {
+    /// Sends a message, returning its id, e.g. `projects/p/messages/0:1500415314455276`.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The Firebase project to send from.
+    /// * `message` - The message to send.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. FCM failures are a
+    /// `GoogleError`; check `GoogleError::is_unregistered` to prune stale tokens.
+    pub async fn send(
+        &self,
+        project_id: &str,
+        message: &Message,
+    ) -> Result<String, Box<dyn Error>> {
+        self.post(project_id, message, false).await
+    }
+
+    /// Validates a message without delivering it.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the message is invalid or the request fails.
+    pub async fn validate(
+        &self,
+        project_id: &str,
+        message: &Message,
+    ) -> Result<String, Box<dyn Error>> {
+        self.post(project_id, message, true).await
+    }
+
+    async fn post(
+        &self,
+        project_id: &str,
+        message: &Message,
+        validate_only: bool,
+    ) -> Result<String, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{FCM_API}/projects/{}/messages:send",
+                encode_path(project_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "message": message, "validate_only": validate_only }));
+        let response: SendResponse = self.google.send_json(request).await?;
+
+        Ok(response.name)
+    }
+}
@@ -0,0 +1,424 @@
+//! Cloud Firestore REST API, for document access without a gRPC stack.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::api::{Page, encode_path, paginate};
+use crate::{Google, Token};
+
+/// Scope granting access to Firestore data.
+pub const SCOPE_DATASTORE: &str = "https://www.googleapis.com/auth/datastore";
+
+const FIRESTORE_API: &str = "https://firestore.googleapis.com/v1";
+
+/// A client for the Firestore API of one database.
+#[derive(Clone, Copy)]
+pub struct Firestore<'a> {
+    google: &'a Google,
+    token: &'a Token,
+    project_id: &'a str,
+    database: &'a str,
+}
+
+/// A Firestore document, with its fields in Firestore's typed `Value` encoding.
+///
+/// Use `Document::data` to convert the fields into a serde type.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Document {
+    /// The full resource name, e.g.
+    /// `projects/p/databases/(default)/documents/users/alice`.
+    pub name: String,
+    pub fields: Map<String, Value>,
+    pub create_time: Option<DateTime<Utc>>,
+    pub update_time: Option<DateTime<Utc>>,
+}
+
+/// A page of documents returned by `documents.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DocumentsPage {
+    pub documents: Vec<Document>,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for DocumentsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// A page of collection ids returned by `documents.listCollectionIds`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CollectionIdsPage {
+    pub collection_ids: Vec<String>,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for CollectionIdsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Document {
+    /// The id of the document, the last segment of its name.
+    pub fn id(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or_default()
+    }
+
+    /// Converts the fields of the document into `T`, as described for `from_fields`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the fields cannot be deserialized into `T`.
+    pub fn data<T: DeserializeOwned>(&self) -> Result<T, Box<dyn Error>> {
+        from_fields(&self.fields)
+    }
+}
+
+/// Encodes a serializable value as Firestore document fields.
+///
+/// `T` has to serialize to a JSON object. Integers become `integerValue`, other numbers
+/// `doubleValue`, sequences `arrayValue` and nested objects `mapValue`.
+///
+/// # Errors
+///
+/// This function returns an error if `value` does not serialize to an object.
+pub fn to_fields<T: Serialize>(value: &T) -> Result<Map<String, Value>, Box<dyn Error>> {
+    match serde_json::to_value(value)? {
+        Value::Object(object) => Ok(object
+            .into_iter()
+            .map(|(name, value)| (name, encode_value(value)))
+            .collect()),
+        _ => Err("Firestore documents have to serialize to an object".into()),
+    }
+}
+
+/// Decodes Firestore document fields into a deserializable value.
+///
+/// Integers and doubles become numbers, timestamps RFC 3339 strings, bytes base64 strings,
+/// references resource name strings and geo points `{ latitude, longitude }` objects.
+///
+/// # Errors
+///
+/// This function returns an error if the fields cannot be deserialized into `T`.
+pub fn from_fields<T: DeserializeOwned>(fields: &Map<String, Value>) -> Result<T, Box<dyn Error>> {
+    let object: Map<String, Value> = fields
+        .iter()
+        .map(|(name, value)| (name.clone(), decode_value(value)))
+        .collect();
+
+    Ok(serde_json::from_value(Value::Object(object))?)
+}
+
+/// Converts a JSON value into Firestore's `Value` encoding.
+fn encode_value(value: Value) -> Value {
+    match value {
+        Value::Null => json!({ "nullValue": null }),
+        Value::Bool(value) => json!({ "booleanValue": value }),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => json!({ "integerValue": integer.to_string() }),
+            None => json!({ "doubleValue": number.as_f64() }),
+        },
+        Value::String(value) => json!({ "stringValue": value }),
+        Value::Array(values) => json!({
+            "arrayValue": {
+                "values": values.into_iter().map(encode_value).collect::<Vec<_>>(),
+            },
+        }),
+        Value::Object(object) => json!({
+            "mapValue": {
+                "fields": object
+                    .into_iter()
+                    .map(|(name, value)| (name, encode_value(value)))
+                    .collect::<Map<_, _>>(),
+            },
+        }),
+    }
+}
+
+/// Converts a value in Firestore's `Value` encoding into plain JSON.
+fn decode_value(value: &Value) -> Value {
+    let Some((kind, inner)) = value.as_object().and_then(|object| object.iter().next()) else {
+        return Value::Null;
+    };
+
+    match kind.as_str() {
+        "integerValue" => match inner {
+            Value::String(text) => text.parse::<i64>().map(Value::from).unwrap_or(Value::Null),
+            other => other.clone(),
+        },
+        "arrayValue" => Value::Array(
+            inner
+                .get("values")
+                .and_then(Value::as_array)
+                .map(|values| values.iter().map(decode_value).collect())
+                .unwrap_or_default(),
+        ),
+        "mapValue" => Value::Object(
+            inner
+                .get("fields")
+                .and_then(Value::as_object)
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .map(|(name, value)| (name.clone(), decode_value(value)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        _ => inner.clone(),
+    }
+}
+
+impl Google {
+    /// Returns a Firestore API client for the `(default)` database of `project_id`, that
+    /// authorizes its requests with `token`.
+    pub fn firestore<'a>(&'a self, token: &'a Token, project_id: &'a str) -> Firestore<'a> {
+        Firestore {
+            google: self,
+            token,
+            project_id,
+            database: "(default)",
+        }
+    }
+}
+
+impl<'a> Firestore<'a> {
+    /// Uses a named database instead of `(default)`.
+    pub fn database(mut self, database: &'a str) -> Firestore<'a> {
+        self.database = database;
+        self
+    }
+
+    /// Fetches a document.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the document relative to the database root, e.g.
+    ///   `users/alice` or `users/alice/orders/1`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, including a `GoogleError` with
+    /// status 404 when the document does not exist.
+    pub async fn get_document(&self, path: &str) -> Result<Document, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(self.documents_url(path))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Creates a document in a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The path of the collection, e.g. `users` or `users/alice/orders`.
+    /// * `document_id` - The id of the new document, `None` to let Firestore assign one.
+    /// * `data` - The content of the document, encoded with `to_fields`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, including a `GoogleError` with
+    /// status 409 when a document with the same id exists.
+    pub async fn create_document<T: Serialize>(
+        &self,
+        collection: &str,
+        document_id: Option<&str>,
+        data: &T,
+    ) -> Result<Document, Box<dyn Error>> {
+        let mut request = self
+            .google
+            .http
+            .post(self.documents_url(collection))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "fields": to_fields(data)? }));
+
+        if let Some(document_id) = document_id {
+            request = request.query(&[("documentId", document_id)]);
+        }
+
+        self.google.send_json(request).await
+    }
+
+    /// Updates a document, creating it if it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the document relative to the database root.
+    /// * `data` - The new content of the document, encoded with `to_fields`.
+    /// * `update_mask` - The field paths to update, leaving all other fields untouched.
+    ///   `None` replaces the whole document. Fields listed here but missing from `data`
+    ///   are deleted.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn patch_document<T: Serialize>(
+        &self,
+        path: &str,
+        data: &T,
+        update_mask: Option<&[&str]>,
+    ) -> Result<Document, Box<dyn Error>> {
+        let params: Vec<_> = update_mask
+            .unwrap_or_default()
+            .iter()
+            .map(|field| ("updateMask.fieldPaths", *field))
+            .collect();
+
+        let request = self
+            .google
+            .http
+            .patch(self.documents_url(path))
+            .bearer_auth(self.token.access_token())
+            .query(&params)
+            .json(&json!({ "fields": to_fields(data)? }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Deletes a document. Its subcollections are not deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the document relative to the database root.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. Deleting a document that
+    /// does not exist succeeds.
+    pub async fn delete_document(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .delete(self.documents_url(path))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Lists a single page of the documents in a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The path of the collection relative to the database root.
+    /// * `page_size` - The maximum number of documents per page.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_documents(
+        &self,
+        collection: &str,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<DocumentsPage, Box<dyn Error>> {
+        let mut params = Vec::new();
+        if let Some(page_size) = page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(self.documents_url(collection))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the documents in a collection.
+    pub fn documents<'s>(
+        self,
+        collection: &'s str,
+        page_size: Option<u32>,
+    ) -> impl Stream<Item = Result<DocumentsPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_documents(collection, page_size, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Lists a single page of the collection ids below a document, or the root
+    /// collections of the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The path of the parent document, `None` for the database root.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_collection_ids(
+        &self,
+        parent: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<CollectionIdsPage, Box<dyn Error>> {
+        let mut body = json!({});
+        if let Some(page_token) = page_token {
+            body["pageToken"] = json!(page_token);
+        }
+
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{}:listCollectionIds",
+                self.documents_url(parent.unwrap_or_default())
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&body);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the collection ids below a document, or the root collections
+    /// of the database.
+    pub fn collection_ids<'s>(
+        self,
+        parent: Option<&'s str>,
+    ) -> impl Stream<Item = Result<CollectionIdsPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_collection_ids(parent, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// The URL of a document or collection, with each path segment percent-encoded.
+    fn documents_url(&self, path: &str) -> String {
+        let mut url = format!(
+            "{FIRESTORE_API}/projects/{}/databases/{}/documents",
+            encode_path(self.project_id),
+            self.database
+        );
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            url.push('/');
+            url.push_str(&encode_path(segment));
+        }
+        url
+    }
+}
@@ -0,0 +1,213 @@
+//! Google Forms API.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::{Page, paginate};
+use crate::{Google, Token};
+
+const FORMS_API: &str = "https://forms.googleapis.com/v1/forms";
+
+/// Scope granting read-only access to form structure.
+pub const SCOPE_BODY_READONLY: &str = "https://www.googleapis.com/auth/forms.body.readonly";
+
+/// Scope granting read-only access to form responses.
+pub const SCOPE_RESPONSES_READONLY: &str =
+    "https://www.googleapis.com/auth/forms.responses.readonly";
+
+/// A client for the Forms API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Forms<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A form and its items.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Form {
+    pub form_id: String,
+    pub info: FormInfo,
+    pub items: Vec<Item>,
+    pub revision_id: Option<String>,
+    /// The URL respondents fill the form at.
+    pub responder_uri: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormInfo {
+    pub title: String,
+    pub document_title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// An item of a form: a question, a group of questions, or layout such as a page break.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Item {
+    pub item_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub question_item: Option<QuestionItem>,
+    pub question_group_item: Option<QuestionGroupItem>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuestionItem {
+    pub question: Question,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuestionGroupItem {
+    pub questions: Vec<Question>,
+}
+
+/// A question. The question kind, e.g. `choiceQuestion` or `textQuestion`, is kept as raw
+/// JSON in `kind`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Question {
+    /// The id answers refer to in `FormResponse::answers`.
+    pub question_id: String,
+    pub required: bool,
+    #[serde(flatten)]
+    pub kind: HashMap<String, Value>,
+}
+
+/// A submitted response to a form.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormResponse {
+    pub response_id: String,
+    pub create_time: Option<DateTime<Utc>>,
+    pub last_submitted_time: Option<DateTime<Utc>>,
+    /// Only set when the form collects email addresses.
+    pub respondent_email: Option<String>,
+    /// Answers keyed by question id.
+    pub answers: HashMap<String, Answer>,
+    pub total_score: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Answer {
+    pub question_id: String,
+    pub text_answers: Option<TextAnswers>,
+    pub file_upload_answers: Option<Value>,
+    pub grade: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextAnswers {
+    pub answers: Vec<TextAnswer>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextAnswer {
+    pub value: String,
+}
+
+/// A page of responses returned by `forms.responses.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormResponseList {
+    pub responses: Vec<FormResponse>,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for FormResponseList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Answer {
+    /// Returns the text values of the answer, e.g. the selected choices.
+    pub fn values(&self) -> Vec<&str> {
+        self.text_answers
+            .iter()
+            .flat_map(|answers| &answers.answers)
+            .map(|answer| answer.value.as_str())
+            .collect()
+    }
+}
+
+impl Google {
+    /// Returns a Forms API client that authorizes its requests with `token`.
+    pub fn forms<'a>(&'a self, token: &'a Token) -> Forms<'a> {
+        Forms {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Forms<'a> {
+    /// Fetches a form with all its items.
+    pub async fn get_form(&self, form_id: &str) -> Result<Form, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{FORMS_API}/{form_id}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Lists a single page of responses.
+    ///
+    /// # Arguments
+    ///
+    /// * `form_id` - The form to list responses of.
+    /// * `since` - Only responses submitted after this time, for incremental ingestion.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    pub async fn list_responses(
+        &self,
+        form_id: &str,
+        since: Option<DateTime<Utc>>,
+        page_token: Option<&str>,
+    ) -> Result<FormResponseList, Box<dyn Error>> {
+        let mut params = Vec::new();
+        if let Some(since) = since {
+            let since = since.to_rfc3339_opts(SecondsFormat::Secs, true);
+            params.push(("filter", format!("timestamp > {since}")));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{FORMS_API}/{form_id}/responses"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of responses submitted after `since`.
+    pub fn responses<'s>(
+        self,
+        form_id: &'s str,
+        since: Option<DateTime<Utc>>,
+    ) -> impl Stream<Item = Result<FormResponseList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_responses(form_id, since, page_token.as_deref())
+                .await
+        })
+    }
+}
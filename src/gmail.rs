@@ -0,0 +1,754 @@
+//! Gmail API, acting on the mailbox of the signed-in user.
+
+use std::error::Error;
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE, URL_SAFE_NO_PAD};
+use futures_util::Stream;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::{Page, encode_path, paginate};
+use crate::de::{number_from_string, option_number_from_string};
+use crate::pubsub::PushRequest;
+use crate::{Google, Token};
+
+const GMAIL_API: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
+/// The most bytes of text an RFC 2047 encoded-word holds: 45 bytes encode to 60 base64
+/// characters, which with the delimiters stay under the 75 characters allowed.
+const ENCODED_WORD_BYTES: usize = 45;
+
+/// Scope required to send messages on behalf of the user.
+pub const SCOPE_SEND: &str = "https://www.googleapis.com/auth/gmail.send";
+
+/// Scope required to list and read messages.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/gmail.readonly";
+
+/// A client for the Gmail API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Gmail<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A Gmail message resource. Which fields are populated depends on the `MessageFormat` it
+/// was fetched with; listings only carry `id` and `thread_id`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Message {
+    pub id: String,
+    pub thread_id: String,
+    pub label_ids: Vec<String>,
+    pub snippet: Option<String>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub history_id: Option<u64>,
+    /// Milliseconds since the epoch at which Gmail received the message, as a string.
+    pub internal_date: Option<String>,
+    pub size_estimate: Option<u64>,
+    pub payload: Option<MessagePart>,
+    /// The base64url encoded RFC 2822 message, for `MessageFormat::Raw`.
+    pub raw: Option<String>,
+}
+
+/// A single MIME part of a message.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MessagePart {
+    pub part_id: String,
+    pub mime_type: String,
+    pub filename: String,
+    pub headers: Vec<Header>,
+    pub body: MessagePartBody,
+    pub parts: Vec<MessagePart>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+/// The body of a message part. Attachments only carry an `attachment_id`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MessagePartBody {
+    pub attachment_id: Option<String>,
+    pub size: u64,
+    /// The base64url encoded body data.
+    pub data: Option<String>,
+}
+
+/// The format `Gmail::get_message` returns a message in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// The full parsed message, including the payload with all parts.
+    Full,
+    /// Only the message headers, in `payload.headers`.
+    Metadata,
+    /// Only ids, labels and the snippet.
+    Minimal,
+    /// The full RFC 2822 message in `raw`.
+    Raw,
+}
+
+/// Options for listing messages.
+#[derive(Debug, Clone, Default)]
+pub struct MessageQuery {
+    /// A Gmail search query, using the same syntax as the Gmail search box, e.g.
+    /// `from:someone@example.com is:unread`.
+    pub q: Option<String>,
+    /// Only return messages carrying all of these labels, e.g. `INBOX`.
+    pub label_ids: Vec<String>,
+    /// The number of messages per page, at most 500.
+    pub max_results: Option<u32>,
+    /// Include messages from `SPAM` and `TRASH`.
+    pub include_spam_trash: bool,
+}
+
+/// A page of messages returned by `messages.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MessageList {
+    pub messages: Vec<Message>,
+    pub next_page_token: Option<String>,
+    pub result_size_estimate: u32,
+}
+
+impl Page for MessageList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// The response of `users.watch`. The watch must be renewed before `expiration`, at least
+/// once a week.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WatchResponse {
+    #[serde(deserialize_with = "number_from_string")]
+    pub history_id: u64,
+    /// Milliseconds since the epoch at which the watch expires, as a string.
+    pub expiration: String,
+}
+
+/// A mailbox change notification delivered through a Cloud Pub/Sub push subscription.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Notification {
+    pub email_address: String,
+    /// The mailbox history id after the change. Pass the previously stored id to
+    /// `list_history` to fetch what changed.
+    #[serde(deserialize_with = "number_from_string")]
+    pub history_id: u64,
+}
+
+/// A page of mailbox history records returned by `history.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryList {
+    pub history: Vec<History>,
+    pub next_page_token: Option<String>,
+    /// The current history id of the mailbox.
+    #[serde(deserialize_with = "number_from_string")]
+    pub history_id: u64,
+}
+
+impl Page for HistoryList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+/// A single mailbox change.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct History {
+    pub id: String,
+    pub messages: Vec<Message>,
+    pub messages_added: Vec<HistoryMessage>,
+    pub messages_deleted: Vec<HistoryMessage>,
+    pub labels_added: Vec<HistoryLabel>,
+    pub labels_removed: Vec<HistoryLabel>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryMessage {
+    pub message: Message,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryLabel {
+    pub message: Message,
+    pub label_ids: Vec<String>,
+}
+
+impl Notification {
+    /// Decodes the body of a Cloud Pub/Sub push request carrying a Gmail notification.
+    ///
+    /// Use `Google::verify_push` instead to authenticate the request as well, then parse
+    /// the message data with `serde_json`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the body is not a Pub/Sub push envelope or its
+    /// data is not a Gmail notification.
+    pub fn from_push(body: &[u8]) -> Result<Notification, Box<dyn Error>> {
+        let request = PushRequest::from_body(body)?;
+
+        Ok(serde_json::from_slice(&request.message.data)?)
+    }
+}
+
+impl MessageFormat {
+    fn as_str(self) -> &'static str {
+        match self {
+            MessageFormat::Full => "full",
+            MessageFormat::Metadata => "metadata",
+            MessageFormat::Minimal => "minimal",
+            MessageFormat::Raw => "raw",
+        }
+    }
+}
+
+impl Message {
+    /// Returns the value of the first header named `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.payload
+            .as_ref()
+            .and_then(|payload| payload.header(name))
+    }
+}
+
+impl MessagePart {
+    /// Returns the value of the first header named `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+
+    /// Finds the first part, depth first and including this one, with the given MIME type,
+    /// e.g. `text/plain`.
+    pub fn find(&self, mime_type: &str) -> Option<&MessagePart> {
+        if self.mime_type.eq_ignore_ascii_case(mime_type) {
+            return Some(self);
+        }
+        self.parts.iter().find_map(|part| part.find(mime_type))
+    }
+}
+
+impl MessagePartBody {
+    /// Decodes the base64url encoded body data.
+    pub fn decode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = self.data.as_deref().unwrap_or_default();
+        Ok(URL_SAFE_NO_PAD.decode(data.trim_end_matches('='))?)
+    }
+}
+
+/// An email to be sent through `Gmail::send`, encoded as an RFC 2822 message.
+///
+/// The `From` header defaults to the signed-in user when not set.
+#[derive(Debug, Clone, Default)]
+pub struct NewMessage {
+    from: Option<String>,
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    reply_to: Option<String>,
+    subject: String,
+    text: Option<String>,
+    html: Option<String>,
+    thread_id: Option<String>,
+}
+
+impl NewMessage {
+    /// Creates an empty message.
+    pub fn new() -> NewMessage {
+        NewMessage::default()
+    }
+
+    /// Sets the sender, which must be the user's address or one of their send-as aliases.
+    pub fn from(mut self, address: impl Into<String>) -> NewMessage {
+        self.from = Some(address.into());
+        self
+    }
+
+    /// Adds a `To` recipient.
+    pub fn to(mut self, address: impl Into<String>) -> NewMessage {
+        self.to.push(address.into());
+        self
+    }
+
+    /// Adds a `Cc` recipient.
+    pub fn cc(mut self, address: impl Into<String>) -> NewMessage {
+        self.cc.push(address.into());
+        self
+    }
+
+    /// Adds a `Bcc` recipient.
+    pub fn bcc(mut self, address: impl Into<String>) -> NewMessage {
+        self.bcc.push(address.into());
+        self
+    }
+
+    /// Sets the `Reply-To` header.
+    pub fn reply_to(mut self, address: impl Into<String>) -> NewMessage {
+        self.reply_to = Some(address.into());
+        self
+    }
+
+    /// Sets the subject. Non-ASCII subjects are encoded as RFC 2047 encoded-words.
+    pub fn subject(mut self, subject: impl Into<String>) -> NewMessage {
+        self.subject = subject.into();
+        self
+    }
+
+    /// Sets the plain text body.
+    pub fn text(mut self, body: impl Into<String>) -> NewMessage {
+        self.text = Some(body.into());
+        self
+    }
+
+    /// Sets the HTML body. When a plain text body is set too, both are sent as
+    /// `multipart/alternative`.
+    pub fn html(mut self, body: impl Into<String>) -> NewMessage {
+        self.html = Some(body.into());
+        self
+    }
+
+    /// Sends the message as a reply within an existing thread. The subject has to match
+    /// the thread's subject for Gmail to keep the message in it.
+    pub fn thread_id(mut self, thread_id: impl Into<String>) -> NewMessage {
+        self.thread_id = Some(thread_id.into());
+        self
+    }
+
+    /// Renders the message in RFC 2822 format.
+    ///
+    /// Non-ASCII display names of addresses, e.g. `Jürgen <j@example.com>`, and subjects
+    /// are encoded as RFC 2047 encoded-words.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if an address or the subject contains a line break,
+    /// which would let it inject headers or a body into the message.
+    pub fn to_rfc2822(&self) -> Result<String, Box<dyn Error>> {
+        let addresses = self
+            .from
+            .iter()
+            .chain(&self.to)
+            .chain(&self.cc)
+            .chain(&self.bcc)
+            .chain(&self.reply_to);
+        for value in addresses.chain([&self.subject]) {
+            if value.contains(['\r', '\n']) {
+                return Err("Message header contains a line break".into());
+            }
+        }
+
+        let mut message = String::new();
+        let mut header = |name: &str, value: &str| {
+            if !value.is_empty() {
+                message.push_str(&format!("{name}: {value}\r\n"));
+            }
+        };
+
+        header("From", &encode_addresses(self.from.iter()));
+        header("To", &encode_addresses(&self.to));
+        header("Cc", &encode_addresses(&self.cc));
+        header("Bcc", &encode_addresses(&self.bcc));
+        header("Reply-To", &encode_addresses(self.reply_to.iter()));
+        header("Subject", &encode_header(&self.subject));
+        header("MIME-Version", "1.0");
+
+        match (&self.text, &self.html) {
+            (Some(text), Some(html)) => {
+                let boundary = format!("boundary_{}", rand_boundary()?);
+                message.push_str(&format!(
+                    "Content-Type: multipart/alternative; boundary=\"{boundary}\"\r\n\r\n"
+                ));
+                for (content_type, body) in [("text/plain", text), ("text/html", html)] {
+                    message.push_str(&format!("--{boundary}\r\n"));
+                    message.push_str(&body_part(content_type, body));
+                }
+                message.push_str(&format!("--{boundary}--\r\n"));
+            }
+            (None, Some(html)) => message.push_str(&body_part("text/html", html)),
+            (text, None) => message.push_str(&body_part(
+                "text/plain",
+                text.as_deref().unwrap_or_default(),
+            )),
+        }
+
+        Ok(message)
+    }
+}
+
+impl Google {
+    /// Returns a Gmail API client that authorizes its requests with `token`.
+    pub fn gmail<'a>(&'a self, token: &'a Token) -> Gmail<'a> {
+        Gmail {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Gmail<'a> {
+    /// Sends `message` from the signed-in user's mailbox.
+    ///
+    /// The token must have been granted the `gmail.send` scope (`SCOPE_SEND`) or a broader
+    /// Gmail scope.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the message cannot be rendered, see
+    /// `NewMessage::to_rfc2822`, or if the request fails, e.g. because of a missing scope
+    /// or an invalid recipient address.
+    pub async fn send(&self, message: &NewMessage) -> Result<Message, Box<dyn Error>> {
+        let mut body = json!({ "raw": URL_SAFE.encode(message.to_rfc2822()?) });
+        if let Some(thread_id) = &message.thread_id {
+            body["threadId"] = json!(thread_id);
+        }
+
+        let request = self
+            .google
+            .http
+            .post(format!("{GMAIL_API}/messages/send"))
+            .bearer_auth(self.token.access_token())
+            .json(&body);
+
+        self.google.send_json(request).await
+    }
+
+    /// Lists a single page of messages in the user's mailbox.
+    ///
+    /// Listed messages only carry their `id` and `thread_id`; use `get_message` to fetch
+    /// their content. Requires `SCOPE_READONLY` or a broader Gmail scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Search and label filters.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    pub async fn list_messages(
+        &self,
+        query: &MessageQuery,
+        page_token: Option<&str>,
+    ) -> Result<MessageList, Box<dyn Error>> {
+        let mut params = Vec::new();
+        if let Some(q) = &query.q {
+            params.push(("q", q.clone()));
+        }
+        for label_id in &query.label_ids {
+            params.push(("labelIds", label_id.clone()));
+        }
+        if let Some(max_results) = query.max_results {
+            params.push(("maxResults", max_results.to_string()));
+        }
+        if query.include_spam_trash {
+            params.push(("includeSpamTrash", "true".to_string()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{GMAIL_API}/messages"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of messages matching `query`.
+    pub fn messages<'s>(
+        self,
+        query: &'s MessageQuery,
+    ) -> impl Stream<Item = Result<MessageList, Box<dyn Error>>> + 's
+    where
+        Self: 's,
+    {
+        paginate(
+            move |page_token| async move { self.list_messages(query, page_token.as_deref()).await },
+        )
+    }
+
+    /// Fetches a single message.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The message id, as returned by `list_messages`.
+    /// * `format` - How much of the message to return.
+    pub async fn get_message(
+        &self,
+        id: &str,
+        format: MessageFormat,
+    ) -> Result<Message, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{GMAIL_API}/messages/{}", encode_path(id)))
+            .bearer_auth(self.token.access_token())
+            .query(&[("format", format.as_str())]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Starts push notifications for changes to the user's mailbox.
+    ///
+    /// Notifications are published to the Cloud Pub/Sub `topic_name`, which must grant
+    /// `gmail-api-push@system.gserviceaccount.com` the publisher role. Decode them with
+    /// `Notification::from_push`.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_name` - The full topic name, e.g. `projects/my-project/topics/gmail`.
+    /// * `label_ids` - Only notify about changes to these labels, or all changes if empty.
+    pub async fn watch(
+        &self,
+        topic_name: &str,
+        label_ids: &[&str],
+    ) -> Result<WatchResponse, Box<dyn Error>> {
+        let mut body = json!({ "topicName": topic_name });
+        if !label_ids.is_empty() {
+            body["labelIds"] = json!(label_ids);
+            body["labelFilterBehavior"] = json!("include");
+        }
+
+        let request = self
+            .google
+            .http
+            .post(format!("{GMAIL_API}/watch"))
+            .bearer_auth(self.token.access_token())
+            .json(&body);
+
+        self.google.send_json(request).await
+    }
+
+    /// Stops push notifications for the user's mailbox.
+    pub async fn stop(&self) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{GMAIL_API}/stop"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Lists a single page of changes made to the mailbox after `start_history_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_history_id` - The history id stored from the last sync or notification.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. A `GoogleError` with a `404`
+    /// status means `start_history_id` is too old and a full sync is required.
+    pub async fn list_history(
+        &self,
+        start_history_id: u64,
+        page_token: Option<&str>,
+    ) -> Result<HistoryList, Box<dyn Error>> {
+        let mut params = vec![("startHistoryId", start_history_id.to_string())];
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{GMAIL_API}/history"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of changes made to the mailbox after `start_history_id`.
+    pub fn history(
+        self,
+        start_history_id: u64,
+    ) -> impl Stream<Item = Result<HistoryList, Box<dyn Error>>> + 'a {
+        paginate(move |page_token| async move {
+            self.list_history(start_history_id, page_token.as_deref())
+                .await
+        })
+    }
+}
+
+/// Encodes a header value as RFC 2047 encoded-words when it is not plain ASCII. Each
+/// encoded-word is at most 75 characters long, as RFC 2047 requires, and they are folded
+/// onto continuation lines.
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+
+    let mut words = Vec::new();
+    let mut rest = value;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(ENCODED_WORD_BYTES);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        words.push(format!("=?UTF-8?B?{}?=", STANDARD.encode(&rest[..end])));
+        rest = &rest[end..];
+    }
+
+    words.join("\r\n ")
+}
+
+/// Formats a list of addresses, encoding non-ASCII display names as in
+/// `"Jürgen Müller" <j@example.com>`.
+fn encode_addresses<'a>(addresses: impl IntoIterator<Item = &'a String>) -> String {
+    addresses
+        .into_iter()
+        .map(|address| match address.rsplit_once('<') {
+            Some((name, email)) if !name.is_ascii() => {
+                let name = name.trim().trim_matches('"');
+                format!("{} <{email}", encode_header(name))
+            }
+            _ => address.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn body_part(content_type: &str, body: &str) -> String {
+    let encoded = STANDARD.encode(body);
+    let mut part = format!(
+        "Content-Type: {content_type}; charset=\"UTF-8\"\r\n\
+         Content-Transfer-Encoding: base64\r\n\r\n"
+    );
+    for line in encoded.as_bytes().chunks(76) {
+        part.push_str(std::str::from_utf8(line).unwrap_or_default());
+        part.push_str("\r\n");
+    }
+    part
+}
+
+/// Produces a random value unlikely to collide with the message content, for MIME
+/// boundaries.
+fn rand_boundary() -> Result<String, Box<dyn Error>> {
+    let mut bytes = [0; 16];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate a MIME boundary")?;
+
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_words(value: &str) -> String {
+        let bytes: Vec<u8> = value
+            .split_whitespace()
+            .flat_map(|word| {
+                let encoded = word.strip_prefix("=?UTF-8?B?").unwrap().strip_suffix("?=");
+                STANDARD.decode(encoded.unwrap()).unwrap()
+            })
+            .collect();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn rejects_line_breaks_in_headers() {
+        let messages = [
+            NewMessage::new().to("a@example.com\r\nBcc: victim@example.com"),
+            NewMessage::new().cc("a@example.com\nX-Injected: 1"),
+            NewMessage::new().from("me@example.com\r"),
+            NewMessage::new().reply_to("a@example.com\r\n\r\nbody"),
+            NewMessage::new().subject("Hello\r\nContent-Type: text/html"),
+        ];
+        for message in messages {
+            assert!(message.to_rfc2822().is_err(), "{message:?}");
+        }
+    }
+
+    #[test]
+    fn encodes_non_ascii_display_names() {
+        let message = NewMessage::new()
+            .from("\"Jürgen Müller\" <j@example.com>")
+            .to("Plain Name <p@example.com>")
+            .to("Zoë <z@example.com>")
+            .to("bare@example.com")
+            .to_rfc2822()
+            .unwrap();
+
+        let from = format!(
+            "From: {} <j@example.com>\r\n",
+            encode_header("Jürgen Müller")
+        );
+        assert!(message.contains(&from), "{message}");
+        let to = format!(
+            "To: Plain Name <p@example.com>, {} <z@example.com>, bare@example.com\r\n",
+            encode_header("Zoë")
+        );
+        assert!(message.contains(&to), "{message}");
+        assert_eq!(
+            decode_words(&encode_header("Jürgen Müller")),
+            "Jürgen Müller"
+        );
+    }
+
+    #[test]
+    fn splits_long_subjects_into_short_encoded_words() {
+        let subject =
+            "Größenänderung der Rechnung für März — bitte prüfen und bestätigen ✓".repeat(3);
+        let encoded = encode_header(&subject);
+
+        let words: Vec<&str> = encoded.split("\r\n ").collect();
+        assert!(words.len() > 1);
+        assert!(words.iter().all(|word| word.len() <= 75), "{encoded}");
+        assert_eq!(decode_words(&encoded), subject);
+
+        let message = NewMessage::new().subject(&subject).to_rfc2822().unwrap();
+        assert!(message.contains(&format!("Subject: {encoded}\r\n")));
+    }
+
+    #[test]
+    fn keeps_ascii_subjects() {
+        assert_eq!(encode_header("Weekly report"), "Weekly report");
+    }
+
+    #[test]
+    fn decodes_documented_notification() {
+        let data =
+            STANDARD.encode(r#"{"emailAddress": "user@example.com", "historyId": "9876543210"}"#);
+        let body = json!({
+            "message": { "data": data, "messageId": "2070443601311540" },
+            "subscription": "projects/myproject/subscriptions/mysubscription",
+        });
+
+        let notification = Notification::from_push(body.to_string().as_bytes()).unwrap();
+
+        assert_eq!(notification.email_address, "user@example.com");
+        assert_eq!(notification.history_id, 9876543210);
+    }
+
+    #[test]
+    fn decodes_history_ids_as_numbers() {
+        let history: HistoryList = serde_json::from_value(json!({
+            "history": [{ "id": "1", "messages": [{ "id": "m", "historyId": "41" }] }],
+            "historyId": "42",
+        }))
+        .unwrap();
+
+        assert_eq!(history.history_id, 42);
+        assert_eq!(history.history[0].messages[0].history_id, Some(41));
+    }
+}
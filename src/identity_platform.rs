@@ -0,0 +1,267 @@
+//! Identity Platform, for verifying the ID tokens of its users and looking up their accounts.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::api::encode_path;
+use crate::de::option_number_from_string;
+use crate::jwt::CLOCK_SKEW;
+use crate::{Google, Token};
+
+const IDENTITY_TOOLKIT_API: &str = "https://identitytoolkit.googleapis.com/v1";
+
+/// The public keys Identity Platform signs ID tokens with.
+const SECURE_TOKEN_CERTS_URL: &str =
+    "https://www.googleapis.com/service_accounts/v1/jwk/securetoken@system.gserviceaccount.com";
+
+/// Scope granting administrative access to the users of Identity Platform.
+pub const SCOPE_IDENTITY_TOOLKIT: &str = "https://www.googleapis.com/auth/identitytoolkit";
+
+/// A client for the Identity Platform admin API, usually authorized with a service account
+/// token with `SCOPE_IDENTITY_TOOLKIT`.
+#[derive(Clone, Copy)]
+pub struct IdentityPlatform<'a> {
+    google: &'a Google,
+    token: &'a Token,
+    project_id: &'a str,
+    tenant_id: Option<&'a str>,
+}
+
+/// The claims of a verified Identity Platform ID token.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct IdentityPlatformClaims {
+    /// The uid of the user.
+    pub sub: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub phone_number: Option<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    /// When the user last signed in, in seconds since the epoch.
+    pub auth_time: i64,
+    pub iat: i64,
+    pub exp: i64,
+    pub firebase: FirebaseClaims,
+    /// Any other claim, including custom claims set on the account.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FirebaseClaims {
+    /// E.g. `password`, `google.com` or `anonymous`.
+    pub sign_in_provider: String,
+    /// The tenant of the user, for multi-tenant projects.
+    pub tenant: Option<String>,
+    /// The identifiers of the user at each provider.
+    pub identities: Map<String, Value>,
+}
+
+/// An Identity Platform user account.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccountInfo {
+    /// The uid of the user.
+    pub local_id: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub display_name: Option<String>,
+    pub photo_url: Option<String>,
+    pub phone_number: Option<String>,
+    pub disabled: bool,
+    pub tenant_id: Option<String>,
+    pub provider_user_info: Vec<ProviderUserInfo>,
+    /// The custom claims as a JSON object string, see `AccountInfo::custom_claims`.
+    pub custom_attributes: Option<String>,
+    /// The creation time in milliseconds since the epoch.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub created_at: Option<i64>,
+    /// The last sign-in time in milliseconds since the epoch.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub last_login_at: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderUserInfo {
+    pub provider_id: String,
+    /// The id of the user at the provider.
+    pub raw_id: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub photo_url: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LookupResponse {
+    users: Vec<AccountInfo>,
+}
+
+impl AccountInfo {
+    /// Parses the custom claims of the account, empty when none are set.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the custom attributes are not a JSON object.
+    pub fn custom_claims(&self) -> Result<Map<String, Value>, Box<dyn Error>> {
+        match &self.custom_attributes {
+            Some(attributes) => Ok(serde_json::from_str(attributes)?),
+            None => Ok(Map::new()),
+        }
+    }
+
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at.and_then(DateTime::from_timestamp_millis)
+    }
+
+    pub fn last_login_at(&self) -> Option<DateTime<Utc>> {
+        self.last_login_at.and_then(DateTime::from_timestamp_millis)
+    }
+}
+
+impl Google {
+    /// Verifies an ID token issued by Identity Platform to a signed-in user.
+    ///
+    /// The token must be signed by Identity Platform, issued for `project_id`, unexpired,
+    /// have a non-empty subject and an `auth_time` that is not in the future. It does not
+    /// check whether the account was disabled or its tokens revoked since; use
+    /// `IdentityPlatform::get_account` for that.
+    ///
+    /// # Arguments
+    ///
+    /// * `id_token` - The ID token sent by the client.
+    /// * `project_id` - The Google Cloud project of Identity Platform.
+    /// * `tenant_id` - The tenant the user must belong to, or `None` to only accept users
+    ///   of the project level, outside of any tenant.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the token is malformed, fails any of the checks
+    /// above or belongs to another tenant, or if the signing keys cannot be fetched.
+    pub async fn verify_identity_platform_token(
+        &self,
+        id_token: &str,
+        project_id: &str,
+        tenant_id: Option<&str>,
+    ) -> Result<IdentityPlatformClaims, Box<dyn Error>> {
+        let issuer = format!("https://securetoken.google.com/{project_id}");
+        let claims: IdentityPlatformClaims = self
+            .verify_jwt(id_token, SECURE_TOKEN_CERTS_URL, &[&issuer], project_id)
+            .await?;
+
+        if claims.sub.is_empty() {
+            return Err("ID token has no subject".into());
+        }
+        check_auth_time(claims.auth_time, Utc::now().timestamp())?;
+        if claims.firebase.tenant.as_deref() != tenant_id {
+            return Err("ID token belongs to another tenant".into());
+        }
+
+        Ok(claims)
+    }
+
+    /// Returns an Identity Platform admin API client for the users of `project_id` outside
+    /// of any tenant, that authorizes its requests with `token`.
+    pub fn identity_platform<'a>(
+        &'a self,
+        token: &'a Token,
+        project_id: &'a str,
+    ) -> IdentityPlatform<'a> {
+        IdentityPlatform {
+            google: self,
+            token,
+            project_id,
+            tenant_id: None,
+        }
+    }
+}
+
+impl<'a> IdentityPlatform<'a> {
+    /// Manages the users of a tenant instead.
+    pub fn tenant(mut self, tenant_id: &'a str) -> IdentityPlatform<'a> {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    /// Looks up the accounts with the given uids. Unknown uids are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn lookup_accounts(&self, uids: &[&str]) -> Result<Vec<AccountInfo>, Box<dyn Error>> {
+        self.lookup(json!({ "localId": uids })).await
+    }
+
+    /// Looks up the accounts with the given email addresses. Unknown addresses are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn lookup_accounts_by_email(
+        &self,
+        emails: &[&str],
+    ) -> Result<Vec<AccountInfo>, Box<dyn Error>> {
+        self.lookup(json!({ "email": emails })).await
+    }
+
+    /// Gets the account with the given uid, `None` if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_account(&self, uid: &str) -> Result<Option<AccountInfo>, Box<dyn Error>> {
+        let accounts = self.lookup_accounts(&[uid]).await?;
+
+        Ok(accounts.into_iter().next())
+    }
+
+    async fn lookup(&self, mut body: Value) -> Result<Vec<AccountInfo>, Box<dyn Error>> {
+        if let Some(tenant_id) = self.tenant_id {
+            body["tenantId"] = tenant_id.into();
+        }
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{IDENTITY_TOOLKIT_API}/projects/{}/accounts:lookup",
+                encode_path(self.project_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&body);
+        let response: LookupResponse = self.google.send_json(request).await?;
+
+        Ok(response.users)
+    }
+}
+
+/// Rejects an `auth_time` later than `now`, beyond the tolerated clock skew.
+fn check_auth_time(auth_time: i64, now: i64) -> Result<(), Box<dyn Error>> {
+    if auth_time - CLOCK_SKEW > now {
+        return Err("ID token is authenticated in the future".into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_past_auth_times() {
+        assert!(check_auth_time(1_700_000_000, 1_700_000_100).is_ok());
+        assert!(check_auth_time(1_700_000_000 + CLOCK_SKEW, 1_700_000_000).is_ok());
+    }
+
+    #[test]
+    fn rejects_future_auth_times() {
+        assert!(check_auth_time(1_700_000_001 + CLOCK_SKEW, 1_700_000_000).is_err());
+    }
+}
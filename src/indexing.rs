@@ -0,0 +1,116 @@
+//! Indexing API, for notifying Google about added, updated or removed pages.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{Google, Token};
+
+/// Scope granting permission to submit URL notifications.
+pub const SCOPE_INDEXING: &str = "https://www.googleapis.com/auth/indexing";
+
+const INDEXING_API: &str = "https://indexing.googleapis.com/v3";
+
+/// A client for the Indexing API.
+///
+/// The API only accepts service account tokens, e.g. from
+/// `Google::get_service_account_token`, for a service account added as an owner of the
+/// site in Search Console.
+#[derive(Clone, Copy)]
+pub struct Indexing<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// What happened to a URL.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationType {
+    /// The page was added or its content changed.
+    UrlUpdated,
+    /// The page was removed.
+    UrlDeleted,
+}
+
+/// The latest notifications received for a URL.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UrlNotificationMetadata {
+    pub url: String,
+    pub latest_update: Option<UrlNotification>,
+    pub latest_remove: Option<UrlNotification>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlNotification {
+    pub url: String,
+    #[serde(rename = "type")]
+    pub notification_type: NotificationType,
+    pub notify_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublishResponse {
+    url_notification_metadata: UrlNotificationMetadata,
+}
+
+impl Google {
+    /// Returns an Indexing API client that authorizes its requests with `token`.
+    pub fn indexing<'a>(&'a self, token: &'a Token) -> Indexing<'a> {
+        Indexing {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl Indexing<'_> {
+    /// Notifies Google that a page was updated or deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the page.
+    /// * `notification_type` - Whether the page was updated or deleted.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. with status 403 when the
+    /// service account is not an owner of the site or status 429 when the daily quota is
+    /// exhausted.
+    pub async fn publish(
+        &self,
+        url: &str,
+        notification_type: NotificationType,
+    ) -> Result<UrlNotificationMetadata, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{INDEXING_API}/urlNotifications:publish"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "url": url, "type": notification_type }));
+        let response: PublishResponse = self.google.send_json(request).await?;
+
+        Ok(response.url_notification_metadata)
+    }
+
+    /// Fetches the latest notifications sent for a URL.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, including a `GoogleError` with
+    /// status 404 when no notification was ever sent for the URL.
+    pub async fn get_metadata(&self, url: &str) -> Result<UrlNotificationMetadata, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{INDEXING_API}/urlNotifications/metadata"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("url", url)]);
+
+        self.google.send_json(request).await
+    }
+}
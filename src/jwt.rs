@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::Utc;
+use reqwest::header::CACHE_CONTROL;
+use ring::signature::{RSA_PKCS1_2048_8192_SHA256, RsaPublicKeyComponents};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::Google;
+
+/// The public keys Google signs its OIDC ID tokens with.
+pub(crate) const GOOGLE_CERTS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+
+/// The issuers found in Google-signed ID tokens.
+pub(crate) const GOOGLE_ISSUERS: [&str; 2] = ["https://accounts.google.com", "accounts.google.com"];
+
+/// Tolerated clock skew when checking `exp` and `iat`, in seconds.
+pub(crate) const CLOCK_SKEW: i64 = 60;
+
+/// How long keys are cached when the response carries no `max-age`.
+const DEFAULT_KEYS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// How long a cached key set is trusted to be complete: tokens with an unknown key id only
+/// trigger a new fetch once this has passed since the last one, so that made-up key ids
+/// cannot make every request fetch the keys.
+const KEYS_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Public keys fetched from a JWKS endpoint, keyed by their URL.
+#[derive(Default)]
+pub(crate) struct KeyCache {
+    entries: Mutex<HashMap<String, CachedKeys>>,
+}
+
+struct CachedKeys {
+    expires_at: Instant,
+    fetched_at: Instant,
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Jwk {
+    kid: String,
+    n: String,
+    e: String,
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// The registered claims checked for every token.
+#[derive(Deserialize)]
+struct RegisteredClaims {
+    iss: String,
+    aud: Audience,
+    exp: i64,
+    #[serde(default)]
+    iat: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::One(value) => value == audience,
+            Audience::Many(values) => values.iter().any(|value| value == audience),
+        }
+    }
+}
+
+impl Google {
+    /// Verifies an RS256 JWT signed by one of the keys published at `certs_url` and returns
+    /// its claims.
+    ///
+    /// Besides the signature, the issuer must be one of `issuers`, the audience must
+    /// contain `audience`, and the token must be neither expired nor issued in the future.
+    pub(crate) async fn verify_jwt<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        certs_url: &str,
+        issuers: &[&str],
+        audience: &str,
+    ) -> Result<T, Box<dyn Error>> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err("Malformed JWT".into());
+        };
+
+        let message = &token[..header.len() + claims.len() + 1];
+        let header: Header = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header)?)?;
+        if header.alg != "RS256" {
+            return Err(format!("Unsupported JWT algorithm {}", header.alg).into());
+        }
+        let kid = header.kid.ok_or("JWT has no key id")?;
+        let key = self.signing_key(certs_url, &kid).await?;
+
+        let components = RsaPublicKeyComponents {
+            n: URL_SAFE_NO_PAD.decode(&key.n)?,
+            e: URL_SAFE_NO_PAD.decode(&key.e)?,
+        };
+        components
+            .verify(
+                &RSA_PKCS1_2048_8192_SHA256,
+                message.as_bytes(),
+                &URL_SAFE_NO_PAD.decode(signature)?,
+            )
+            .map_err(|_| "Invalid JWT signature")?;
+
+        let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims)?)?;
+        let registered = RegisteredClaims::deserialize(&claims)?;
+        let now = Utc::now().timestamp();
+        if !issuers.contains(&registered.iss.as_str()) {
+            return Err(format!("Unexpected JWT issuer {}", registered.iss).into());
+        }
+        if !registered.aud.contains(audience) {
+            return Err("JWT audience does not match".into());
+        }
+        if registered.exp + CLOCK_SKEW < now {
+            return Err("JWT has expired".into());
+        }
+        if registered.iat.is_some_and(|iat| iat - CLOCK_SKEW > now) {
+            return Err("JWT is issued in the future".into());
+        }
+
+        Ok(serde_json::from_value(claims)?)
+    }
+
+    /// Returns the key with id `kid`, fetching the key set again when the cached set has
+    /// expired, or when `kid` is unknown, which happens as Google rotates its keys, and the
+    /// set was fetched more than `KEYS_REFETCH_INTERVAL` ago.
+    async fn signing_key(&self, certs_url: &str, kid: &str) -> Result<Jwk, Box<dyn Error>> {
+        {
+            let mut entries = self.keys.entries.lock().unwrap();
+            let now = Instant::now();
+            if let Some(cached) = entries
+                .get_mut(certs_url)
+                .filter(|cached| cached.expires_at > now)
+            {
+                if let Some(key) = cached.keys.iter().find(|key| key.kid == kid) {
+                    return Ok(key.clone());
+                }
+                if now.duration_since(cached.fetched_at) < KEYS_REFETCH_INTERVAL {
+                    return Err(format!("Unknown JWT key id {kid}").into());
+                }
+                // Claims the refetch, so concurrent tokens with unknown ids fail fast.
+                cached.fetched_at = now;
+            }
+        }
+
+        let response = self.send(self.http.get(certs_url)).await?;
+        let max_age = response
+            .headers()
+            .get(CACHE_CONTROL)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_max_age)
+            .unwrap_or(DEFAULT_KEYS_MAX_AGE);
+        let set: JwkSet = response.json().await?;
+        let key = set.keys.iter().find(|key| key.kid == kid).cloned();
+
+        self.keys.entries.lock().unwrap().insert(
+            certs_url.to_string(),
+            CachedKeys {
+                expires_at: Instant::now() + max_age,
+                fetched_at: Instant::now(),
+                keys: set.keys,
+            },
+        );
+
+        key.ok_or_else(|| format!("Unknown JWT key id {kid}").into())
+    }
+}
+
+/// Extracts the `max-age` directive of a `Cache-Control` header.
+fn parse_max_age(cache_control: &str) -> Option<Duration> {
+    cache_control
+        .split(',')
+        .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+        .find_map(|seconds| seconds.parse().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn google_with_keys(certs_url: &str, fetched_at: Instant) -> Google {
+        let google = Google::new(
+            "client-id".to_string(),
+            "secret".to_string(),
+            "https://app.example.com/callback".to_string(),
+        );
+        google.keys.entries.lock().unwrap().insert(
+            certs_url.to_string(),
+            CachedKeys {
+                expires_at: Instant::now() + DEFAULT_KEYS_MAX_AGE,
+                fetched_at,
+                keys: vec![Jwk {
+                    kid: "known".to_string(),
+                    n: "n".to_string(),
+                    e: "AQAB".to_string(),
+                }],
+            },
+        );
+        google
+    }
+
+    #[tokio::test]
+    async fn returns_cached_keys() {
+        // Nothing listens on the discard port, so any fetch would fail.
+        let certs_url = "http://127.0.0.1:9/certs";
+        let google = google_with_keys(certs_url, Instant::now());
+
+        let key = google.signing_key(certs_url, "known").await.unwrap();
+
+        assert_eq!(key.kid, "known");
+    }
+
+    #[tokio::test]
+    async fn does_not_refetch_keys_for_unknown_ids_right_away() {
+        let certs_url = "http://127.0.0.1:9/certs";
+        let google = google_with_keys(certs_url, Instant::now());
+
+        let err = google.signing_key(certs_url, "made-up").await.unwrap_err();
+
+        assert_eq!(err.to_string(), "Unknown JWT key id made-up");
+    }
+
+    #[tokio::test]
+    async fn refetches_keys_for_unknown_ids_after_the_interval() {
+        let certs_url = "http://127.0.0.1:9/certs";
+        let Some(fetched_at) = Instant::now().checked_sub(KEYS_REFETCH_INTERVAL) else {
+            return;
+        };
+        let google = google_with_keys(certs_url, fetched_at);
+
+        let err = google.signing_key(certs_url, "made-up").await.unwrap_err();
+
+        assert_ne!(err.to_string(), "Unknown JWT key id made-up");
+        let second = google.signing_key(certs_url, "made-up").await.unwrap_err();
+        assert_eq!(second.to_string(), "Unknown JWT key id made-up");
+    }
+}
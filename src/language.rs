@@ -0,0 +1,216 @@
+//! Cloud Natural Language API, for sentiment and entity analysis.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::Auth;
+use crate::{Google, Token};
+
+/// Scope granting access to the Cloud Natural Language API.
+pub const SCOPE_CLOUD_LANGUAGE: &str = "https://www.googleapis.com/auth/cloud-language";
+
+const LANGUAGE_API: &str = "https://language.googleapis.com/v1";
+
+/// A client for the Natural Language API.
+#[derive(Clone, Copy)]
+pub struct NaturalLanguage<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// The text to analyze.
+#[derive(Debug, Clone)]
+pub enum Document {
+    PlainText(String),
+    /// HTML, whose markup is ignored during analysis.
+    Html(String),
+    /// A Cloud Storage URI, `gs://bucket/object`, of a plain text file.
+    Uri(String),
+}
+
+/// The overall emotional opinion of a text.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Sentiment {
+    /// From -1.0 (negative) to 1.0 (positive).
+    pub score: f32,
+    /// The overall strength of emotion, from 0.0 upwards regardless of its direction;
+    /// longer texts tend to have larger magnitudes.
+    pub magnitude: f32,
+}
+
+/// A portion of the analyzed text.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextSpan {
+    pub content: String,
+    /// The offset of the span in the text, in bytes of its UTF-8 encoding.
+    pub begin_offset: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Sentence {
+    pub text: TextSpan,
+    pub sentiment: Option<Sentiment>,
+}
+
+/// The result of `analyze_sentiment`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SentimentAnalysis {
+    pub document_sentiment: Sentiment,
+    /// The language of the text, given or detected.
+    pub language: String,
+    pub sentences: Vec<Sentence>,
+}
+
+/// The result of `analyze_entities`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntityAnalysis {
+    /// The entities, the most salient first.
+    pub entities: Vec<Entity>,
+    pub language: String,
+}
+
+/// A person, place, organization or other thing mentioned in the text.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Entity {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub entity_type: EntityType,
+    /// Additional information, e.g. `wikipedia_url` and `mid` for well-known entities.
+    pub metadata: HashMap<String, String>,
+    /// The importance of the entity to the text, from 0.0 to 1.0.
+    pub salience: f32,
+    pub mentions: Vec<EntityMention>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EntityType {
+    Person,
+    Location,
+    Organization,
+    Event,
+    WorkOfArt,
+    ConsumerGood,
+    Other,
+    PhoneNumber,
+    Address,
+    Date,
+    Number,
+    Price,
+    /// Any type not known to this crate.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// An occurrence of an entity in the text.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntityMention {
+    pub text: TextSpan,
+    /// `PROPER` for names, `COMMON` for common nouns.
+    #[serde(rename = "type")]
+    pub mention_type: String,
+}
+
+impl Document {
+    fn to_json(&self, language: Option<&str>) -> serde_json::Value {
+        let mut document = match self {
+            Document::PlainText(content) => json!({ "type": "PLAIN_TEXT", "content": content }),
+            Document::Html(content) => json!({ "type": "HTML", "content": content }),
+            Document::Uri(uri) => json!({ "type": "PLAIN_TEXT", "gcsContentUri": uri }),
+        };
+        if let Some(language) = language {
+            document["language"] = json!(language);
+        }
+        document
+    }
+}
+
+impl Google {
+    /// Returns a Natural Language API client that authorizes its requests with `token`.
+    pub fn natural_language<'a>(&'a self, token: &'a Token) -> NaturalLanguage<'a> {
+        NaturalLanguage {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a Natural Language API client that authorizes its requests with an API key.
+    pub fn natural_language_with_key<'a>(&'a self, api_key: &'a str) -> NaturalLanguage<'a> {
+        NaturalLanguage {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl NaturalLanguage<'_> {
+    /// Analyzes the sentiment of a text and of each of its sentences.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The text to analyze.
+    /// * `language` - The language of the text, e.g. `en`, `None` to detect it.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the language is
+    /// not supported.
+    pub async fn analyze_sentiment(
+        &self,
+        document: &Document,
+        language: Option<&str>,
+    ) -> Result<SentimentAnalysis, Box<dyn Error>> {
+        self.analyze("analyzeSentiment", document, language).await
+    }
+
+    /// Finds the entities mentioned in a text.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The text to analyze.
+    /// * `language` - The language of the text, e.g. `en`, `None` to detect it.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn analyze_entities(
+        &self,
+        document: &Document,
+        language: Option<&str>,
+    ) -> Result<EntityAnalysis, Box<dyn Error>> {
+        self.analyze("analyzeEntities", document, language).await
+    }
+
+    async fn analyze<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        document: &Document,
+        language: Option<&str>,
+    ) -> Result<T, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{LANGUAGE_API}/documents:{method}")),
+            )
+            .json(&json!({
+                "document": document.to_json(language),
+                "encodingType": "UTF8",
+            }));
+
+        self.google.send_json(request).await
+    }
+}
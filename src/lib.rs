@@ -1,33 +1,279 @@
-use oauth2::basic::BasicClient;
-use oauth2::reqwest::async_http_client;
+use oauth2::basic::{
+    BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
+};
 use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, RedirectUrl, Scope,
-    TokenResponse, TokenUrl,
+    AuthUrl, AuthorizationCode, AuthorizationRequest, ClientId, ClientSecret, CsrfToken,
+    EndpointNotSet, EndpointSet, PkceCodeVerifier, RedirectUrl, Scope, StandardRevocableToken,
+    TokenUrl,
 };
 use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use jwt::{GOOGLE_CERTS_URL, GOOGLE_ISSUERS, KeyCache};
+use refresh::{DEFAULT_REFRESH_LEEWAY, RefreshLocks};
+
+#[cfg(feature = "admin-reports")]
+pub mod admin_reports;
+#[cfg(feature = "analytics")]
+pub mod analytics;
+mod api;
+#[cfg(feature = "apps-script")]
+pub mod apps_script;
+mod authorization;
+#[cfg(feature = "bigquery")]
+pub mod bigquery;
+#[cfg(feature = "business-profile")]
+pub mod business_profile;
+#[cfg(feature = "calendar")]
+pub mod calendar;
+mod callback;
+#[cfg(feature = "channel")]
+pub mod channel;
+#[cfg(feature = "chat")]
+pub mod chat;
+#[cfg(feature = "classroom")]
+pub mod classroom;
+#[cfg(feature = "custom-search")]
+pub mod custom_search;
+#[cfg(any(
+    feature = "admin-reports",
+    feature = "bigquery",
+    feature = "channel",
+    feature = "custom-search",
+    feature = "directory",
+    feature = "drive",
+    feature = "gmail",
+    feature = "identity-platform",
+    feature = "photos",
+    feature = "play",
+    feature = "play-integrity",
+    feature = "search-console",
+    feature = "storage",
+    feature = "youtube"
+))]
+mod de;
+#[cfg(feature = "directory")]
+pub mod directory;
+#[cfg(feature = "docs")]
+pub mod docs;
+#[cfg(feature = "drive")]
+pub mod drive;
+mod error;
+mod events;
+#[cfg(feature = "fcm")]
+pub mod fcm;
+#[cfg(feature = "firestore")]
+pub mod firestore;
+#[cfg(feature = "forms")]
+pub mod forms;
+#[cfg(feature = "gmail")]
+pub mod gmail;
+#[cfg(feature = "identity-platform")]
+pub mod identity_platform;
+#[cfg(feature = "indexing")]
+pub mod indexing;
+mod jwt;
+#[cfg(feature = "language")]
+pub mod language;
+mod linking;
+mod locale;
+#[cfg(feature = "maps")]
+pub mod maps;
+#[cfg(feature = "meet")]
+pub mod meet;
+#[cfg(feature = "people")]
+pub mod people;
+mod persist;
+#[cfg(feature = "photos")]
+pub mod photos;
+#[cfg(feature = "places")]
+pub mod places;
+#[cfg(feature = "play")]
+pub mod play;
+#[cfg(feature = "play-integrity")]
+pub mod play_integrity;
+#[cfg(feature = "poem")]
+pub mod poem;
+#[cfg(feature = "pubsub")]
+pub mod pubsub;
+#[cfg(feature = "recaptcha")]
+pub mod recaptcha;
+mod refresh;
+#[cfg(feature = "safe-browsing")]
+pub mod safe_browsing;
+#[cfg(feature = "salvo")]
+pub mod salvo;
+mod scope;
+#[cfg(feature = "search-console")]
+pub mod search_console;
+mod secret;
+mod service_account;
+#[cfg(feature = "sheets")]
+pub mod sheets;
+#[cfg(feature = "slides")]
+pub mod slides;
+#[cfg(feature = "speech")]
+pub mod speech;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[cfg(feature = "tasks")]
+pub mod tasks;
+#[cfg(test)]
+mod test_server;
+#[cfg(feature = "text-to-speech")]
+pub mod text_to_speech;
+#[cfg(feature = "tide")]
+pub mod tide;
+mod token;
+#[cfg(feature = "translate")]
+pub mod translate;
+#[cfg(feature = "upload")]
+pub mod upload;
+#[cfg(feature = "vision")]
+pub mod vision;
+#[cfg(feature = "wallet")]
+pub mod wallet;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(any(feature = "poem", feature = "salvo", feature = "tide"))]
+mod web;
+mod workspace;
+#[cfg(feature = "youtube")]
+pub mod youtube;
+#[cfg(feature = "youtube-analytics")]
+pub mod youtube_analytics;
+
+pub use authorization::{Authorization, AuthorizationBuilder, PkceCallback};
+pub use callback::{AuthCallback, AuthError, AuthErrorKind};
+pub use error::GoogleError;
+pub use events::AuthEvents;
+pub use linking::{AccountLinks, LinkConflict};
+pub use locale::Locale;
+pub use refresh::{RefreshedToken, TokenStore};
+pub use scope::Scopes;
+pub use service_account::ServiceAccountKey;
+pub use token::{GoogleTokenResponse, IdTokenFields, Token};
+pub use workspace::WorkspaceMismatch;
+
+/// Scopes requested by `get_redirect_url` unless changed with `Google::with_default_scopes`,
+/// enough to fetch the user's profile information.
+const DEFAULT_SCOPES: [&str; 3] = ["openid", "email", "profile"];
+
+/// The endpoint revoking access and refresh tokens.
+const REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+
+/// Printed in place of secrets by `Debug` implementations.
+pub(crate) const REDACTED: &str = "[redacted]";
 
+/// The `oauth2` client configured by `Google::new`, whose token responses keep Google's ID
+/// token. See `Google::oauth_client`.
+pub type GoogleClient = oauth2::Client<
+    BasicErrorResponse,
+    GoogleTokenResponse,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointSet,
+>;
+
+/// The Google authorization client, and the entry point to every API client of this crate.
+///
+/// Cloning is cheap: clones share the OAuth2 client, the HTTP connection pool and the
+/// cache of signing keys, so a `Google` can be kept in web framework state and cloned per
+/// request.
+#[derive(Clone)]
 pub struct Google {
-    client: BasicClient,
+    client: Arc<GoogleClient>,
+    http: Client,
+    keys: Arc<KeyCache>,
+    userinfo_endpoint: UserInfoEndpoint,
+    response_mode: ResponseMode,
+    default_scopes: Arc<Scopes>,
+    allowed_scopes: Option<Arc<Scopes>>,
+    token_store: Option<Arc<dyn TokenStore>>,
+    refresh_leeway: Duration,
+    max_retries: u32,
+    generate_correlation_ids: bool,
+    correlation_id: Option<Arc<str>>,
+    auth_events: Option<Arc<dyn AuthEvents>>,
+    timeout: Option<Duration>,
+    accept_language: Option<Arc<str>>,
+    account_links: Option<Arc<dyn AccountLinks>>,
+    hosted_domains: Option<Arc<[String]>>,
+    refresh_locks: Arc<RefreshLocks>,
+}
+
+/// How Google delivers the authorization response to the redirect URI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseMode {
+    /// As query parameters of a `GET` request.
+    #[default]
+    Query,
+    /// As the form-encoded body of a `POST` request, which keeps the code out of URLs,
+    /// browser history and access logs. Parse it with `AuthCallback::from_form`.
+    FormPost,
 }
 
+/// The endpoint `UserInfo` is fetched from.
+///
+/// The endpoints differ in the names and availability of their claims, e.g. v1 and v2
+/// return `id` and `verified_email` instead of `sub` and `email_verified`. All of them are
+/// mapped onto `UserInfo`, with the claims it does not model kept in `UserInfo::extra`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UserInfoEndpoint {
+    /// `https://www.googleapis.com/oauth2/v1/userinfo`.
+    V1,
+    /// `https://www.googleapis.com/oauth2/v2/userinfo`.
+    V2,
+    /// `https://www.googleapis.com/oauth2/v3/userinfo`.
+    #[default]
+    V3,
+    /// The OpenID Connect endpoint from Google's discovery document,
+    /// `https://openidconnect.googleapis.com/v1/userinfo`.
+    OpenIdConnect,
+    /// No endpoint: `UserInfo` is built from the claims of the ID token returned by the
+    /// code exchange once its signature, issuer, audience and expiry are verified. This
+    /// saves a request on every login. Google's signing keys are cached.
+    IdToken,
+}
+
+/// The profile of the authenticated user, as returned by the userinfo endpoint.
+///
+/// Claims not modelled by a field, such as `hd` for Google Workspace accounts, are kept in
+/// `extra`. More fields may be added in minor releases.
 #[derive(Deserialize, Serialize, Debug)]
+#[non_exhaustive]
 pub struct UserInfo {
-    #[serde(rename = "sub")]
+    #[serde(rename = "sub", alias = "id")]
     pub open_id: String,
 
     #[serde(rename = "name")]
     pub username: String,
-    given_name: Option<String>,
-    family_name: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
 
     #[serde(rename = "picture")]
     pub profile_url: String,
 
     pub email: String,
+    #[serde(alias = "verified_email")]
     pub email_verified: bool,
-    locale: Option<String>,
+    pub locale: Option<Locale>,
+
+    /// Any other claim returned by Google.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Google {
@@ -46,38 +292,442 @@ impl Google {
     ///
     /// * `Google` - A new instance of the Google authorization client.
     pub fn new(appid: String, app_secret: String, callback_url: String) -> Google {
-        let client_id = ClientId::new(appid.clone());
-        let client_secret = ClientSecret::new(app_secret.clone());
+        Google::with_client(appid, Some(app_secret), callback_url)
+    }
+
+    /// Creates a client for a public application, such as a single-page app running in the
+    /// browser, which cannot keep a client secret.
+    ///
+    /// The authorization code flow of a public client must be protected with PKCE, see
+    /// `AuthorizationBuilder::pkce` and `Google::get_token_with_verifier`.
+    ///
+    /// # Arguments
+    ///
+    /// * `appid` - The client ID provided by Google when registering the application.
+    /// * `callback_url` - The URL that the user will be redirected to after authorization
+    ///   is complete.
+    pub fn new_public(appid: String, callback_url: String) -> Google {
+        Google::with_client(appid, None, callback_url)
+    }
+
+    fn with_client(appid: String, app_secret: Option<String>, callback_url: String) -> Google {
+        let client_id = ClientId::new(appid);
 
         let auth_url =
             AuthUrl::new("https://accounts.google.com/o/oauth2/auth".to_string()).unwrap();
         let token_url =
             TokenUrl::new("https://accounts.google.com/o/oauth2/token".to_string()).unwrap();
 
-        let redirect_url = RedirectUrl::new(callback_url.clone()).unwrap();
+        let redirect_url = RedirectUrl::new(callback_url).unwrap();
 
-        let client = BasicClient::new(client_id, Some(client_secret), auth_url, Some(token_url))
+        let mut client: GoogleClient = oauth2::Client::new(client_id)
+            .set_auth_uri(auth_url)
+            .set_token_uri(token_url)
             .set_redirect_uri(redirect_url);
+        if let Some(app_secret) = app_secret {
+            client = client.set_client_secret(ClientSecret::new(app_secret));
+        }
+
+        Google {
+            client: Arc::new(client),
+            http: default_http_client(),
+            keys: Arc::default(),
+            userinfo_endpoint: UserInfoEndpoint::default(),
+            response_mode: ResponseMode::default(),
+            default_scopes: Arc::new(Scopes::new(DEFAULT_SCOPES)),
+            allowed_scopes: None,
+            token_store: None,
+            refresh_leeway: DEFAULT_REFRESH_LEEWAY,
+            max_retries: 0,
+            generate_correlation_ids: false,
+            correlation_id: None,
+            auth_events: None,
+            timeout: None,
+            accept_language: None,
+            account_links: None,
+            hosted_domains: None,
+            refresh_locks: Arc::default(),
+        }
+    }
+
+    /// Creates a new instance of the Google authorization client like `Google::new`, taking
+    /// the client secret as a `SecretString`.
+    ///
+    /// The secret is handed to the `oauth2` client, which keeps it out of its `Debug`
+    /// output but does not zeroize it.
+    #[cfg(feature = "secrecy")]
+    pub fn new_with_secret(
+        appid: String,
+        app_secret: secrecy::SecretString,
+        callback_url: String,
+    ) -> Google {
+        use secrecy::ExposeSecret;
+
+        Google::new(appid, app_secret.expose_secret().to_string(), callback_url)
+    }
+
+    /// Returns the underlying `oauth2` client, configured with the client credentials, the
+    /// Google endpoints and the redirect URI.
+    ///
+    /// This is an escape hatch for flows this crate does not cover. Tokens obtained through
+    /// it bypass the scope allow-list and the `AuthEvents` hooks; convert responses with
+    /// `Token::from` to use them with the API clients.
+    pub fn oauth_client(&self) -> &GoogleClient {
+        &self.client
+    }
+
+    /// Sends every request, including the token exchanges of the OAuth2 flow, through
+    /// `http`, e.g. a client configured with a proxy, custom timeouts or root certificates.
+    ///
+    /// The default client does not follow redirects, which keeps a redirecting token or
+    /// revocation endpoint from sending credentials to another URL (SSRF). `http` should
+    /// be built with `redirect(reqwest::redirect::Policy::none())` as well.
+    pub fn with_http_client(mut self, http: Client) -> Google {
+        self.http = http;
+        self
+    }
+
+    /// Sets how Google delivers the authorization response to the redirect URI.
+    pub fn with_response_mode(mut self, response_mode: ResponseMode) -> Google {
+        self.response_mode = response_mode;
+        self
+    }
+
+    /// Sets the scopes requested by `get_redirect_url` and `get_redirect_url_with_state`,
+    /// in place of `openid`, `email` and `profile`.
+    ///
+    /// Fetching `UserInfo` needs the `openid`, `email` and `profile` scopes, so keep them
+    /// in the set when signing users in, e.g. to also request read access to their
+    /// calendar.
+    pub fn with_default_scopes(mut self, scopes: Scopes) -> Google {
+        self.default_scopes = Arc::new(scopes);
+        self
+    }
+
+    /// Restricts the scopes this client may request to `allowed`, e.g. on a platform where
+    /// several teams share one OAuth client.
+    ///
+    /// Incremental authorization URLs and service account tokens for other scopes are
+    /// refused, as are tokens from a code exchange that were granted other scopes. The
+    /// allow-list must include the `openid`, `email` and `profile` scopes to sign users in.
+    pub fn with_allowed_scopes(mut self, allowed: Scopes) -> Google {
+        self.allowed_scopes = Some(Arc::new(allowed));
+        self
+    }
+
+    /// Sets the store refreshed tokens are saved to by `refresh_stored_token`.
+    pub fn with_token_store(mut self, store: impl TokenStore + 'static) -> Google {
+        self.token_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets the store `link_account` and `unlink_account` keep account links in.
+    pub fn with_account_links(mut self, links: impl AccountLinks + 'static) -> Google {
+        self.account_links = Some(Arc::new(links));
+        self
+    }
+
+    /// Sets how long before its expiry `get_fresh_token` refreshes a token, five minutes by
+    /// default.
+    pub fn with_refresh_leeway(mut self, leeway: Duration) -> Google {
+        self.refresh_leeway = leeway;
+        self
+    }
+
+    /// Retries API requests failing with a retryable `GoogleError` up to `max_retries`
+    /// times, honoring the `Retry-After` header and backing off exponentially otherwise.
+    /// Server errors of non-idempotent requests such as `POST` are not retried, as they may
+    /// have taken effect. Requests are not retried by default.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Google {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sends a random correlation id in the `X-Request-Id` header of every API request. The
+    /// id of a failed request is available from `GoogleError::correlation_id`.
+    pub fn with_correlation_ids(mut self) -> Google {
+        self.generate_correlation_ids = true;
+        self
+    }
+
+    /// Sends `correlation_id` in the `X-Request-Id` header of the API requests made by this
+    /// client, e.g. the id of the incoming request being handled. As `Google` is cheap to
+    /// clone, a clone can be made per incoming request.
+    pub fn with_correlation_id(mut self, correlation_id: &str) -> Google {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// Fails token exchanges, token refreshes and API calls that take longer than `timeout`,
+    /// including their retries, e.g. to bound the latency of a login handler. As `Google` is
+    /// cheap to clone, a clone can be made to apply a timeout to a single call.
+    ///
+    /// Without a timeout, calls can still be cancelled by dropping their future, which
+    /// aborts the request in flight.
+    pub fn with_timeout(mut self, timeout: Duration) -> Google {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends `language` in the `Accept-Language` header of the userinfo and API requests
+    /// made by this client, so that localized fields and error messages come back in the
+    /// application's language, e.g. `de` or `fr-CA, fr;q=0.9`. As `Google` is cheap to
+    /// clone, a clone can be made per call, e.g. with the language of the signed-in user.
+    pub fn with_accept_language(mut self, language: &str) -> Google {
+        self.accept_language = Some(language.into());
+        self
+    }
+
+    /// Restricts sign-in to the users of the Google Workspace organizations owning
+    /// `domains`, e.g. for an internal tool or the tenants of a B2B application.
+    ///
+    /// The `hd` claim of every profile returned by `get_userinfo`, `get_userinfo_raw`,
+    /// `authenticate` and `verify_id_token` must be one of `domains`, otherwise a
+    /// `WorkspaceMismatch` is returned. An empty `domains` lifts the restriction.
+    /// Authorization URLs carry the `hd` parameter, which only preselects the account on
+    /// the consent screen and is not a check by itself. To also verify the user's Workspace
+    /// customer, see `Directory::verify_customer`.
+    pub fn with_hosted_domains<S: AsRef<str>>(mut self, domains: &[S]) -> Google {
+        self.hosted_domains = (!domains.is_empty()).then(|| {
+            domains
+                .iter()
+                .map(|domain| domain.as_ref().to_string())
+                .collect()
+        });
+        self
+    }
 
-        Google { client }
+    /// Sets the callbacks invoked on logins, token refreshes and revocations.
+    pub fn with_auth_events(mut self, events: impl AuthEvents + 'static) -> Google {
+        self.auth_events = Some(Arc::new(events));
+        self
+    }
+
+    /// Fetches `UserInfo` from another endpoint than the default v3 one.
+    pub fn with_userinfo_endpoint(mut self, endpoint: UserInfoEndpoint) -> Google {
+        self.userinfo_endpoint = endpoint;
+        self
     }
 
     /// Generates a URL that the user should be redirected to in order to authorize this
     /// application. This URL is the standard authorization URL for the OAuth2 flow with the
     /// Google OAuth2 provider, and includes the scopes required to fetch the user's profile
-    /// information.
+    /// information, or the ones set with `with_default_scopes`.
     pub fn get_redirect_url(&self) -> String {
         let (auth_url, _csrf_token) = self
-            .client
-            .authorize_url(CsrfToken::new_random)
-            .add_scope(Scope::new("openid".to_string()))
-            .add_scope(Scope::new("email".to_string()))
-            .add_scope(Scope::new("profile".to_string()))
+            .authorization_request(CsrfToken::new_random())
+            .add_scopes(self.default_scope_list())
             .url();
 
         auth_url.to_string()
     }
 
+    /// Generates the same URL as `get_redirect_url`, carrying `state` as the `state`
+    /// parameter.
+    ///
+    /// The state should be a random value bound to the user's session, e.g. stored in a
+    /// cookie, and checked when the user comes back with `AuthCallback::verify`. This
+    /// protects the redirect URI against cross-site request forgery.
+    pub fn get_redirect_url_with_state(&self, state: &str) -> String {
+        let (auth_url, _csrf_token) = self
+            .authorization_request(CsrfToken::new(state.to_string()))
+            .add_scopes(self.default_scope_list())
+            .url();
+
+        auth_url.to_string()
+    }
+
+    /// Generates an authorization URL that asks the user for additional scopes on top of
+    /// the ones already granted.
+    ///
+    /// The URL sets `include_granted_scopes=true`, so the token obtained from the resulting
+    /// code covers both the previously granted and the newly requested scopes. This is
+    /// typically used after an API call fails with `GoogleError::is_insufficient_scope`.
+    ///
+    /// # Arguments
+    ///
+    /// * `scopes` - The missing scopes to request, e.g. from `GoogleError::required_scopes`
+    ///   or `Scopes::missing`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a scope is outside the allow-list set with
+    /// `with_allowed_scopes`.
+    pub fn get_incremental_redirect_url<S: AsRef<str>>(
+        &self,
+        scopes: &[S],
+    ) -> Result<String, Box<dyn Error>> {
+        self.check_scopes(scopes)?;
+
+        let (auth_url, _csrf_token) = self
+            .authorization_request(CsrfToken::new_random())
+            .add_scopes(
+                scopes
+                    .iter()
+                    .map(|scope| Scope::new(scope.as_ref().to_string())),
+            )
+            .add_extra_param("include_granted_scopes", "true")
+            .url();
+
+        Ok(auth_url.to_string())
+    }
+
+    /// Checks `scopes` against the allow-list set with `with_allowed_scopes`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error naming the scopes outside the allow-list, if any.
+    pub fn check_scopes<S: AsRef<str>>(&self, scopes: &[S]) -> Result<(), Box<dyn Error>> {
+        let Some(allowed) = &self.allowed_scopes else {
+            return Ok(());
+        };
+        let denied: Vec<&str> = scopes
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|scope| !allowed.contains(scope))
+            .collect();
+
+        if denied.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Scopes not allowed by policy: {}", denied.join(" ")).into())
+        }
+    }
+
+    /// The default scopes, as passed to the authorization request.
+    fn default_scope_list(&self) -> impl Iterator<Item = Scope> + '_ {
+        self.default_scopes
+            .iter()
+            .map(|scope| Scope::new(scope.to_string()))
+    }
+
+    /// Starts an authorization URL carrying `state`, with the configured response mode.
+    fn authorization_request(&self, state: CsrfToken) -> AuthorizationRequest<'_> {
+        let mut request = self.client.authorize_url(|| state);
+        if let Some(hd) = self.hosted_domain_hint() {
+            request = request.add_extra_param("hd", hd);
+        }
+
+        match self.response_mode {
+            ResponseMode::Query => request,
+            ResponseMode::FormPost => request.add_extra_param("response_mode", "form_post"),
+        }
+    }
+
+    /// Exchanges the provided authorization code for a `Token`.
+    ///
+    /// The returned token records the scopes the user actually granted, which can be
+    /// narrower than the requested ones since Google lets users uncheck scopes on the
+    /// consent screen. Use `Token::has_scope` to check before calling an API. When Google
+    /// does not list the granted scopes, the token is assumed to carry the default scopes
+    /// requested by `get_redirect_url`; exchange the code of an `Authorization` with
+    /// `get_token_for_authorization` instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `String` representing the authorization code received from Google's
+    ///   OAuth2 authorization flow.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the authorization code exchange fails.
+    pub async fn get_token(&self, code: String) -> Result<Token, Box<dyn Error>> {
+        self.exchange_code(code, None, &self.default_scopes).await
+    }
+
+    /// Exchanges the authorization code received for `authorization` for a `Token`, with
+    /// its PKCE verifier if any.
+    ///
+    /// When Google does not list the granted scopes, the token is assumed to carry the
+    /// scopes requested in `authorization`.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The authorization code received from Google's OAuth2 authorization flow.
+    /// * `authorization` - The authorization the user was sent to, kept in their session.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the authorization code exchange fails.
+    pub async fn get_token_for_authorization(
+        &self,
+        code: String,
+        authorization: &Authorization,
+    ) -> Result<Token, Box<dyn Error>> {
+        let pkce_verifier = authorization
+            .pkce_verifier
+            .clone()
+            .map(PkceCodeVerifier::new);
+
+        self.exchange_code(code, pkce_verifier, &authorization.scopes)
+            .await
+    }
+
+    /// Exchanges the authorization code of a request built with
+    /// `AuthorizationBuilder::pkce` for a `Token`, proving possession of the PKCE verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The authorization code received from Google's OAuth2 authorization flow.
+    /// * `pkce_verifier` - The verifier returned in `Authorization::pkce_verifier`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the authorization code exchange fails, e.g.
+    /// because the verifier does not match the challenge.
+    pub async fn get_token_with_verifier(
+        &self,
+        code: String,
+        pkce_verifier: String,
+    ) -> Result<Token, Box<dyn Error>> {
+        self.exchange_code(
+            code,
+            Some(PkceCodeVerifier::new(pkce_verifier)),
+            &self.default_scopes,
+        )
+        .await
+    }
+
+    /// Exchanges `code`, falling back to `requested` when the response lists no scopes.
+    async fn exchange_code(
+        &self,
+        code: String,
+        pkce_verifier: Option<PkceCodeVerifier>,
+        requested: &Scopes,
+    ) -> Result<Token, Box<dyn Error>> {
+        let mut request = self.client.exchange_code(AuthorizationCode::new(code));
+        if let Some(pkce_verifier) = pkce_verifier {
+            request = request.set_pkce_verifier(pkce_verifier);
+        }
+        let response = self
+            .with_deadline(async { Ok(request.request_async(&self.http).await?) })
+            .await?;
+        let token = Token::from_response(&response, requested);
+        self.check_scopes(&token.scopes().iter().collect::<Vec<_>>())?;
+        self.emit(|events| events.on_login(&token));
+
+        Ok(token)
+    }
+
+    /// Revokes the access granted to this application with `token`, e.g. when the user
+    /// disconnects their Google account.
+    ///
+    /// The refresh token is revoked when present, which also invalidates the access tokens
+    /// issued with it; otherwise the access token is.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if Google rejects the token, e.g. because it has
+    /// already been revoked or has expired.
+    pub async fn revoke_token(&self, token: &Token) -> Result<(), Box<dyn Error>> {
+        let secret = token.refresh_token().unwrap_or(token.access_token());
+        let request = self.http.post(REVOKE_URL).form(&[("token", secret)]);
+        self.send(request).await?;
+        self.emit(|events| events.on_revoked(token));
+
+        Ok(())
+    }
+
     /// Fetches and returns the user's profile information from Google using the provided
     /// authorization code.
     ///
@@ -88,7 +738,7 @@ impl Google {
     /// # Arguments
     ///
     /// * `code` - A `String` representing the authorization code received from Google's
-    ///            OAuth2 authorization flow.
+    ///   OAuth2 authorization flow.
     ///
     /// # Returns
     ///
@@ -100,37 +750,230 @@ impl Google {
     ///
     /// This function can return an error if the authorization code exchange fails, if the
     /// request to fetch the user's profile information fails, or if parsing the response
-    /// into a `UserInfo` struct fails.
+    /// into a `UserInfo` struct fails. It returns a `WorkspaceMismatch` if the user is
+    /// outside the domains set with `with_hosted_domains`.
     pub async fn get_userinfo(&self, code: String) -> Result<UserInfo, Box<dyn Error>> {
-        let token = match self
-            .client
-            .exchange_code(AuthorizationCode::new(code))
-            .request_async(async_http_client)
-            .await
-        {
-            Ok(result) => result.access_token().clone(),
-            Err(err) => {
-                return Err(err.into());
-            }
-        };
+        let token = self.get_token(code).await?;
+        let userinfo = self.fetch_userinfo(&token).await?;
+        self.check_hosted_domain(&userinfo)?;
 
-        let response = Client::new()
-            .get("https://www.googleapis.com/oauth2/v3/userinfo".to_string())
-            .bearer_auth(&token.secret())
-            .send()
+        Ok(userinfo)
+    }
+
+    /// Exchanges the authorization code for a `Token` and fetches the user's profile
+    /// information with it, for applications that need both, e.g. to store the refresh
+    /// token of a signed-in user.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `String` representing the authorization code received from Google's
+    ///   OAuth2 authorization flow.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the authorization code exchange fails, or if the
+    /// profile information cannot be fetched. In the latter case the token is dropped;
+    /// authorization codes are single-use, so the user has to sign in again. It returns a
+    /// `WorkspaceMismatch` if the user is outside the domains set with `with_hosted_domains`.
+    pub async fn authenticate(&self, code: String) -> Result<(Token, UserInfo), Box<dyn Error>> {
+        let token = self.get_token(code).await?;
+        let userinfo = self.fetch_userinfo(&token).await?;
+        self.check_hosted_domain(&userinfo)?;
+
+        Ok((token, userinfo))
+    }
+
+    /// Fetches the user's profile information like `get_userinfo`, but returns the userinfo
+    /// response as raw JSON, for reading claims `UserInfo` does not model.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - A `String` representing the authorization code received from Google's
+    ///   OAuth2 authorization flow.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the authorization code exchange or the request to
+    /// the userinfo endpoint fails. It returns a `WorkspaceMismatch` if the user is outside
+    /// the domains set with `with_hosted_domains`.
+    pub async fn get_userinfo_raw(&self, code: String) -> Result<Value, Box<dyn Error>> {
+        let token = self.get_token(code).await?;
+        let userinfo = self.fetch_userinfo(&token).await?;
+        self.check_hosted_domain_raw(&userinfo)?;
+
+        Ok(userinfo)
+    }
+
+    /// Verifies a Google ID token issued to this application and returns the profile
+    /// information it carries, e.g. to authenticate requests of a single-page application
+    /// that signed in with Google.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the token is malformed, not signed by Google, not
+    /// issued to this client id, or expired, or if it lacks the `email` and `profile`
+    /// claims. It returns a `WorkspaceMismatch` if the user is outside the domains set with
+    /// `with_hosted_domains`.
+    pub async fn verify_id_token(&self, id_token: &str) -> Result<UserInfo, Box<dyn Error>> {
+        let client_id = self.client.client_id().as_str();
+
+        let userinfo = self
+            .verify_jwt(id_token, GOOGLE_CERTS_URL, &GOOGLE_ISSUERS, client_id)
             .await?;
+        self.check_hosted_domain(&userinfo)?;
 
-        if !response.status().is_success() {
-            return Err("Failed to fetch profile information".into());
-        }
+        Ok(userinfo)
+    }
 
-        let result = match response.json::<UserInfo>().await {
-            Ok(result) => result,
-            Err(err) => {
-                return Err(err.into());
-            }
+    /// Requests the profile of the user `token` was issued to from the userinfo endpoint.
+    /// With `UserInfoEndpoint::IdToken`, the claims of the verified ID token are returned
+    /// instead.
+    async fn fetch_userinfo<T: DeserializeOwned>(
+        &self,
+        token: &Token,
+    ) -> Result<T, Box<dyn Error>> {
+        let Some(url) = self.userinfo_endpoint.url() else {
+            let id_token = token.id_token().ok_or("Token has no ID token")?;
+            let client_id = self.client.client_id().as_str();
+
+            return self
+                .verify_jwt(id_token, GOOGLE_CERTS_URL, &GOOGLE_ISSUERS, client_id)
+                .await;
+        };
+        let request = self.http.get(url).bearer_auth(token.access_token());
+
+        self.send_json(request).await
+    }
+}
+
+/// A downloaded profile picture.
+#[derive(Debug, Clone)]
+pub struct Avatar {
+    pub data: Vec<u8>,
+    /// The MIME type of the image, e.g. `image/jpeg`.
+    pub content_type: Option<String>,
+}
+
+impl UserInfo {
+    /// Returns the URL of the profile picture cropped to a `size` x `size` pixels square,
+    /// by rewriting the size options of the `picture` URL, e.g. `=s96-c` to `=s256-c`.
+    pub fn avatar_url(&self, size: u32) -> String {
+        let url = &self.profile_url;
+        let segment_start = url.rfind('/').map_or(0, |index| index + 1);
+        let base = match url[segment_start..].rfind('=') {
+            Some(index) => &url[..segment_start + index],
+            None => url,
+        };
+
+        format!("{base}=s{size}-c")
+    }
+}
+
+impl Google {
+    /// Downloads the user's profile picture, e.g. to re-host it.
+    ///
+    /// # Arguments
+    ///
+    /// * `userinfo` - The profile of the user.
+    /// * `size` - The size of the square picture in pixels, see `UserInfo::avatar_url`, or
+    ///   `None` for the picture as returned by Google.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the download fails.
+    pub async fn download_avatar(
+        &self,
+        userinfo: &UserInfo,
+        size: Option<u32>,
+    ) -> Result<Avatar, Box<dyn Error>> {
+        let url = match size {
+            Some(size) => userinfo.avatar_url(size),
+            None => userinfo.profile_url.clone(),
         };
+        let response = self.send(self.http.get(url)).await?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Ok(Avatar {
+            data: response.bytes().await?.to_vec(),
+            content_type,
+        })
+    }
+}
+
+impl fmt::Debug for Google {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Google")
+            .field("client_id", &self.client.client_id().as_str())
+            .field("client_secret", &REDACTED)
+            .field(
+                "redirect_url",
+                &self.client.redirect_uri().map(|url| url.as_str()),
+            )
+            .field("userinfo_endpoint", &self.userinfo_endpoint)
+            .field("response_mode", &self.response_mode)
+            .field("default_scopes", &self.default_scopes)
+            .field("allowed_scopes", &self.allowed_scopes)
+            .field("token_store", &self.token_store.is_some())
+            .field("refresh_leeway", &self.refresh_leeway)
+            .field("max_retries", &self.max_retries)
+            .field("generate_correlation_ids", &self.generate_correlation_ids)
+            .field("correlation_id", &self.correlation_id)
+            .field("auth_events", &self.auth_events.is_some())
+            .field("timeout", &self.timeout)
+            .field("accept_language", &self.accept_language)
+            .field("account_links", &self.account_links.is_some())
+            .field("hosted_domains", &self.hosted_domains)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds the HTTP client used unless one is set with `Google::with_http_client`, which
+/// does not follow redirects, as `oauth2` requires for the token endpoints.
+fn default_http_client() -> Client {
+    let builder = Client::builder();
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = builder.redirect(reqwest::redirect::Policy::none());
+
+    builder.build().unwrap()
+}
+
+impl UserInfoEndpoint {
+    fn url(self) -> Option<&'static str> {
+        match self {
+            UserInfoEndpoint::V1 => Some("https://www.googleapis.com/oauth2/v1/userinfo"),
+            UserInfoEndpoint::V2 => Some("https://www.googleapis.com/oauth2/v2/userinfo"),
+            UserInfoEndpoint::V3 => Some("https://www.googleapis.com/oauth2/v3/userinfo"),
+            UserInfoEndpoint::OpenIdConnect => {
+                Some("https://openidconnect.googleapis.com/v1/userinfo")
+            }
+            UserInfoEndpoint::IdToken => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::test_server::{TestServer, response};
+
+    #[tokio::test]
+    async fn default_client_does_not_follow_redirects() {
+        let redirect = response("302 Found", &[("Location", "http://169.254.169.254/")], "");
+        let server = TestServer::start(vec![redirect]).await;
+
+        let response = default_http_client()
+            .post(format!("{}/token", server.url))
+            .send()
+            .await
+            .unwrap();
 
-        Ok(result)
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(server.requests().len(), 1);
     }
 }
@@ -0,0 +1,414 @@
+use std::error::Error;
+use std::fmt;
+
+use futures_util::future::BoxFuture;
+
+use crate::{Google, GoogleError, Token, UserInfo};
+
+/// The prefix of the link keys built by `UserInfo::link_key`.
+const LINK_KEY_PREFIX: &str = "google:";
+
+/// Persists the links between the application's users and their Google accounts, e.g. in
+/// a table with a unique index on the link key.
+///
+/// Set with `Google::with_account_links`, it is used by `Google::link_account`,
+/// `Google::unlink_account` and the multi-account helpers. Accounts are keyed by
+/// `UserInfo::link_key`, which is built from the stable `sub` claim: email addresses can
+/// change and be reassigned to another account, so they must never identify a linked
+/// account.
+///
+/// A user may link several Google accounts, whose tokens are then stored under their link
+/// keys in the `TokenStore`, see `Google::add_account`.
+pub trait AccountLinks: Send + Sync {
+    /// Returns the id of the user the Google account `link_key` is linked to, if any.
+    fn linked_user<'a>(
+        &'a self,
+        link_key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, Box<dyn Error>>>;
+
+    /// Returns the link keys of the Google accounts linked to the user `user_id`.
+    fn linked_accounts<'a>(
+        &'a self,
+        user_id: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, Box<dyn Error>>>;
+
+    /// Links the Google account `link_key` to the user `user_id` unless it is already
+    /// linked, returning the user it was already linked to.
+    ///
+    /// The check and the insert must be a single atomic step, e.g. an `INSERT ... ON
+    /// CONFLICT DO NOTHING` followed by reading the owner, so that concurrent requests
+    /// cannot link the same account to two users.
+    fn save_link_if_absent<'a>(
+        &'a self,
+        link_key: &'a str,
+        user_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, Box<dyn Error>>>;
+
+    /// Removes the link of the Google account `link_key`.
+    fn remove_link<'a>(&'a self, link_key: &'a str) -> BoxFuture<'a, Result<(), Box<dyn Error>>>;
+}
+
+/// The error returned boxed by `Google::link_account` when the Google account is already
+/// linked to another user.
+///
+/// It can be inspected by downcasting with `err.downcast_ref::<LinkConflict>()`, e.g. to
+/// tell the user to sign in to the other account and unlink it first.
+#[derive(Debug, Clone)]
+pub struct LinkConflict {
+    pub link_key: String,
+    /// The id of the user the Google account is linked to.
+    pub linked_user: String,
+}
+
+impl UserInfo {
+    /// The canonical key identifying this Google account in `AccountLinks`, built from its
+    /// `sub` claim.
+    pub fn link_key(&self) -> String {
+        format!("{LINK_KEY_PREFIX}{}", self.open_id)
+    }
+}
+
+impl Google {
+    /// Links the Google account `identity` to the application's user `user_id`, returning
+    /// its link key.
+    ///
+    /// Linking an account to the user it is already linked to succeeds without saving
+    /// anything, so it can be called on every sign-in. The link is saved with
+    /// `AccountLinks::save_link_if_absent`, so of concurrent requests linking the same
+    /// account to different users, only one succeeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The id of the user record in the application.
+    /// * `identity` - The verified identity of the Google account, as returned by
+    ///   `authenticate` or `verify_id_token`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns a `LinkConflict` if the account is linked to another user,
+    /// and another error if no `AccountLinks` is configured or the store fails.
+    pub async fn link_account(
+        &self,
+        user_id: &str,
+        identity: &UserInfo,
+    ) -> Result<String, Box<dyn Error>> {
+        let links = self.account_links()?;
+        let link_key = identity.link_key();
+
+        match links.save_link_if_absent(&link_key, user_id).await? {
+            Some(linked_user) if linked_user != user_id => {
+                return Err(LinkConflict {
+                    link_key,
+                    linked_user,
+                }
+                .into());
+            }
+            _ => {}
+        }
+
+        Ok(link_key)
+    }
+
+    /// Unlinks the Google account `link_key` from the user `user_id`: revokes the access
+    /// granted with `token`, removes the link from the configured `AccountLinks`, and
+    /// deletes the account's token from the `TokenStore`, if one is configured, so its
+    /// refresh token does not stay stored.
+    ///
+    /// A token Google no longer knows, because it expired or was already revoked, does not
+    /// keep the account from being unlinked.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The id of the user record in the application.
+    /// * `link_key` - The link key returned by `link_account`.
+    /// * `token` - A token of the linked account, preferably with its refresh token.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if no `AccountLinks` is configured, if the account is
+    /// not linked to `user_id`, if revoking fails for another reason, in which case the
+    /// link is kept so unlinking can be retried, or if the store fails.
+    pub async fn unlink_account(
+        &self,
+        user_id: &str,
+        link_key: &str,
+        token: &Token,
+    ) -> Result<(), Box<dyn Error>> {
+        let links = self.account_links()?;
+        self.check_linked_user(links, user_id, link_key).await?;
+
+        match self.revoke_token(token).await {
+            Ok(()) => {}
+            Err(err) if is_invalid_token(err.as_ref()) => {}
+            Err(err) => return Err(err),
+        }
+
+        links.remove_link(link_key).await?;
+        if let Some(store) = self.token_store.as_deref() {
+            store.delete(link_key).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Links the Google account `identity` to the user `user_id` like `link_account`, and
+    /// saves `token` under the account's link key in the configured `TokenStore`, for
+    /// `account_token` to return it later.
+    ///
+    /// Users linking several accounts should be sent to an authorization URL built with
+    /// `AuthorizationBuilder::select_account`, so they can pick another account than the
+    /// one they are signed in to.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error as described for `link_account`, or if no token store
+    /// is configured or the token cannot be saved.
+    pub async fn add_account(
+        &self,
+        user_id: &str,
+        identity: &UserInfo,
+        token: &Token,
+    ) -> Result<String, Box<dyn Error>> {
+        let store = self
+            .token_store
+            .as_deref()
+            .ok_or("No token store configured")?;
+        let link_key = self.link_account(user_id, identity).await?;
+        store.save(&link_key, token).await?;
+
+        Ok(link_key)
+    }
+
+    /// Returns the link keys of the Google accounts linked to the user `user_id`, to let
+    /// them choose which account to act as.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if no `AccountLinks` is configured or the store fails.
+    pub async fn linked_accounts(&self, user_id: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        self.account_links()?.linked_accounts(user_id).await
+    }
+
+    /// Returns a token of the Google account `link_key` linked to the user `user_id`,
+    /// refreshed as described for `get_fresh_token`, to call APIs as that account.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The id of the user record in the application.
+    /// * `link_key` - One of the link keys returned by `linked_accounts`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the account is not linked to `user_id`, which
+    /// keeps a user from acting as another user's account, and otherwise as described for
+    /// `get_fresh_token`.
+    pub async fn account_token(
+        &self,
+        user_id: &str,
+        link_key: &str,
+    ) -> Result<Token, Box<dyn Error>> {
+        self.check_linked_user(self.account_links()?, user_id, link_key)
+            .await?;
+
+        self.get_fresh_token(link_key).await
+    }
+
+    /// Checks that the Google account `link_key` is linked to the user `user_id`, which
+    /// keeps a user from acting on another user's account.
+    async fn check_linked_user(
+        &self,
+        links: &dyn AccountLinks,
+        user_id: &str,
+        link_key: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let linked_user = links.linked_user(link_key).await?;
+        if linked_user.as_deref() != Some(user_id) {
+            return Err(format!("Google account {link_key} is not linked to the user").into());
+        }
+
+        Ok(())
+    }
+
+    fn account_links(&self) -> Result<&dyn AccountLinks, Box<dyn Error>> {
+        Ok(self
+            .account_links
+            .as_deref()
+            .ok_or("No account links store configured")?)
+    }
+}
+
+/// Returns `true` if Google rejected a revocation because it does not know the token.
+fn is_invalid_token(err: &(dyn Error + 'static)) -> bool {
+    err.downcast_ref::<GoogleError>()
+        .is_some_and(|err| err.code() == Some("invalid_token"))
+}
+
+impl fmt::Display for LinkConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Google account is already linked to another user")
+    }
+}
+
+impl Error for LinkConflict {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use futures_util::FutureExt;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryLinks(Mutex<HashMap<String, String>>);
+
+    impl AccountLinks for MemoryLinks {
+        fn linked_user<'a>(
+            &'a self,
+            link_key: &'a str,
+        ) -> BoxFuture<'a, Result<Option<String>, Box<dyn Error>>> {
+            async move { Ok(self.0.lock().unwrap().get(link_key).cloned()) }.boxed()
+        }
+
+        fn linked_accounts<'a>(
+            &'a self,
+            user_id: &'a str,
+        ) -> BoxFuture<'a, Result<Vec<String>, Box<dyn Error>>> {
+            async move {
+                let links = self.0.lock().unwrap();
+                Ok(links
+                    .iter()
+                    .filter(|(_, user)| *user == user_id)
+                    .map(|(key, _)| key.clone())
+                    .collect())
+            }
+            .boxed()
+        }
+
+        fn save_link_if_absent<'a>(
+            &'a self,
+            link_key: &'a str,
+            user_id: &'a str,
+        ) -> BoxFuture<'a, Result<Option<String>, Box<dyn Error>>> {
+            async move {
+                let mut links = self.0.lock().unwrap();
+                if let Some(existing) = links.get(link_key) {
+                    return Ok(Some(existing.clone()));
+                }
+                links.insert(link_key.to_string(), user_id.to_string());
+                Ok(None)
+            }
+            .boxed()
+        }
+
+        fn remove_link<'a>(
+            &'a self,
+            link_key: &'a str,
+        ) -> BoxFuture<'a, Result<(), Box<dyn Error>>> {
+            async move {
+                self.0.lock().unwrap().remove(link_key);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    fn google() -> Google {
+        Google::new(
+            "client-id".to_string(),
+            "secret".to_string(),
+            "https://app.example.com/callback".to_string(),
+        )
+        .with_account_links(MemoryLinks::default())
+    }
+
+    fn identity(sub: &str) -> UserInfo {
+        serde_json::from_value(json!({
+            "sub": sub,
+            "name": "Ada",
+            "picture": "",
+            "email": "ada@example.com",
+            "email_verified": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn link_key_uses_sub() {
+        assert_eq!(identity("1234").link_key(), "google:1234");
+    }
+
+    #[tokio::test]
+    async fn links_account_once_per_user() {
+        let google = google();
+        let identity = identity("1234");
+
+        assert_eq!(
+            google.link_account("user-1", &identity).await.unwrap(),
+            "google:1234"
+        );
+        assert!(google.link_account("user-1", &identity).await.is_ok());
+
+        let err = google.link_account("user-2", &identity).await.unwrap_err();
+        let conflict = err.downcast_ref::<LinkConflict>().unwrap();
+        assert_eq!(conflict.linked_user, "user-1");
+        assert_eq!(
+            google.linked_accounts("user-1").await.unwrap(),
+            ["google:1234"]
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_links_link_to_a_single_user() {
+        let google = google();
+        let identity = identity("1234");
+
+        let (first, second) = futures_util::join!(
+            google.link_account("user-1", &identity),
+            google.link_account("user-2", &identity)
+        );
+
+        assert!(first.is_ok() != second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn refuses_to_unlink_another_users_account() {
+        let google = google();
+        let token = Token::new(
+            "ya29.token".to_string(),
+            "Bearer".to_string(),
+            None,
+            None,
+            Default::default(),
+        );
+        google
+            .link_account("user-1", &identity("1234"))
+            .await
+            .unwrap();
+
+        let err = google
+            .unlink_account("user-2", "google:1234", &token)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not linked"));
+        assert_eq!(
+            google.linked_accounts("user-1").await.unwrap(),
+            ["google:1234"]
+        );
+    }
+
+    #[tokio::test]
+    async fn treats_unknown_tokens_as_revoked() {
+        let body = r#"{"error": "invalid_token", "error_description": "Token expired"}"#;
+        let response = http::Response::builder().status(400).body(body).unwrap();
+        let err: Box<dyn Error> = GoogleError::from_response(response.into()).await.into();
+        assert!(is_invalid_token(err.as_ref()));
+
+        let body = r#"{"error": "server_error"}"#;
+        let response = http::Response::builder().status(500).body(body).unwrap();
+        let err: Box<dyn Error> = GoogleError::from_response(response.into()).await.into();
+        assert!(!is_invalid_token(err.as_ref()));
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A BCP 47 language tag as found in the `locale` claim, e.g. `en`, `en-GB` or
+/// `zh-Hant-TW`.
+///
+/// With the `language-tags` feature, `Locale::to_language_tag` parses it fully.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale(String);
+
+impl Locale {
+    /// The tag as returned by Google.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language subtag, e.g. `en` for `en-GB`.
+    pub fn language(&self) -> &str {
+        self.subtags().next().unwrap_or_default()
+    }
+
+    /// The region subtag, e.g. `GB` for `en-GB` or `TW` for `zh-Hant-TW`, if any.
+    pub fn region(&self) -> Option<&str> {
+        self.subtags().skip(1).find(|subtag| {
+            (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+                || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+        })
+    }
+
+    /// Parses the tag into a `language_tags::LanguageTag`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the tag is not well-formed BCP 47.
+    #[cfg(feature = "language-tags")]
+    pub fn to_language_tag(&self) -> Result<language_tags::LanguageTag, language_tags::ParseError> {
+        language_tags::LanguageTag::parse(&self.0.replace('_', "-"))
+    }
+
+    /// Splits the tag into subtags, accepting the `_` separator some accounts report.
+    fn subtags(&self) -> impl Iterator<Item = &str> {
+        self.0.split(['-', '_'])
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Locale {
+    fn from(tag: String) -> Locale {
+        Locale(tag)
+    }
+}
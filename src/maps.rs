@@ -0,0 +1,456 @@
+//! Google Maps Platform web services: Geocoding, Distance Matrix and Time Zone.
+
+use std::error::Error;
+use std::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE;
+use chrono::{DateTime, FixedOffset, Utc};
+use reqwest::Url;
+use ring::hmac;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Google;
+
+const MAPS_API: &str = "https://maps.googleapis.com";
+
+/// A client for the Maps web services, authorized with an API key.
+#[derive(Clone, Copy)]
+pub struct Maps<'a> {
+    google: &'a Google,
+    api_key: &'a str,
+    signing_secret: Option<&'a str>,
+}
+
+/// A geographic coordinate.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatLng {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+/// Options shared by forward and reverse geocoding.
+#[derive(Debug, Clone, Default)]
+pub struct GeocodeQuery {
+    /// The language of the results, e.g. `de`.
+    pub language: Option<String>,
+    /// A ccTLD region code to bias results towards, e.g. `es`.
+    pub region: Option<String>,
+    /// Component filters, e.g. `country:DE|postal_code:10115`.
+    pub components: Option<String>,
+}
+
+/// A geocoded address.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct GeocodingResult {
+    pub place_id: String,
+    pub formatted_address: String,
+    pub address_components: Vec<AddressComponent>,
+    pub geometry: Geometry,
+    /// The feature types of the result, e.g. `street_address` or `locality`.
+    pub types: Vec<String>,
+    /// Set when the geocoder matched only part of the requested address.
+    pub partial_match: bool,
+}
+
+/// A part of an address, such as the street number or the country.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AddressComponent {
+    pub long_name: String,
+    pub short_name: String,
+    /// The component types, e.g. `route`, `locality` or `country`.
+    pub types: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Geometry {
+    pub location: LatLng,
+    /// The precision of `location`: `ROOFTOP`, `RANGE_INTERPOLATED`,
+    /// `GEOMETRIC_CENTER` or `APPROXIMATE`.
+    pub location_type: String,
+    /// The recommended viewport to display the result in.
+    pub viewport: Bounds,
+    /// The bounds of the whole feature, e.g. of a city.
+    pub bounds: Option<Bounds>,
+}
+
+/// A rectangle given by its corners.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct Bounds {
+    pub northeast: LatLng,
+    pub southwest: LatLng,
+}
+
+/// How a route is travelled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TravelMode {
+    #[default]
+    Driving,
+    Walking,
+    Bicycling,
+    Transit,
+}
+
+/// The assumptions used to predict `duration_in_traffic`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrafficModel {
+    #[default]
+    BestGuess,
+    Pessimistic,
+    Optimistic,
+}
+
+/// When a trip starts, required for traffic-aware durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepartureTime {
+    Now,
+    At(DateTime<Utc>),
+}
+
+/// Options for a distance matrix request.
+#[derive(Debug, Clone, Default)]
+pub struct DistanceMatrixQuery {
+    pub mode: TravelMode,
+    pub departure_time: Option<DepartureTime>,
+    /// The arrival time of transit trips, instead of a departure time.
+    pub arrival_time: Option<DateTime<Utc>>,
+    /// Only used for driving with a departure time.
+    pub traffic_model: Option<TrafficModel>,
+    /// Features to avoid, e.g. `tolls|highways|ferries`.
+    pub avoid: Option<String>,
+    /// `metric` or `imperial`, only affecting the `text` of distances.
+    pub units: Option<String>,
+    pub language: Option<String>,
+}
+
+/// Distances and durations between every origin and destination.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DistanceMatrix {
+    /// The origins as resolved addresses, in request order.
+    pub origin_addresses: Vec<String>,
+    pub destination_addresses: Vec<String>,
+    /// One row per origin.
+    pub rows: Vec<DistanceMatrixRow>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DistanceMatrixRow {
+    /// One element per destination.
+    pub elements: Vec<DistanceMatrixElement>,
+}
+
+/// The route between one origin and one destination.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DistanceMatrixElement {
+    /// `OK`, `NOT_FOUND` or `ZERO_RESULTS`; the other fields are only set for `OK`.
+    pub status: String,
+    /// The distance in meters.
+    pub distance: Option<TextValue>,
+    /// The duration in seconds, without traffic.
+    pub duration: Option<TextValue>,
+    /// The duration in seconds with traffic, when a departure time was given.
+    pub duration_in_traffic: Option<TextValue>,
+    /// The fare of transit trips, kept as raw JSON.
+    pub fare: Option<Value>,
+}
+
+/// A quantity both as a number and as localized text, e.g. `1234` and `1.2 km`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct TextValue {
+    pub text: String,
+    pub value: i64,
+}
+
+/// The time zone of a location at a given time.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimeZone {
+    /// The IANA time zone id, e.g. `America/Los_Angeles`.
+    pub time_zone_id: String,
+    /// The localized long name, e.g. `Pacific Daylight Time`.
+    pub time_zone_name: String,
+    /// The offset from UTC in seconds, without daylight saving time.
+    pub raw_offset: i32,
+    /// The daylight saving time offset in seconds, `0` outside of DST.
+    pub dst_offset: i32,
+}
+
+#[derive(Deserialize)]
+struct Status {
+    status: String,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GeocodeResponse {
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+impl fmt::Display for LatLng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.lat, self.lng)
+    }
+}
+
+impl GeocodeQuery {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+        if let Some(region) = &self.region {
+            params.push(("region", region.clone()));
+        }
+        if let Some(components) = &self.components {
+            params.push(("components", components.clone()));
+        }
+        params
+    }
+}
+
+impl TravelMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            TravelMode::Driving => "driving",
+            TravelMode::Walking => "walking",
+            TravelMode::Bicycling => "bicycling",
+            TravelMode::Transit => "transit",
+        }
+    }
+}
+
+impl TrafficModel {
+    fn as_str(self) -> &'static str {
+        match self {
+            TrafficModel::BestGuess => "best_guess",
+            TrafficModel::Pessimistic => "pessimistic",
+            TrafficModel::Optimistic => "optimistic",
+        }
+    }
+}
+
+impl DistanceMatrixQuery {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("mode", self.mode.as_str().to_string())];
+        match self.departure_time {
+            Some(DepartureTime::Now) => params.push(("departure_time", "now".to_string())),
+            Some(DepartureTime::At(time)) => {
+                params.push(("departure_time", time.timestamp().to_string()))
+            }
+            None => {}
+        }
+        if let Some(arrival_time) = self.arrival_time {
+            params.push(("arrival_time", arrival_time.timestamp().to_string()));
+        }
+        if let Some(traffic_model) = self.traffic_model {
+            params.push(("traffic_model", traffic_model.as_str().to_string()));
+        }
+        if let Some(avoid) = &self.avoid {
+            params.push(("avoid", avoid.clone()));
+        }
+        if let Some(units) = &self.units {
+            params.push(("units", units.clone()));
+        }
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+        params
+    }
+}
+
+impl DistanceMatrix {
+    /// Returns the element for the origin and destination at the given request indices.
+    pub fn element(&self, origin: usize, destination: usize) -> Option<&DistanceMatrixElement> {
+        self.rows.get(origin)?.elements.get(destination)
+    }
+}
+
+impl TimeZone {
+    /// The total offset from UTC at the requested time, including daylight saving time.
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        FixedOffset::east_opt(self.raw_offset + self.dst_offset)
+    }
+}
+
+impl GeocodingResult {
+    /// Returns the first address component of `component_type`, e.g. `postal_code`.
+    pub fn component(&self, component_type: &str) -> Option<&AddressComponent> {
+        self.address_components
+            .iter()
+            .find(|component| component.types.iter().any(|t| t == component_type))
+    }
+}
+
+impl Google {
+    /// Returns a Maps web services client that authorizes its requests with `api_key`.
+    pub fn maps<'a>(&'a self, api_key: &'a str) -> Maps<'a> {
+        Maps {
+            google: self,
+            api_key,
+            signing_secret: None,
+        }
+    }
+}
+
+impl<'a> Maps<'a> {
+    /// Signs every request with the URL signing secret of the API key, as required when
+    /// the key's quota is restricted to signed requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The URL-safe base64 signing secret from the Cloud console.
+    pub fn signing_secret(mut self, secret: &'a str) -> Maps<'a> {
+        self.signing_secret = Some(secret);
+        self
+    }
+
+    /// Geocodes an address into coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to geocode, e.g. `1600 Amphitheatre Parkway, Mountain
+    ///   View, CA`.
+    /// * `query` - Language, region and component options.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails or the API reports an error
+    /// status such as `REQUEST_DENIED`. No match is an empty list rather than an error.
+    pub async fn geocode(
+        &self,
+        address: &str,
+        query: &GeocodeQuery,
+    ) -> Result<Vec<GeocodingResult>, Box<dyn Error>> {
+        let mut params = query.params();
+        params.push(("address", address.to_string()));
+        let response: GeocodeResponse = self.get("/maps/api/geocode/json", params).await?;
+
+        Ok(response.results)
+    }
+
+    /// Looks up the addresses at a coordinate, the most specific first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails or the API reports an error
+    /// status.
+    pub async fn reverse_geocode(
+        &self,
+        location: LatLng,
+        query: &GeocodeQuery,
+    ) -> Result<Vec<GeocodingResult>, Box<dyn Error>> {
+        let mut params = query.params();
+        params.push(("latlng", location.to_string()));
+        let response: GeocodeResponse = self.get("/maps/api/geocode/json", params).await?;
+
+        Ok(response.results)
+    }
+
+    /// Computes travel distances and durations between every origin and destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `origins` - The starting points, as addresses, `lat,lng` pairs or
+    ///   `place_id:ChIJ...` references.
+    /// * `destinations` - The end points, in the same formats.
+    /// * `query` - Travel mode, timing and traffic options.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails or the API reports an error
+    /// status, e.g. `MAX_ELEMENTS_EXCEEDED`. Routes that cannot be computed are reported
+    /// in `DistanceMatrixElement::status` instead.
+    pub async fn distance_matrix(
+        &self,
+        origins: &[&str],
+        destinations: &[&str],
+        query: &DistanceMatrixQuery,
+    ) -> Result<DistanceMatrix, Box<dyn Error>> {
+        let mut params = query.params();
+        params.push(("origins", origins.join("|")));
+        params.push(("destinations", destinations.join("|")));
+
+        self.get("/maps/api/distancematrix/json", params).await
+    }
+
+    /// Looks up the time zone of a location at a point in time, which determines whether
+    /// daylight saving time applies.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location to look up.
+    /// * `timestamp` - The time the offsets are computed for.
+    /// * `language` - The language of `time_zone_name`, e.g. `de`.
+    ///
+    /// # Returns
+    ///
+    /// The time zone, or `None` for locations without one, such as the open sea.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails or the API reports an error
+    /// status.
+    pub async fn time_zone(
+        &self,
+        location: LatLng,
+        timestamp: DateTime<Utc>,
+        language: Option<&str>,
+    ) -> Result<Option<TimeZone>, Box<dyn Error>> {
+        let mut params = vec![
+            ("location", location.to_string()),
+            ("timestamp", timestamp.timestamp().to_string()),
+        ];
+        if let Some(language) = language {
+            params.push(("language", language.to_string()));
+        }
+        let time_zone: TimeZone = self.get("/maps/api/timezone/json", params).await?;
+
+        Ok((!time_zone.time_zone_id.is_empty()).then_some(time_zone))
+    }
+
+    /// Sends a request to a Maps web service, signing it when a secret is configured and
+    /// turning error statuses reported in the body into errors.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        mut params: Vec<(&'static str, String)>,
+    ) -> Result<T, Box<dyn Error>> {
+        params.push(("key", self.api_key.to_string()));
+        let mut url = Url::parse_with_params(&format!("{MAPS_API}{path}"), &params)?;
+
+        if let Some(secret) = self.signing_secret {
+            let key = hmac::Key::new(
+                hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+                &URL_SAFE.decode(secret)?,
+            );
+            let resource = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+            let signature = hmac::sign(&key, resource.as_bytes());
+            url.query_pairs_mut()
+                .append_pair("signature", &URL_SAFE.encode(signature.as_ref()));
+        }
+
+        let body: Value = self.google.send_json(self.google.http.get(url)).await?;
+        let status = Status::deserialize(&body)?;
+        if !matches!(status.status.as_str(), "OK" | "ZERO_RESULTS") {
+            return Err(match status.error_message {
+                Some(message) => format!("Maps API error {}: {message}", status.status),
+                None => format!("Maps API error {}", status.status),
+            }
+            .into());
+        }
+
+        Ok(serde_json::from_value(body)?)
+    }
+}
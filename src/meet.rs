@@ -0,0 +1,334 @@
+//! Google Meet REST API, for creating meeting spaces and reading conference records.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::{Page, paginate};
+use crate::{Google, Token};
+
+const MEET_API: &str = "https://meet.googleapis.com/v2";
+
+/// Scope granting access to create spaces and manage the spaces created by the app.
+pub const SCOPE_SPACE_CREATED: &str = "https://www.googleapis.com/auth/meetings.space.created";
+/// Scope granting read-only access to the user's spaces and conference records.
+pub const SCOPE_SPACE_READONLY: &str = "https://www.googleapis.com/auth/meetings.space.readonly";
+
+/// A client for the Google Meet REST API.
+#[derive(Clone, Copy)]
+pub struct Meet<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A meeting space, the virtual place where conferences are held.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Space {
+    /// The resource name, `spaces/{space}`.
+    pub name: String,
+    /// The URL to join the meeting, e.g. `https://meet.google.com/abc-mnop-xyz`.
+    pub meeting_uri: String,
+    /// The code of the meeting, e.g. `abc-mnop-xyz`.
+    pub meeting_code: String,
+    pub config: SpaceConfig,
+    /// The conference currently held in the space, if any.
+    pub active_conference: Option<ActiveConference>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpaceConfig {
+    pub access_type: Option<AccessType>,
+    /// `ALL` or `CREATOR_APP_ONLY`.
+    pub entry_point_access: Option<String>,
+}
+
+/// Who can join a meeting without knocking.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccessType {
+    /// Anyone with the link.
+    Open,
+    /// Users of the organization, and invited users from outside.
+    Trusted,
+    /// Only invited users.
+    Restricted,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActiveConference {
+    /// The resource name of the conference record, `conferenceRecords/{record}`.
+    pub conference_record: String,
+}
+
+/// A conference held in a space.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConferenceRecord {
+    /// The resource name, `conferenceRecords/{record}`.
+    pub name: String,
+    /// The resource name of the space.
+    pub space: String,
+    pub start_time: Option<DateTime<Utc>>,
+    /// Unset while the conference is ongoing.
+    pub end_time: Option<DateTime<Utc>>,
+    /// When the record is deleted, 30 days after the conference ended.
+    pub expire_time: Option<DateTime<Utc>>,
+}
+
+/// A page of conference records returned by `conferenceRecords.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConferenceRecordsPage {
+    pub conference_records: Vec<ConferenceRecord>,
+    pub next_page_token: Option<String>,
+}
+
+/// A participant of a conference; exactly one of the user fields is set.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Participant {
+    /// The resource name, `conferenceRecords/{record}/participants/{participant}`.
+    pub name: String,
+    pub earliest_start_time: Option<DateTime<Utc>>,
+    /// Unset while the participant is still in the conference.
+    pub latest_end_time: Option<DateTime<Utc>>,
+    pub signedin_user: Option<SignedinUser>,
+    pub anonymous_user: Option<DisplayNameUser>,
+    pub phone_user: Option<DisplayNameUser>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SignedinUser {
+    /// The resource name of the user, `users/{user}`, matching the People API id.
+    pub user: String,
+    pub display_name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DisplayNameUser {
+    pub display_name: String,
+}
+
+/// A page of participants returned by `participants.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParticipantsPage {
+    pub participants: Vec<Participant>,
+    pub next_page_token: Option<String>,
+    pub total_size: Option<u32>,
+}
+
+impl Page for ConferenceRecordsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for ParticipantsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Participant {
+    /// The display name of the participant, whatever the kind of user.
+    pub fn display_name(&self) -> Option<&str> {
+        self.signedin_user
+            .as_ref()
+            .map(|user| user.display_name.as_str())
+            .or(self
+                .anonymous_user
+                .as_ref()
+                .map(|user| user.display_name.as_str()))
+            .or(self
+                .phone_user
+                .as_ref()
+                .map(|user| user.display_name.as_str()))
+    }
+}
+
+impl Google {
+    /// Returns a Google Meet REST API client that authorizes its requests with `token`.
+    pub fn meet<'a>(&'a self, token: &'a Token) -> Meet<'a> {
+        Meet {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Meet<'a> {
+    /// Creates a meeting space.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_type` - Who can join without knocking, or `None` for the organization's
+    ///   default.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn create_space(
+        &self,
+        access_type: Option<AccessType>,
+    ) -> Result<Space, Box<dyn Error>> {
+        let body = match access_type {
+            Some(access_type) => json!({ "config": { "accessType": access_type } }),
+            None => json!({}),
+        };
+        let request = self
+            .google
+            .http
+            .post(format!("{MEET_API}/spaces"))
+            .bearer_auth(self.token.access_token())
+            .json(&body);
+
+        self.google.send_json(request).await
+    }
+
+    /// Gets a meeting space.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The resource name of the space, `spaces/{space}`, or `spaces/{code}`
+    ///   with the meeting code.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_space(&self, name: &str) -> Result<Space, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{MEET_API}/{name}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Ends the conference currently held in a space created by the app, disconnecting
+    /// every participant.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn end_active_conference(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{MEET_API}/{name}:endActiveConference"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({}));
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Lists a single page of the conference records of the user, the newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - An optional filter, e.g. `space.meeting_code = "abc-mnop-xyz"` or
+    ///   `start_time>="2024-01-01T00:00:00Z"`.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_conference_records(
+        &self,
+        filter: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<ConferenceRecordsPage, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{MEET_API}/conferenceRecords"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("filter", filter), ("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the conference records matching `filter`.
+    pub fn conference_records<'s>(
+        self,
+        filter: Option<&'s str>,
+    ) -> impl Stream<Item = Result<ConferenceRecordsPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_conference_records(filter, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Gets a conference record.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_conference_record(
+        &self,
+        name: &str,
+    ) -> Result<ConferenceRecord, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{MEET_API}/{name}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Lists a single page of the participants of a conference.
+    ///
+    /// # Arguments
+    ///
+    /// * `conference_record` - The resource name of the conference record.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_participants(
+        &self,
+        conference_record: &str,
+        page_token: Option<&str>,
+    ) -> Result<ParticipantsPage, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{MEET_API}/{conference_record}/participants"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the participants of a conference.
+    pub fn participants<'s>(
+        self,
+        conference_record: &'s str,
+    ) -> impl Stream<Item = Result<ParticipantsPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_participants(conference_record, page_token.as_deref())
+                .await
+        })
+    }
+}
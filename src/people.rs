@@ -0,0 +1,410 @@
+//! Google People API, the successor of the limited userinfo payload.
+
+use std::error::Error;
+
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{Page, paginate};
+use crate::{Google, Token};
+
+const PEOPLE_API: &str = "https://people.googleapis.com/v1";
+
+/// Scope granting read-only access to the "Other contacts" the user interacted with.
+pub const SCOPE_OTHER_CONTACTS_READONLY: &str =
+    "https://www.googleapis.com/auth/contacts.other.readonly";
+/// Scope granting read-only access to the Google Workspace domain directory.
+pub const SCOPE_DIRECTORY_READONLY: &str = "https://www.googleapis.com/auth/directory.readonly";
+
+/// A client for the People API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct People<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A person resource as returned by the People API. Only the fields listed in
+/// `person_fields` are populated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Person {
+    pub resource_name: String,
+    pub etag: Option<String>,
+    pub metadata: PersonMetadata,
+    pub names: Vec<Name>,
+    pub photos: Vec<Photo>,
+    pub email_addresses: Vec<EmailAddress>,
+    pub phone_numbers: Vec<PhoneNumber>,
+    pub organizations: Vec<Organization>,
+}
+
+/// Metadata about a person, including whether it was deleted since the last sync.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PersonMetadata {
+    pub sources: Vec<Source>,
+    pub deleted: bool,
+}
+
+/// Where a person field came from and whether it is the primary value.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FieldMetadata {
+    pub primary: bool,
+    pub verified: bool,
+    pub source: Option<Source>,
+}
+
+/// The source of a person field, e.g. the user's profile or a contact.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Source {
+    #[serde(rename = "type")]
+    pub source_type: String,
+    pub id: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Name {
+    pub metadata: FieldMetadata,
+    pub display_name: Option<String>,
+    pub family_name: Option<String>,
+    pub given_name: Option<String>,
+    pub middle_name: Option<String>,
+    pub display_name_last_first: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Photo {
+    pub metadata: FieldMetadata,
+    pub url: String,
+    pub default: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailAddress {
+    pub metadata: FieldMetadata,
+    pub value: String,
+    #[serde(rename = "type")]
+    pub email_type: Option<String>,
+    pub display_name: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PhoneNumber {
+    pub metadata: FieldMetadata,
+    pub value: String,
+    pub canonical_form: Option<String>,
+    #[serde(rename = "type")]
+    pub phone_type: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Organization {
+    pub metadata: FieldMetadata,
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub department: Option<String>,
+    #[serde(rename = "type")]
+    pub organization_type: Option<String>,
+    pub current: bool,
+}
+
+/// Options for listing the authenticated user's contacts.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionsQuery {
+    /// The number of connections per page, between 1 and 1000.
+    pub page_size: Option<u32>,
+    /// The sort order, e.g. `LAST_MODIFIED_ASCENDING` or `FIRST_NAME_ASCENDING`. Not
+    /// supported together with a sync token.
+    pub sort_order: Option<String>,
+    /// Whether the last page should carry a `next_sync_token` for incremental sync.
+    pub request_sync_token: bool,
+    /// A sync token from a previous listing. Only connections changed since then are
+    /// returned, with deleted ones flagged in `PersonMetadata::deleted`.
+    pub sync_token: Option<String>,
+}
+
+/// A page of connections returned by `people.connections.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConnectionsPage {
+    pub connections: Vec<Person>,
+    pub next_page_token: Option<String>,
+    /// Present on the last page when a sync token was requested.
+    pub next_sync_token: Option<String>,
+    pub total_items: Option<u32>,
+}
+
+/// A page of other contacts returned by `otherContacts.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OtherContactsPage {
+    pub other_contacts: Vec<Person>,
+    pub next_page_token: Option<String>,
+    pub total_size: Option<u32>,
+}
+
+/// Which directory entries to search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectorySource {
+    /// The profiles of the users of the domain.
+    DomainProfile,
+    /// The shared contacts of the domain.
+    DomainContact,
+}
+
+/// A page of domain directory people returned by `people.searchDirectoryPeople`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DirectoryPeoplePage {
+    pub people: Vec<Person>,
+    pub next_page_token: Option<String>,
+    pub total_size: Option<u32>,
+}
+
+impl DirectorySource {
+    fn as_str(self) -> &'static str {
+        match self {
+            DirectorySource::DomainProfile => "DIRECTORY_SOURCE_TYPE_DOMAIN_PROFILE",
+            DirectorySource::DomainContact => "DIRECTORY_SOURCE_TYPE_DOMAIN_CONTACT",
+        }
+    }
+}
+
+impl Page for ConnectionsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for OtherContactsPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for DirectoryPeoplePage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Google {
+    /// Returns a People API client that authorizes its requests with `token`.
+    pub fn people<'a>(&'a self, token: &'a Token) -> People<'a> {
+        People {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl People<'_> {
+    /// Fetches the profile of the authenticated user (`people/me`).
+    ///
+    /// # Arguments
+    ///
+    /// * `person_fields` - The fields to return, e.g. `["names", "photos",
+    ///   "emailAddresses", "phoneNumbers", "organizations"]`. Each field may require its
+    ///   own scope, such as `user.phonenumbers.read` for phone numbers.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, including a `GoogleError` when
+    /// the token lacks the scope needed for one of the requested fields.
+    pub async fn get_me(&self, person_fields: &[&str]) -> Result<Person, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{PEOPLE_API}/people/me"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("personFields", person_fields.join(","))]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Lists a single page of the authenticated user's contacts.
+    ///
+    /// # Arguments
+    ///
+    /// * `person_fields` - The fields to return for each contact, as in `get_me`.
+    /// * `query` - Paging and sync options.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. An expired sync token is
+    /// reported as a `GoogleError` with the `EXPIRED_SYNC_TOKEN` reason, after which a full
+    /// sync has to be performed.
+    pub async fn list_connections(
+        &self,
+        person_fields: &[&str],
+        query: &ConnectionsQuery,
+        page_token: Option<&str>,
+    ) -> Result<ConnectionsPage, Box<dyn Error>> {
+        let mut params = vec![("personFields", person_fields.join(","))];
+        if let Some(page_size) = query.page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        if let Some(sort_order) = &query.sort_order {
+            params.push(("sortOrder", sort_order.clone()));
+        }
+        if query.request_sync_token {
+            params.push(("requestSyncToken", "true".to_string()));
+        }
+        if let Some(sync_token) = &query.sync_token {
+            params.push(("syncToken", sync_token.clone()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{PEOPLE_API}/people/me/connections"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the authenticated user's contacts.
+    ///
+    /// When `query.request_sync_token` is set, the last page carries the
+    /// `next_sync_token` to pass as `query.sync_token` on the next incremental sync.
+    pub fn connections<'s>(
+        self,
+        person_fields: &'s [&'s str],
+        query: &'s ConnectionsQuery,
+    ) -> impl Stream<Item = Result<ConnectionsPage, Box<dyn Error>>> + 's
+    where
+        Self: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_connections(person_fields, query, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Lists a single page of the user's "Other contacts", the people they interacted
+    /// with without adding them as contacts.
+    ///
+    /// # Arguments
+    ///
+    /// * `read_mask` - The fields to return, among `names`, `emailAddresses`,
+    ///   `phoneNumbers`, `photos` and `metadata`.
+    /// * `page_size` - The number of contacts per page, at most 1000.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_other_contacts(
+        &self,
+        read_mask: &[&str],
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<OtherContactsPage, Box<dyn Error>> {
+        let mut params = vec![("readMask", read_mask.join(","))];
+        if let Some(page_size) = page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{PEOPLE_API}/otherContacts"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the user's "Other contacts".
+    pub fn other_contacts<'s>(
+        self,
+        read_mask: &'s [&'s str],
+        page_size: Option<u32>,
+    ) -> impl Stream<Item = Result<OtherContactsPage, Box<dyn Error>>> + 's
+    where
+        Self: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_other_contacts(read_mask, page_size, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Searches the Google Workspace domain directory by name, email address or phone
+    /// number prefix, e.g. for a people picker. Returns a single page of results.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The prefix to search for.
+    /// * `read_mask` - The fields to return for each person, as in `get_me`.
+    /// * `sources` - The directory entries to search.
+    /// * `page_size` - The number of people per page, at most 500.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the user is
+    /// not part of a Google Workspace domain.
+    pub async fn search_directory_people(
+        &self,
+        query: &str,
+        read_mask: &[&str],
+        sources: &[DirectorySource],
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<DirectoryPeoplePage, Box<dyn Error>> {
+        let mut params = vec![
+            ("query", query.to_string()),
+            ("readMask", read_mask.join(",")),
+        ];
+        for source in sources {
+            params.push(("sources", source.as_str().to_string()));
+        }
+        if let Some(page_size) = page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{PEOPLE_API}/people:searchDirectoryPeople"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the domain directory people matching `query`.
+    pub fn directory_people<'s>(
+        self,
+        query: &'s str,
+        read_mask: &'s [&'s str],
+        sources: &'s [DirectorySource],
+    ) -> impl Stream<Item = Result<DirectoryPeoplePage, Box<dyn Error>>> + 's
+    where
+        Self: 's,
+    {
+        paginate(move |page_token| async move {
+            self.search_directory_people(query, read_mask, sources, None, page_token.as_deref())
+                .await
+        })
+    }
+}
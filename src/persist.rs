@@ -0,0 +1,71 @@
+use std::error::Error;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Token;
+
+/// The version of the persisted token format written by `Token::to_persisted`. Bump it
+/// together with a migration step in `migrate` whenever the format changes.
+const FORMAT_VERSION: u64 = 1;
+
+#[derive(Serialize)]
+struct PersistedToken<'a> {
+    version: u64,
+    #[serde(flatten)]
+    token: &'a Token,
+}
+
+impl Token {
+    /// Serializes the token into the versioned JSON format meant for `TokenStore`
+    /// backends, e.g. to store it in a database column.
+    ///
+    /// The format carries a `version` field, so tokens stored by an older release of this
+    /// crate keep loading with `from_persisted` when fields are added. The tokens are
+    /// written in plain text and should be encrypted at rest.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if serialization fails.
+    pub fn to_persisted(&self) -> Result<String, Box<dyn Error>> {
+        let persisted = PersistedToken {
+            version: FORMAT_VERSION,
+            token: self,
+        };
+
+        Ok(serde_json::to_string(&persisted)?)
+    }
+
+    /// Parses a token written by `to_persisted`, upgrading it from older versions of the
+    /// format. JSON without a `version` field is read as a plain serialized `Token`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the JSON is not a persisted token, or if it was
+    /// written by a newer release of this crate using a format version it does not know.
+    pub fn from_persisted(json: &str) -> Result<Token, Box<dyn Error>> {
+        let mut value: Value = serde_json::from_str(json)?;
+        let fields = value
+            .as_object_mut()
+            .ok_or("Persisted token is not a JSON object")?;
+        let version = match fields.remove("version") {
+            Some(version) => version
+                .as_u64()
+                .ok_or("Persisted token has an invalid version")?,
+            None => 0,
+        };
+
+        Ok(serde_json::from_value(migrate(version, value)?)?)
+    }
+}
+
+/// Upgrades the fields of a persisted token from `version` to `FORMAT_VERSION`, one
+/// version at a time.
+fn migrate(version: u64, value: Value) -> Result<Value, Box<dyn Error>> {
+    match version {
+        FORMAT_VERSION => Ok(value),
+        // The unversioned format is the plain serialized `Token`, identical to version 1.
+        0 => migrate(1, value),
+        _ => Err(format!("Unsupported persisted token version {version}").into()),
+    }
+}
@@ -0,0 +1,247 @@
+//! Google Photos Library API.
+
+use std::error::Error;
+
+use bytes::Bytes;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::{Page, paginate};
+use crate::de::option_number_from_string;
+use crate::{Google, Token};
+
+const PHOTOS_API: &str = "https://photoslibrary.googleapis.com/v1";
+
+/// Scope granting read-only access to the albums and media items created by the app.
+pub const SCOPE_READONLY_APP_CREATED: &str =
+    "https://www.googleapis.com/auth/photoslibrary.readonly.appcreateddata";
+
+/// A client for the Photos Library API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Photos<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// An album.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Album {
+    pub id: String,
+    pub title: String,
+    pub product_url: Option<String>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub media_items_count: Option<u64>,
+    pub cover_photo_base_url: Option<String>,
+    pub cover_photo_media_item_id: Option<String>,
+}
+
+/// A photo or video.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaItem {
+    pub id: String,
+    pub description: Option<String>,
+    pub product_url: Option<String>,
+    /// The base URL of the bytes, valid for 60 minutes. Use `download_url` or `sized_url`
+    /// rather than fetching it as-is.
+    pub base_url: String,
+    pub mime_type: String,
+    pub filename: String,
+    pub media_metadata: MediaMetadata,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaMetadata {
+    pub creation_time: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub width: Option<u32>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub height: Option<u32>,
+    /// Camera details of a photo.
+    pub photo: Option<Value>,
+    /// Frame rate and processing status of a video.
+    pub video: Option<Value>,
+}
+
+/// Filters for `Photos::search_media_items`.
+#[derive(Debug, Clone, Default)]
+pub struct MediaSearch {
+    /// Only items in this album. Cannot be combined with `date_ranges`.
+    pub album_id: Option<String>,
+    /// Only items created within one of these inclusive date ranges.
+    pub date_ranges: Vec<(NaiveDate, NaiveDate)>,
+    /// The number of items per page, at most 100.
+    pub page_size: Option<u32>,
+}
+
+/// A page of albums returned by `albums.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AlbumList {
+    pub albums: Vec<Album>,
+    pub next_page_token: Option<String>,
+}
+
+/// A page of media items returned by `mediaItems.list` or `mediaItems.search`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaItemList {
+    pub media_items: Vec<MediaItem>,
+    pub next_page_token: Option<String>,
+}
+
+impl Page for AlbumList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for MediaItemList {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl MediaItem {
+    /// Returns `true` if the item is a video.
+    pub fn is_video(&self) -> bool {
+        self.media_metadata.video.is_some() || self.mime_type.starts_with("video/")
+    }
+
+    /// The URL of the original bytes: `=d` for photos, keeping their metadata, and `=dv`
+    /// for videos.
+    pub fn download_url(&self) -> String {
+        if self.is_video() {
+            format!("{}=dv", self.base_url)
+        } else {
+            format!("{}=d", self.base_url)
+        }
+    }
+
+    /// The URL of a photo or video thumbnail scaled to fit within `width` x `height`,
+    /// keeping the aspect ratio.
+    pub fn sized_url(&self, width: u32, height: u32) -> String {
+        format!("{}=w{width}-h{height}", self.base_url)
+    }
+}
+
+impl Google {
+    /// Returns a Photos Library API client that authorizes its requests with `token`.
+    pub fn photos<'a>(&'a self, token: &'a Token) -> Photos<'a> {
+        Photos {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Photos<'a> {
+    /// Lists a single page of albums.
+    pub async fn list_albums(&self, page_token: Option<&str>) -> Result<AlbumList, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{PHOTOS_API}/albums"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of albums.
+    pub fn albums(self) -> impl Stream<Item = Result<AlbumList, Box<dyn Error>>> + 'a {
+        paginate(move |page_token| async move { self.list_albums(page_token.as_deref()).await })
+    }
+
+    /// Lists a single page of all media items in the library, newest first.
+    pub async fn list_media_items(
+        &self,
+        page_token: Option<&str>,
+    ) -> Result<MediaItemList, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{PHOTOS_API}/mediaItems"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of all media items in the library.
+    pub fn media_items(self) -> impl Stream<Item = Result<MediaItemList, Box<dyn Error>>> + 'a {
+        paginate(
+            move |page_token| async move { self.list_media_items(page_token.as_deref()).await },
+        )
+    }
+
+    /// Searches a single page of media items by album or creation date.
+    pub async fn search_media_items(
+        &self,
+        search: &MediaSearch,
+        page_token: Option<&str>,
+    ) -> Result<MediaItemList, Box<dyn Error>> {
+        let mut body = json!({});
+        if let Some(album_id) = &search.album_id {
+            body["albumId"] = json!(album_id);
+        }
+        if !search.date_ranges.is_empty() {
+            let ranges: Vec<Value> = search
+                .date_ranges
+                .iter()
+                .map(|(start, end)| json!({ "startDate": date(start), "endDate": date(end) }))
+                .collect();
+            body["filters"] = json!({ "dateFilter": { "ranges": ranges } });
+        }
+        if let Some(page_size) = search.page_size {
+            body["pageSize"] = json!(page_size);
+        }
+        if let Some(page_token) = page_token {
+            body["pageToken"] = json!(page_token);
+        }
+
+        let request = self
+            .google
+            .http
+            .post(format!("{PHOTOS_API}/mediaItems:search"))
+            .bearer_auth(self.token.access_token())
+            .json(&body);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of media items matching `search`.
+    pub fn search<'s>(
+        self,
+        search: &'s MediaSearch,
+    ) -> impl Stream<Item = Result<MediaItemList, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.search_media_items(search, page_token.as_deref()).await
+        })
+    }
+
+    /// Downloads the original bytes of a media item through `MediaItem::download_url`.
+    ///
+    /// The item must have been fetched within the last 60 minutes, after which its
+    /// `base_url` expires.
+    pub async fn download(&self, item: &MediaItem) -> Result<Bytes, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(item.download_url())
+            .bearer_auth(self.token.access_token());
+
+        Ok(self.google.send(request).await?.bytes().await?)
+    }
+}
+
+fn date(date: &NaiveDate) -> Value {
+    json!({ "year": date.year(), "month": date.month(), "day": date.day() })
+}
@@ -0,0 +1,389 @@
+//! Places API (New), for place search, autocomplete and details.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::{Auth, encode_path};
+use crate::{Google, Token};
+
+const PLACES_API: &str = "https://places.googleapis.com/v1";
+
+/// A client for the Places API (New).
+#[derive(Clone, Copy)]
+pub struct Places<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// A geographic coordinate, as used by the Places API.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct LatLng {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A circular area, used to bias or restrict results.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct Circle {
+    pub center: LatLng,
+    /// The radius in meters, at most 50000.
+    pub radius: f64,
+}
+
+/// A place. Only the fields requested through the field mask are populated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Place {
+    /// The resource name, `places/{id}`.
+    pub name: String,
+    pub id: String,
+    pub display_name: Option<LocalizedText>,
+    pub formatted_address: Option<String>,
+    pub short_formatted_address: Option<String>,
+    pub location: Option<LatLng>,
+    pub types: Vec<String>,
+    pub primary_type: Option<String>,
+    pub rating: Option<f64>,
+    pub user_rating_count: Option<u32>,
+    /// E.g. `PRICE_LEVEL_MODERATE`.
+    pub price_level: Option<String>,
+    /// E.g. `OPERATIONAL` or `CLOSED_PERMANENTLY`.
+    pub business_status: Option<String>,
+    pub national_phone_number: Option<String>,
+    pub international_phone_number: Option<String>,
+    pub website_uri: Option<String>,
+    pub google_maps_uri: Option<String>,
+    pub utc_offset_minutes: Option<i32>,
+    /// The opening hours, kept as raw JSON.
+    pub regular_opening_hours: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalizedText {
+    pub text: String,
+    pub language_code: Option<String>,
+}
+
+/// A page of places returned by a text search.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlacesPage {
+    pub places: Vec<Place>,
+    /// Only present when `next_page_token` was included in the field mask.
+    pub next_page_token: Option<String>,
+}
+
+/// Options for a text search, e.g. `pizza in New York`.
+#[derive(Debug, Clone, Default)]
+pub struct TextSearchQuery {
+    pub text_query: String,
+    pub language_code: Option<String>,
+    pub region_code: Option<String>,
+    /// Only return places of this type, e.g. `restaurant`.
+    pub included_type: Option<String>,
+    pub open_now: bool,
+    pub min_rating: Option<f64>,
+    /// The number of places per page, between 1 and 20.
+    pub page_size: Option<u32>,
+    pub page_token: Option<String>,
+    pub location_bias: Option<Circle>,
+}
+
+/// Options for a nearby search around a point.
+#[derive(Debug, Clone, Default)]
+pub struct NearbySearchQuery {
+    pub location_restriction: Circle,
+    pub included_types: Vec<String>,
+    pub excluded_types: Vec<String>,
+    /// The number of places, between 1 and 20.
+    pub max_result_count: Option<u32>,
+    /// `POPULARITY` or `DISTANCE`.
+    pub rank_preference: Option<String>,
+    pub language_code: Option<String>,
+    pub region_code: Option<String>,
+}
+
+/// Options for autocomplete predictions.
+#[derive(Debug, Clone, Default)]
+pub struct AutocompleteQuery {
+    pub language_code: Option<String>,
+    pub region_code: Option<String>,
+    /// Up to five primary types to restrict predictions to, e.g. `locality`.
+    pub included_primary_types: Vec<String>,
+    /// Restrict predictions to these CLDR region codes, e.g. `de`.
+    pub included_region_codes: Vec<String>,
+    pub location_bias: Option<Circle>,
+    /// A token grouping the autocomplete requests and the final place details request
+    /// of one user session into a single billable session.
+    pub session_token: Option<String>,
+}
+
+/// An autocomplete suggestion: either a place or a query to run as a text search.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Suggestion {
+    pub place_prediction: Option<PlacePrediction>,
+    pub query_prediction: Option<QueryPrediction>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlacePrediction {
+    pub place_id: String,
+    pub text: LocalizedText,
+    pub structured_format: Option<StructuredFormat>,
+    pub types: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueryPrediction {
+    pub text: LocalizedText,
+}
+
+/// A prediction split into its main text, e.g. the name, and its secondary text, e.g.
+/// the city.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StructuredFormat {
+    pub main_text: LocalizedText,
+    pub secondary_text: Option<LocalizedText>,
+}
+
+#[derive(Deserialize)]
+struct NearbyResponse {
+    #[serde(default)]
+    places: Vec<Place>,
+}
+
+#[derive(Deserialize)]
+struct AutocompleteResponse {
+    #[serde(default)]
+    suggestions: Vec<Suggestion>,
+}
+
+/// Inserts `value` under `name` unless it is `None`.
+fn set<T: Serialize>(body: &mut Value, name: &str, value: Option<T>) {
+    if let Some(value) = value {
+        body[name] = json!(value);
+    }
+}
+
+impl TextSearchQuery {
+    fn to_json(&self) -> Value {
+        let mut body = json!({ "textQuery": self.text_query });
+        set(&mut body, "languageCode", self.language_code.as_ref());
+        set(&mut body, "regionCode", self.region_code.as_ref());
+        set(&mut body, "includedType", self.included_type.as_ref());
+        set(&mut body, "openNow", self.open_now.then_some(true));
+        set(&mut body, "minRating", self.min_rating);
+        set(&mut body, "pageSize", self.page_size);
+        set(&mut body, "pageToken", self.page_token.as_ref());
+        set(
+            &mut body,
+            "locationBias",
+            self.location_bias.map(|circle| json!({ "circle": circle })),
+        );
+        body
+    }
+}
+
+impl NearbySearchQuery {
+    fn to_json(&self) -> Value {
+        let mut body = json!({
+            "locationRestriction": { "circle": self.location_restriction },
+        });
+        set(
+            &mut body,
+            "includedTypes",
+            (!self.included_types.is_empty()).then_some(&self.included_types),
+        );
+        set(
+            &mut body,
+            "excludedTypes",
+            (!self.excluded_types.is_empty()).then_some(&self.excluded_types),
+        );
+        set(&mut body, "maxResultCount", self.max_result_count);
+        set(&mut body, "rankPreference", self.rank_preference.as_ref());
+        set(&mut body, "languageCode", self.language_code.as_ref());
+        set(&mut body, "regionCode", self.region_code.as_ref());
+        body
+    }
+}
+
+impl AutocompleteQuery {
+    fn to_json(&self, input: &str) -> Value {
+        let mut body = json!({ "input": input });
+        set(&mut body, "languageCode", self.language_code.as_ref());
+        set(&mut body, "regionCode", self.region_code.as_ref());
+        set(
+            &mut body,
+            "includedPrimaryTypes",
+            (!self.included_primary_types.is_empty()).then_some(&self.included_primary_types),
+        );
+        set(
+            &mut body,
+            "includedRegionCodes",
+            (!self.included_region_codes.is_empty()).then_some(&self.included_region_codes),
+        );
+        set(
+            &mut body,
+            "locationBias",
+            self.location_bias.map(|circle| json!({ "circle": circle })),
+        );
+        set(&mut body, "sessionToken", self.session_token.as_ref());
+        body
+    }
+}
+
+impl Google {
+    /// Returns a Places API client that authorizes its requests with `token`, which needs
+    /// the `cloud-platform` scope.
+    pub fn places<'a>(&'a self, token: &'a Token) -> Places<'a> {
+        Places {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a Places API client that authorizes its requests with an API key.
+    pub fn places_with_key<'a>(&'a self, api_key: &'a str) -> Places<'a> {
+        Places {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl Places<'_> {
+    /// Fetches the details of a place.
+    ///
+    /// # Arguments
+    ///
+    /// * `place_id` - The id of the place, e.g. from a search or autocomplete.
+    /// * `fields` - The fields to return, e.g. `["displayName", "formattedAddress"]`.
+    ///   Billing depends on the requested fields; `["*"]` returns all of them.
+    /// * `session_token` - The token of the autocomplete session that led to this place.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_place(
+        &self,
+        place_id: &str,
+        fields: &[&str],
+        session_token: Option<&str>,
+    ) -> Result<Place, Box<dyn Error>> {
+        let mut request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .get(format!("{PLACES_API}/places/{}", encode_path(place_id))),
+            )
+            .header("X-Goog-FieldMask", fields.join(","));
+        if let Some(session_token) = session_token {
+            request = request.query(&[("sessionToken", session_token)]);
+        }
+
+        self.google.send_json(request).await
+    }
+
+    /// Searches places matching a text query.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The search text and options.
+    /// * `fields` - The place fields to return, e.g. `["id", "displayName"]`. The
+    ///   `next_page_token` is requested as well.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn search_text(
+        &self,
+        query: &TextSearchQuery,
+        fields: &[&str],
+    ) -> Result<PlacesPage, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{PLACES_API}/places:searchText")),
+            )
+            .header("X-Goog-FieldMask", places_field_mask(fields, true))
+            .json(&query.to_json());
+
+        self.google.send_json(request).await
+    }
+
+    /// Searches places of the given types within a circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The area and options.
+    /// * `fields` - The place fields to return, e.g. `["id", "displayName"]`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn search_nearby(
+        &self,
+        query: &NearbySearchQuery,
+        fields: &[&str],
+    ) -> Result<Vec<Place>, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{PLACES_API}/places:searchNearby")),
+            )
+            .header("X-Goog-FieldMask", places_field_mask(fields, false))
+            .json(&query.to_json());
+        let response: NearbyResponse = self.google.send_json(request).await?;
+
+        Ok(response.places)
+    }
+
+    /// Returns place and query predictions for the text a user is typing.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn autocomplete(
+        &self,
+        input: &str,
+        query: &AutocompleteQuery,
+    ) -> Result<Vec<Suggestion>, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{PLACES_API}/places:autocomplete")),
+            )
+            .json(&query.to_json(input));
+        let response: AutocompleteResponse = self.google.send_json(request).await?;
+
+        Ok(response.suggestions)
+    }
+}
+
+/// Builds the field mask of a search response from place field names.
+fn places_field_mask(fields: &[&str], paginated: bool) -> String {
+    let mut mask: Vec<String> = fields
+        .iter()
+        .map(|field| format!("places.{field}"))
+        .collect();
+    if paginated {
+        mask.push("nextPageToken".to_string());
+    }
+    mask.join(",")
+}
@@ -0,0 +1,331 @@
+//! Google Play Developer API, for verifying in-app purchases and subscriptions.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::encode_path;
+use crate::de::option_number_from_string;
+use crate::{Google, Token};
+
+const ANDROID_PUBLISHER_API: &str = "https://androidpublisher.googleapis.com/androidpublisher/v3";
+
+/// Scope granting access to the Play Console data of the apps, typically requested for a
+/// service account linked to the Play Console.
+pub const SCOPE_ANDROID_PUBLISHER: &str = "https://www.googleapis.com/auth/androidpublisher";
+
+/// A client for the Google Play Developer API.
+#[derive(Clone, Copy)]
+pub struct PlayDeveloper<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// The state of a one-time product purchase.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProductPurchase {
+    pub order_id: Option<String>,
+    /// The purchase time in milliseconds since the epoch.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub purchase_time_millis: Option<i64>,
+    /// `0` purchased, `1` canceled or `2` pending.
+    pub purchase_state: Option<u8>,
+    /// `0` yet to be consumed or `1` consumed.
+    pub consumption_state: Option<u8>,
+    /// `0` yet to be acknowledged or `1` acknowledged.
+    pub acknowledgement_state: Option<u8>,
+    /// `0` test purchase, `1` promo or `2` rewarded; absent for regular purchases.
+    pub purchase_type: Option<u8>,
+    pub product_id: Option<String>,
+    pub quantity: Option<u32>,
+    pub region_code: Option<String>,
+    pub obfuscated_external_account_id: Option<String>,
+    pub obfuscated_external_profile_id: Option<String>,
+    pub developer_payload: Option<String>,
+}
+
+/// The state of a subscription purchase.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubscriptionPurchase {
+    pub subscription_state: SubscriptionState,
+    pub acknowledgement_state: AcknowledgementState,
+    pub latest_order_id: Option<String>,
+    /// The token of the purchase this one replaces after an upgrade, downgrade or
+    /// resubscription; that purchase should no longer grant entitlement.
+    pub linked_purchase_token: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub region_code: Option<String>,
+    /// One item per subscribed product; usually exactly one.
+    pub line_items: Vec<SubscriptionLineItem>,
+    /// Present only for license tester purchases.
+    pub test_purchase: Option<serde_json::Value>,
+    pub external_account_identifiers: Option<ExternalAccountIdentifiers>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubscriptionState {
+    #[default]
+    #[serde(rename = "SUBSCRIPTION_STATE_UNSPECIFIED")]
+    Unspecified,
+    /// Created but still awaiting payment.
+    #[serde(rename = "SUBSCRIPTION_STATE_PENDING")]
+    Pending,
+    #[serde(rename = "SUBSCRIPTION_STATE_ACTIVE")]
+    Active,
+    #[serde(rename = "SUBSCRIPTION_STATE_PAUSED")]
+    Paused,
+    /// Payment failed, but the user keeps access while Google retries.
+    #[serde(rename = "SUBSCRIPTION_STATE_IN_GRACE_PERIOD")]
+    InGracePeriod,
+    /// Payment failed and the user lost access while Google retries.
+    #[serde(rename = "SUBSCRIPTION_STATE_ON_HOLD")]
+    OnHold,
+    /// Canceled, but still granting access until it expires.
+    #[serde(rename = "SUBSCRIPTION_STATE_CANCELED")]
+    Canceled,
+    #[serde(rename = "SUBSCRIPTION_STATE_EXPIRED")]
+    Expired,
+    #[serde(rename = "SUBSCRIPTION_STATE_PENDING_PURCHASE_CANCELED")]
+    PendingPurchaseCanceled,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AcknowledgementState {
+    #[default]
+    #[serde(rename = "ACKNOWLEDGEMENT_STATE_UNSPECIFIED")]
+    Unspecified,
+    #[serde(rename = "ACKNOWLEDGEMENT_STATE_PENDING")]
+    Pending,
+    #[serde(rename = "ACKNOWLEDGEMENT_STATE_ACKNOWLEDGED")]
+    Acknowledged,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubscriptionLineItem {
+    pub product_id: String,
+    pub expiry_time: Option<DateTime<Utc>>,
+    pub auto_renewing_plan: Option<AutoRenewingPlan>,
+    pub offer_details: Option<OfferDetails>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoRenewingPlan {
+    pub auto_renew_enabled: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OfferDetails {
+    pub base_plan_id: String,
+    pub offer_id: Option<String>,
+    pub offer_tags: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExternalAccountIdentifiers {
+    pub obfuscated_external_account_id: Option<String>,
+    pub obfuscated_external_profile_id: Option<String>,
+}
+
+impl ProductPurchase {
+    /// Whether the purchase completed, as opposed to being canceled or pending.
+    pub fn is_purchased(&self) -> bool {
+        self.purchase_state == Some(0)
+    }
+
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledgement_state == Some(1)
+    }
+
+    pub fn is_consumed(&self) -> bool {
+        self.consumption_state == Some(1)
+    }
+
+    pub fn purchase_time(&self) -> Option<DateTime<Utc>> {
+        self.purchase_time_millis
+            .and_then(DateTime::from_timestamp_millis)
+    }
+}
+
+impl SubscriptionPurchase {
+    /// Whether the subscription currently grants access, i.e. it is active, in its grace
+    /// period, or canceled but not yet expired.
+    pub fn is_entitled(&self) -> bool {
+        match self.subscription_state {
+            SubscriptionState::Active | SubscriptionState::InGracePeriod => true,
+            SubscriptionState::Canceled => self.expiry_time().is_some_and(|time| time > Utc::now()),
+            _ => false,
+        }
+    }
+
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledgement_state == AcknowledgementState::Acknowledged
+    }
+
+    /// The latest expiry time among the line items.
+    pub fn expiry_time(&self) -> Option<DateTime<Utc>> {
+        self.line_items
+            .iter()
+            .filter_map(|item| item.expiry_time)
+            .max()
+    }
+}
+
+impl Google {
+    /// Returns a Google Play Developer API client that authorizes its requests with
+    /// `token`, usually a service account token with `SCOPE_ANDROID_PUBLISHER`.
+    pub fn play_developer<'a>(&'a self, token: &'a Token) -> PlayDeveloper<'a> {
+        PlayDeveloper {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl PlayDeveloper<'_> {
+    /// Gets the state of a one-time product purchase.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - The package name of the app, e.g. `com.example.app`.
+    /// * `product_id` - The in-app product id.
+    /// * `purchase_token` - The token the app received for the purchase.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the token is
+    /// invalid or belongs to another app.
+    pub async fn get_product_purchase(
+        &self,
+        package_name: &str,
+        product_id: &str,
+        purchase_token: &str,
+    ) -> Result<ProductPurchase, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(product_url(package_name, product_id, purchase_token))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Acknowledges a one-time product purchase. Purchases not acknowledged within three
+    /// days are refunded.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - The package name of the app.
+    /// * `product_id` - The in-app product id.
+    /// * `purchase_token` - The token the app received for the purchase.
+    /// * `developer_payload` - Optional information to attach to the purchase.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn acknowledge_product_purchase(
+        &self,
+        package_name: &str,
+        product_id: &str,
+        purchase_token: &str,
+        developer_payload: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let url = product_url(package_name, product_id, purchase_token);
+        let request = self
+            .google
+            .http
+            .post(format!("{url}:acknowledge"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "developerPayload": developer_payload }));
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Gets the state of a subscription purchase.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - The package name of the app.
+    /// * `purchase_token` - The token the app received for the subscription.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_subscription_purchase(
+        &self,
+        package_name: &str,
+        purchase_token: &str,
+    ) -> Result<SubscriptionPurchase, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!(
+                "{ANDROID_PUBLISHER_API}/applications/{}/purchases/subscriptionsv2/tokens/{}",
+                encode_path(package_name),
+                encode_path(purchase_token)
+            ))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Acknowledges a subscription purchase. Subscriptions not acknowledged within three
+    /// days are refunded.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - The package name of the app.
+    /// * `subscription_id` - The subscription product id, `SubscriptionLineItem::product_id`.
+    /// * `purchase_token` - The token the app received for the subscription.
+    /// * `developer_payload` - Optional information to attach to the purchase.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn acknowledge_subscription_purchase(
+        &self,
+        package_name: &str,
+        subscription_id: &str,
+        purchase_token: &str,
+        developer_payload: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let url = format!(
+            "{ANDROID_PUBLISHER_API}/applications/{}/purchases/subscriptions/{}/tokens/{}",
+            encode_path(package_name),
+            encode_path(subscription_id),
+            encode_path(purchase_token)
+        );
+        let request = self
+            .google
+            .http
+            .post(format!("{url}:acknowledge"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "developerPayload": developer_payload }));
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+}
+
+fn product_url(package_name: &str, product_id: &str, purchase_token: &str) -> String {
+    format!(
+        "{ANDROID_PUBLISHER_API}/applications/{}/purchases/products/{}/tokens/{}",
+        encode_path(package_name),
+        encode_path(product_id),
+        encode_path(purchase_token)
+    )
+}
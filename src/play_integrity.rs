@@ -0,0 +1,191 @@
+//! Play Integrity API, for checking that requests come from a genuine app on a genuine device.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::encode_path;
+use crate::de::option_number_from_string;
+use crate::{Google, Token};
+
+const PLAY_INTEGRITY_API: &str = "https://playintegrity.googleapis.com/v1";
+
+/// Scope granting access to decode integrity tokens.
+pub const SCOPE_PLAY_INTEGRITY: &str = "https://www.googleapis.com/auth/playintegrity";
+
+/// A client for the Play Integrity API.
+#[derive(Clone, Copy)]
+pub struct PlayIntegrity<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// The verdicts of a decoded integrity token.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IntegrityVerdict {
+    pub request_details: RequestDetails,
+    pub app_integrity: AppIntegrity,
+    pub device_integrity: DeviceIntegrity,
+    pub account_details: AccountDetails,
+}
+
+/// Details of the request the token was issued for, to check against the expected nonce
+/// or request hash.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequestDetails {
+    pub request_package_name: String,
+    /// The nonce of a classic request.
+    pub nonce: Option<String>,
+    /// The request hash of a standard request.
+    pub request_hash: Option<String>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub timestamp_millis: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppIntegrity {
+    pub app_recognition_verdict: AppRecognitionVerdict,
+    pub package_name: Option<String>,
+    pub certificate_sha256_digest: Vec<String>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub version_code: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AppRecognitionVerdict {
+    /// The app and certificate match the versions distributed by Google Play.
+    PlayRecognized,
+    /// The certificate or package name does not match Google Play records.
+    UnrecognizedVersion,
+    /// Integrity could not be evaluated.
+    #[default]
+    Unevaluated,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeviceIntegrity {
+    /// Empty when the device fails every integrity check.
+    pub device_recognition_verdict: Vec<DeviceRecognitionVerdict>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeviceRecognitionVerdict {
+    /// A genuine Android device with Google Play services.
+    MeetsDeviceIntegrity,
+    /// A genuine device additionally passing hardware-backed checks.
+    MeetsStrongIntegrity,
+    /// Possibly an emulator or a modified device, but with Google Play services.
+    MeetsBasicIntegrity,
+    /// An emulator running Google Play Games for PC.
+    MeetsVirtualIntegrity,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AccountDetails {
+    pub app_licensing_verdict: AppLicensingVerdict,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AppLicensingVerdict {
+    /// The user installed or bought the app on Google Play.
+    Licensed,
+    Unlicensed,
+    #[default]
+    Unevaluated,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeResponse {
+    token_payload_external: IntegrityVerdict,
+}
+
+impl RequestDetails {
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp_millis
+            .and_then(DateTime::from_timestamp_millis)
+    }
+}
+
+impl IntegrityVerdict {
+    /// Whether the app is recognized by Google Play.
+    pub fn is_play_recognized(&self) -> bool {
+        self.app_integrity.app_recognition_verdict == AppRecognitionVerdict::PlayRecognized
+    }
+
+    /// Whether the device meets the given integrity level. Google lists every level the
+    /// device meets, so a device meeting strong integrity also reports device integrity.
+    pub fn meets(&self, verdict: DeviceRecognitionVerdict) -> bool {
+        self.device_integrity
+            .device_recognition_verdict
+            .contains(&verdict)
+    }
+
+    /// Whether the user is licensed for the app.
+    pub fn is_licensed(&self) -> bool {
+        self.account_details.app_licensing_verdict == AppLicensingVerdict::Licensed
+    }
+}
+
+impl Google {
+    /// Returns a Play Integrity API client that authorizes its requests with `token`,
+    /// usually a service account token with `SCOPE_PLAY_INTEGRITY`.
+    pub fn play_integrity<'a>(&'a self, token: &'a Token) -> PlayIntegrity<'a> {
+        PlayIntegrity {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl PlayIntegrity<'_> {
+    /// Decrypts and verifies an integrity token obtained by the app, returning its
+    /// verdicts.
+    ///
+    /// The caller must still check `RequestDetails` against the nonce or request hash it
+    /// expects, and reject tokens whose timestamp is too old.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_name` - The package name of the app the token was issued to.
+    /// * `integrity_token` - The token sent by the app.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the token is
+    /// malformed or was issued to another app.
+    pub async fn decode_integrity_token(
+        &self,
+        package_name: &str,
+        integrity_token: &str,
+    ) -> Result<IntegrityVerdict, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{PLAY_INTEGRITY_API}/{}:decodeIntegrityToken",
+                encode_path(package_name)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "integrityToken": integrity_token }));
+        let response: DecodeResponse = self.google.send_json(request).await?;
+
+        Ok(response.token_payload_external)
+    }
+}
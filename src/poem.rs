@@ -0,0 +1,104 @@
+//! Poem integration: endpoints for the sign-in flow and a `GoogleUser` extractor.
+//!
+//! The endpoints expect the `Google` client as route data:
+//!
+//! ```no_run
+//! # fn client() -> async_google_auth::Google { unimplemented!() }
+//! use async_google_auth::poem::{GoogleUser, routes};
+//! use poem::{EndpointExt, Route, get, handler};
+//!
+//! #[handler]
+//! fn profile(user: GoogleUser) -> String {
+//!     format!("Hello {}", user.0.email)
+//! }
+//!
+//! let app = Route::new()
+//!     .nest("/auth", routes())
+//!     .at("/profile", get(profile))
+//!     .data(client());
+//! ```
+//!
+//! With the app's callback URL pointing to `/auth/callback`, `/auth/login` signs the user
+//! in and redirects to `/`. The user's ID token is then kept in a cookie, so the session
+//! lasts as long as the token, about an hour.
+
+use ::poem::http::Method;
+use ::poem::http::StatusCode;
+use ::poem::http::header::{AUTHORIZATION, COOKIE, LOCATION, SET_COOKIE};
+use ::poem::web::Data;
+use ::poem::{
+    Body, Error, FromRequest, Request, RequestBody, Response, Result, Route, get, handler,
+};
+
+use crate::web::AFTER_LOGIN_PATH;
+use crate::{AuthCallback, Google, UserInfo};
+
+/// The user signed in with Google, extracted from the ID token of the request.
+///
+/// Requests without a valid ID token, in the `Authorization: Bearer` header or the cookie
+/// set by `callback`, are rejected with `401 Unauthorized`.
+#[derive(Debug)]
+pub struct GoogleUser(pub UserInfo);
+
+/// Returns a route serving `login` at `/login` and `callback` at `/callback`.
+pub fn routes() -> Route {
+    Route::new()
+        .at("/login", get(login))
+        .at("/callback", get(callback).post(callback))
+}
+
+/// Redirects the user to Google's consent screen.
+#[handler]
+pub fn login(google: Data<&Google>) -> Result<Response> {
+    let (url, cookie) = google
+        .start_login()
+        .map_err(|err| Error::from_string(err.to_string(), StatusCode::FORBIDDEN))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::FOUND)
+        .header(LOCATION, url)
+        .header(SET_COOKIE, cookie)
+        .finish())
+}
+
+/// Handles the redirect back from Google, signing the user in and redirecting to `/`.
+#[handler]
+pub async fn callback(request: &Request, body: Body, google: Data<&Google>) -> Result<Response> {
+    let unauthorized = |err: Box<dyn std::error::Error>| {
+        Error::from_string(err.to_string(), StatusCode::UNAUTHORIZED)
+    };
+    let callback = if request.method() == Method::POST {
+        AuthCallback::from_form(&body.into_bytes().await?).map_err(unauthorized)?
+    } else {
+        AuthCallback::from_query(request.uri().query().unwrap_or_default())
+    };
+    let cookies = request.header(COOKIE);
+    let (_, [id_token_cookie, state_cookie]) = google
+        .finish_login(callback, cookies)
+        .await
+        .map_err(unauthorized)?;
+
+    Ok(Response::builder()
+        .status(StatusCode::FOUND)
+        .header(LOCATION, AFTER_LOGIN_PATH)
+        .header(SET_COOKIE, id_token_cookie)
+        .header(SET_COOKIE, state_cookie)
+        .finish())
+}
+
+impl<'a> FromRequest<'a> for GoogleUser {
+    async fn from_request(request: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        let google = request.data::<Google>().ok_or_else(|| {
+            Error::from_string(
+                "Google client is not route data",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+        let userinfo = google
+            .authenticate_request(request.header(AUTHORIZATION), request.header(COOKIE))
+            .await
+            .map_err(|err| Error::from_string(err.to_string(), StatusCode::UNAUTHORIZED))?;
+
+        Ok(GoogleUser(userinfo))
+    }
+}
@@ -0,0 +1,409 @@
+//! Cloud Pub/Sub API, for publishing messages to topics and receiving push deliveries.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use reqwest::header::{AUTHORIZATION, HeaderMap};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::encode_path;
+use crate::jwt::{GOOGLE_CERTS_URL, GOOGLE_ISSUERS};
+use crate::{Google, Token};
+
+/// Scope granting access to Pub/Sub topics and subscriptions.
+pub const SCOPE_PUBSUB: &str = "https://www.googleapis.com/auth/pubsub";
+
+const PUBSUB_API: &str = "https://pubsub.googleapis.com/v1";
+
+/// The maximum number of messages Pub/Sub accepts in a single publish request.
+const MAX_BATCH_MESSAGES: usize = 1000;
+/// The maximum encoded size of a publish request, kept below the 10 MB limit.
+const MAX_BATCH_BYTES: usize = 9 * 1024 * 1024;
+
+/// A client for the Pub/Sub API.
+///
+/// Publishers usually authorize with a service account token, obtained with
+/// `Google::get_service_account_token` or `Google::get_default_token`.
+#[derive(Clone, Copy)]
+pub struct PubSub<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A message to publish, built with `NewMessage::new` and the chained setters.
+#[derive(Debug, Clone, Default)]
+pub struct NewMessage {
+    data: Vec<u8>,
+    attributes: HashMap<String, String>,
+    ordering_key: Option<String>,
+}
+
+/// A push delivery request, as posted by Pub/Sub to a push subscription's endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct PushRequest {
+    pub message: ReceivedMessage,
+    /// The full name of the subscription, e.g. `projects/p/subscriptions/s`.
+    pub subscription: String,
+}
+
+/// A message delivered to a subscriber, with its payload already base64-decoded.
+#[derive(Debug, Clone, Default)]
+pub struct ReceivedMessage {
+    pub data: Vec<u8>,
+    pub attributes: HashMap<String, String>,
+    pub message_id: String,
+    pub publish_time: Option<DateTime<Utc>>,
+    pub ordering_key: Option<String>,
+    /// How often the message has been delivered, set when a dead-letter policy is
+    /// configured.
+    pub delivery_attempt: Option<u32>,
+}
+
+/// The claims of the OIDC token Pub/Sub attaches to authenticated push requests.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PushClaims {
+    /// The service account the subscription pushes as.
+    pub email: String,
+    pub email_verified: bool,
+    /// The audience configured on the subscription, the endpoint URL by default.
+    pub aud: String,
+    pub sub: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// The error returned by `PubSub::publish` when a request fails after earlier batches have
+/// been published.
+///
+/// It can be inspected by downcasting with `err.downcast_ref::<PartialPublish>()`, e.g. to
+/// retry only the messages from `message_ids.len()` on.
+#[derive(Debug)]
+pub struct PartialPublish {
+    /// The ids of the messages published before the failure, in the order of the
+    /// messages passed to `publish`.
+    pub message_ids: Vec<String>,
+    /// The error of the failing request.
+    pub error: Box<dyn Error>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PushEnvelope {
+    message: PushEnvelopeMessage,
+    #[serde(default)]
+    subscription: String,
+    delivery_attempt: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PushEnvelopeMessage {
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    attributes: HashMap<String, String>,
+    #[serde(default)]
+    message_id: String,
+    publish_time: Option<DateTime<Utc>>,
+    ordering_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublishResponse {
+    #[serde(default)]
+    message_ids: Vec<String>,
+}
+
+impl NewMessage {
+    /// Creates a message with the given payload.
+    pub fn new(data: impl Into<Vec<u8>>) -> NewMessage {
+        NewMessage {
+            data: data.into(),
+            ..NewMessage::default()
+        }
+    }
+
+    /// Adds an attribute, delivered alongside the payload and usable in subscription
+    /// filters.
+    pub fn attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> NewMessage {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the ordering key. Messages with the same key are delivered in publish order
+    /// to subscriptions with message ordering enabled.
+    pub fn ordering_key(mut self, ordering_key: impl Into<String>) -> NewMessage {
+        self.ordering_key = Some(ordering_key.into());
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut message = json!({ "data": STANDARD.encode(&self.data) });
+        if !self.attributes.is_empty() {
+            message["attributes"] = json!(self.attributes);
+        }
+        if let Some(ordering_key) = &self.ordering_key {
+            message["orderingKey"] = json!(ordering_key);
+        }
+        message
+    }
+}
+
+impl PushRequest {
+    /// Decodes the JSON body of a push request.
+    ///
+    /// The body is not authenticated; use `Google::verify_push` to check the OIDC token
+    /// first when the subscription is configured with one.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the body is not a Pub/Sub push envelope or its
+    /// data is not valid base64.
+    pub fn from_body(body: &[u8]) -> Result<PushRequest, Box<dyn Error>> {
+        let envelope: PushEnvelope = serde_json::from_slice(body)?;
+        let message = envelope.message;
+
+        Ok(PushRequest {
+            message: ReceivedMessage {
+                data: STANDARD.decode(message.data)?,
+                attributes: message.attributes,
+                message_id: message.message_id,
+                publish_time: message.publish_time,
+                ordering_key: message.ordering_key.filter(|key| !key.is_empty()),
+                delivery_attempt: envelope.delivery_attempt,
+            },
+            subscription: envelope.subscription,
+        })
+    }
+}
+
+impl Google {
+    /// Verifies the OIDC token of an authenticated push request.
+    ///
+    /// The token must be signed by Google, issued for `audience`, unexpired, and belong to
+    /// `service_account_email` with a verified email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorization` - The value of the request's `Authorization` header,
+    ///   `Bearer <token>`.
+    /// * `audience` - The audience configured on the push subscription.
+    /// * `service_account_email` - The service account the subscription pushes as.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the token is missing, malformed or fails any of
+    /// the checks above, or if Google's signing keys cannot be fetched.
+    pub async fn verify_push_token(
+        &self,
+        authorization: &str,
+        audience: &str,
+        service_account_email: &str,
+    ) -> Result<PushClaims, Box<dyn Error>> {
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .ok_or("Push request has no bearer token")?;
+        let claims: PushClaims = self
+            .verify_jwt(token, GOOGLE_CERTS_URL, &GOOGLE_ISSUERS, audience)
+            .await?;
+
+        if !claims.email_verified || claims.email != service_account_email {
+            return Err(format!("Push request is from unexpected account {}", claims.email).into());
+        }
+
+        Ok(claims)
+    }
+
+    /// Verifies a push request and decodes its body, for use in a web framework handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the push request.
+    /// * `body` - The raw body of the push request.
+    /// * `audience` - The audience configured on the push subscription.
+    /// * `service_account_email` - The service account the subscription pushes as.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error as described for `verify_push_token` and
+    /// `PushRequest::from_body`. Handlers should answer errors with `401` or `400` so
+    /// that forged requests are not acknowledged.
+    pub async fn verify_push(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        audience: &str,
+        service_account_email: &str,
+    ) -> Result<PushRequest, Box<dyn Error>> {
+        let authorization = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or("Push request has no Authorization header")?;
+        self.verify_push_token(authorization, audience, service_account_email)
+            .await?;
+
+        PushRequest::from_body(body)
+    }
+
+    /// Returns a Pub/Sub API client that authorizes its requests with `token`.
+    pub fn pubsub<'a>(&'a self, token: &'a Token) -> PubSub<'a> {
+        PubSub {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl PubSub<'_> {
+    /// Publishes messages to a topic, returning the server-assigned message ids in the
+    /// order of `messages`.
+    ///
+    /// The messages are sent in as few requests as the Pub/Sub limits allow, one after
+    /// another, so that messages sharing an ordering key keep their order.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The project the topic belongs to.
+    /// * `topic` - The id of the topic.
+    /// * `messages` - The messages to publish.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a request fails. If batches sent before the
+    /// failing one have been published, the error is a `PartialPublish` holding their ids.
+    pub async fn publish(
+        &self,
+        project_id: &str,
+        topic: &str,
+        messages: &[NewMessage],
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!(
+            "{PUBSUB_API}/projects/{}/topics/{}:publish",
+            encode_path(project_id),
+            encode_path(topic)
+        );
+        let mut message_ids = Vec::with_capacity(messages.len());
+
+        for batch in batches(messages) {
+            match self.publish_batch(&url, &batch).await {
+                Ok(ids) => message_ids.extend(ids),
+                Err(error) if message_ids.is_empty() => return Err(error),
+                Err(error) => return Err(PartialPublish { message_ids, error }.into()),
+            }
+        }
+
+        Ok(message_ids)
+    }
+
+    async fn publish_batch(
+        &self,
+        url: &str,
+        batch: &[Value],
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(url)
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "messages": batch }));
+        let response: PublishResponse = self.google.send_json(request).await?;
+
+        Ok(response.message_ids)
+    }
+}
+
+/// Splits `messages` into publish requests within the Pub/Sub limits, keeping their order.
+fn batches(messages: &[NewMessage]) -> Vec<Vec<Value>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_bytes = 0;
+
+    for message in messages {
+        let message = message.to_json();
+        let size = message.to_string().len();
+        if !batch.is_empty()
+            && (batch.len() == MAX_BATCH_MESSAGES || batch_bytes + size > MAX_BATCH_BYTES)
+        {
+            batches.push(std::mem::take(&mut batch));
+            batch_bytes = 0;
+        }
+        batch_bytes += size;
+        batch.push(message);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+impl fmt::Display for PartialPublish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Publishing failed after {} messages were published: {}",
+            self.message_ids.len(),
+            self.error
+        )
+    }
+}
+
+impl Error for PartialPublish {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_by_message_count() {
+        let messages = vec![NewMessage::new("data"); MAX_BATCH_MESSAGES + 1];
+
+        let batches = batches(&messages);
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), MAX_BATCH_MESSAGES);
+        assert_eq!(batches[1].len(), 1);
+    }
+
+    #[test]
+    fn batches_by_size() {
+        let messages = vec![NewMessage::new(vec![0; 3 * 1024 * 1024]); 3];
+
+        let batches = batches(&messages);
+
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [2, 1]);
+    }
+
+    #[test]
+    fn sends_no_batch_without_messages() {
+        assert!(batches(&[]).is_empty());
+    }
+
+    #[test]
+    fn partial_publish_keeps_the_published_ids() {
+        let error: Box<dyn Error> = PartialPublish {
+            message_ids: vec!["1".to_string(), "2".to_string()],
+            error: "connection reset".into(),
+        }
+        .into();
+
+        let partial = error.downcast_ref::<PartialPublish>().unwrap();
+        assert_eq!(partial.message_ids, ["1", "2"]);
+        assert_eq!(partial.source().unwrap().to_string(), "connection reset");
+        assert_eq!(
+            error.to_string(),
+            "Publishing failed after 2 messages were published: connection reset"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Server-side verification of reCAPTCHA responses, through the classic `siteverify`
+//! endpoint or reCAPTCHA Enterprise assessments.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::encode_path;
+use crate::{Google, Token};
+
+const SITEVERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+const RECAPTCHA_ENTERPRISE_API: &str = "https://recaptchaenterprise.googleapis.com/v1";
+
+/// A client for the reCAPTCHA `siteverify` endpoint, authorized with a site's secret key.
+#[derive(Clone, Copy)]
+pub struct Recaptcha<'a> {
+    google: &'a Google,
+    secret: &'a str,
+}
+
+/// The result of verifying a reCAPTCHA response token.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Verification {
+    /// Whether the token is valid for the site.
+    pub success: bool,
+    /// The v3 score, from 0.0 (likely a bot) to 1.0 (likely a human).
+    pub score: Option<f32>,
+    /// The v3 action name the token was generated for.
+    pub action: Option<String>,
+    /// When the challenge was solved.
+    pub challenge_ts: Option<DateTime<Utc>>,
+    /// The hostname of the site the challenge was solved on.
+    pub hostname: Option<String>,
+    /// The package name of Android apps the challenge was solved in.
+    pub apk_package_name: Option<String>,
+    /// Why verification failed, e.g. `timeout-or-duplicate` or `invalid-input-response`.
+    #[serde(rename = "error-codes")]
+    pub error_codes: Vec<String>,
+}
+
+/// A client for the reCAPTCHA Enterprise API, usually authorized with a service account
+/// token carrying the `cloud-platform` scope.
+#[derive(Clone, Copy)]
+pub struct RecaptchaEnterprise<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// The user interaction to assess.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// The response token generated on the client.
+    pub token: String,
+    /// The site key the token was generated with.
+    pub site_key: String,
+    /// The action the client is expected to have passed to `grecaptcha.enterprise.execute`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+}
+
+/// The result of an Enterprise assessment.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Assessment {
+    /// The resource name, used to annotate the assessment later.
+    pub name: String,
+    pub token_properties: TokenProperties,
+    pub risk_analysis: RiskAnalysis,
+}
+
+/// Properties of the assessed token.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TokenProperties {
+    pub valid: bool,
+    /// Why the token is invalid, e.g. `EXPIRED`, `DUPE` or `MALFORMED`.
+    pub invalid_reason: Option<String>,
+    pub hostname: Option<String>,
+    pub android_package_name: Option<String>,
+    pub ios_bundle_id: Option<String>,
+    /// The action the token was generated for.
+    pub action: Option<String>,
+    pub create_time: Option<DateTime<Utc>>,
+}
+
+/// The risk assessment of a valid token.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RiskAnalysis {
+    /// From 0.0 (likely fraudulent) to 1.0 (likely legitimate).
+    pub score: f32,
+    /// Reasons contributing to the score, e.g. `AUTOMATION` or `UNEXPECTED_ENVIRONMENT`.
+    pub reasons: Vec<String>,
+}
+
+impl Verification {
+    /// Returns `true` if a v3 token is valid, was generated for `action` and scored at
+    /// least `min_score`. Google suggests starting with a threshold of 0.5.
+    pub fn passes(&self, action: &str, min_score: f32) -> bool {
+        self.success
+            && self.action.as_deref() == Some(action)
+            && self.score.is_some_and(|score| score >= min_score)
+    }
+}
+
+impl Assessment {
+    /// Returns `true` if the token is valid, was generated for `action` and scored at
+    /// least `min_score`.
+    pub fn passes(&self, action: &str, min_score: f32) -> bool {
+        self.token_properties.valid
+            && self.token_properties.action.as_deref() == Some(action)
+            && self.risk_analysis.score >= min_score
+    }
+}
+
+impl Google {
+    /// Returns a reCAPTCHA Enterprise API client that authorizes its requests with `token`.
+    pub fn recaptcha_enterprise<'a>(&'a self, token: &'a Token) -> RecaptchaEnterprise<'a> {
+        RecaptchaEnterprise {
+            google: self,
+            token,
+        }
+    }
+
+    /// Returns a reCAPTCHA verification client for the site with the given secret key.
+    pub fn recaptcha<'a>(&'a self, secret: &'a str) -> Recaptcha<'a> {
+        Recaptcha {
+            google: self,
+            secret,
+        }
+    }
+}
+
+impl Recaptcha<'_> {
+    /// Verifies a response token submitted by the client.
+    ///
+    /// Tokens are valid for two minutes and can only be verified once.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The `g-recaptcha-response` value, or the token from `grecaptcha.execute`.
+    /// * `remote_ip` - The IP address of the user, checked by Google when given.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. An invalid token is not an
+    /// error; check `Verification::success` or `Verification::passes`.
+    pub async fn verify(
+        &self,
+        token: &str,
+        remote_ip: Option<&str>,
+    ) -> Result<Verification, Box<dyn Error>> {
+        let mut form = vec![("secret", self.secret), ("response", token)];
+        if let Some(remote_ip) = remote_ip {
+            form.push(("remoteip", remote_ip));
+        }
+
+        let request = self.google.http.post(SITEVERIFY_URL).form(&form);
+
+        self.google.send_json(request).await
+    }
+}
+
+impl RecaptchaEnterprise<'_> {
+    /// Assesses the risk of a user interaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The Google Cloud project the site key belongs to.
+    /// * `event` - The token and context of the interaction.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. An invalid token is not an
+    /// error; check `TokenProperties::valid` or `Assessment::passes`.
+    pub async fn create_assessment(
+        &self,
+        project_id: &str,
+        event: &Event,
+    ) -> Result<Assessment, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{RECAPTCHA_ENTERPRISE_API}/projects/{}/assessments",
+                encode_path(project_id)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "event": event }));
+
+        self.google.send_json(request).await
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use oauth2::RefreshToken;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::{Google, Token};
+
+/// How long before its expiry a token is refreshed by `Google::get_fresh_token`, unless
+/// changed with `Google::with_refresh_leeway`.
+pub(crate) const DEFAULT_REFRESH_LEEWAY: Duration = Duration::from_secs(5 * 60);
+
+/// Persists tokens between requests, e.g. in a database row per user.
+///
+/// Set with `Google::with_token_store`, it is used by `Google::refresh_stored_token` to
+/// save refreshed tokens. Google may rotate the refresh token on refresh, invalidating the
+/// previous one, so `save` must replace the stored token atomically: a failed save has to
+/// leave the previous token in place, never a partially written one.
+///
+/// Backends persisting tokens outside the process should store them with
+/// `Token::to_persisted` and read them with `Token::from_persisted`, whose versioned
+/// format keeps stored tokens readable across releases.
+pub trait TokenStore: Send + Sync {
+    /// Loads the token stored under `key`, or `None` if there is none.
+    fn load<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Token>, Box<dyn Error>>>;
+
+    /// Stores `token` under `key`, replacing the previous token.
+    fn save<'a>(
+        &'a self,
+        key: &'a str,
+        token: &'a Token,
+    ) -> BoxFuture<'a, Result<(), Box<dyn Error>>>;
+
+    /// Deletes the token stored under `key`, if any, e.g. once the account it belongs to
+    /// has been unlinked.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), Box<dyn Error>>>;
+}
+
+/// One lock per token store key, held while the token stored under it is refreshed so that
+/// concurrent callers refresh it once. Shared by the clones of a `Google`.
+#[derive(Default)]
+pub(crate) struct RefreshLocks(Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>);
+
+/// Holds the refresh lock of a key, removing it from `RefreshLocks` on drop unless other
+/// callers are waiting for it.
+struct RefreshGuard<'a> {
+    locks: &'a RefreshLocks,
+    key: &'a str,
+    guard: OwnedMutexGuard<()>,
+}
+
+/// The result of refreshing a token.
+#[derive(Debug)]
+pub struct RefreshedToken {
+    /// The new token. It carries over the previous refresh token unless Google rotated it.
+    pub token: Token,
+    /// Whether Google issued a new refresh token. The previous one must then be replaced
+    /// by the one in `token`, as it may stop working.
+    pub rotated: bool,
+}
+
+impl Google {
+    /// Obtains a new access token with the refresh token of `token`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A token with a refresh token, i.e. obtained with offline access.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `token` has no refresh token, or if Google rejects
+    /// it, e.g. with `invalid_grant` when the user revoked access.
+    pub async fn refresh_token(&self, token: &Token) -> Result<RefreshedToken, Box<dyn Error>> {
+        let refresh_token = token.refresh_token().ok_or("Token has no refresh token")?;
+        let refresh_token = RefreshToken::new(refresh_token.to_string());
+        let request = self.client.exchange_refresh_token(&refresh_token);
+        let response = self
+            .with_deadline(async { Ok(request.request_async(&self.http).await?) })
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => {
+                self.emit(|events| events.on_refresh_failed(err.as_ref()));
+                return Err(err);
+            }
+        };
+        let (token, rotated) = Token::from_refresh_response(&response, token);
+        let refreshed = RefreshedToken { token, rotated };
+        self.emit(|events| events.on_token_refreshed(&refreshed));
+
+        Ok(refreshed)
+    }
+
+    /// Refreshes the token stored under `key` in the configured `TokenStore` and saves
+    /// the new token.
+    ///
+    /// The new token is only returned once it has been saved, so a rotated refresh token
+    /// is never handed out without being persisted.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key the token is stored under, e.g. the user's id.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if no token store is configured or no token is
+    /// stored under `key`, if refreshing fails, or if the new token cannot be saved.
+    pub async fn refresh_stored_token(&self, key: &str) -> Result<RefreshedToken, Box<dyn Error>> {
+        let _guard = self.refresh_locks.lock(key).await;
+        let (store, token) = self.load_token(key).await?;
+
+        self.refresh_and_save(store, key, &token).await
+    }
+
+    /// Returns the token stored under `key` in the configured `TokenStore`, refreshing it
+    /// first if it expires within the leeway set with `with_refresh_leeway`.
+    ///
+    /// Renewing tokens ahead of their expiry keeps them from expiring mid-request, instead
+    /// of reacting to `401` responses. Concurrent calls for the same `key` on this client
+    /// and its clones refresh the token once: the others wait for the refresh and return
+    /// the saved token.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key the token is stored under, e.g. the user's id.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error as described for `refresh_stored_token`. Tokens
+    /// without a refresh token are returned as stored, even if they have expired.
+    pub async fn get_fresh_token(&self, key: &str) -> Result<Token, Box<dyn Error>> {
+        let (_, token) = self.load_token(key).await?;
+        if !self.needs_refresh(&token) {
+            return Ok(token);
+        }
+
+        let _guard = self.refresh_locks.lock(key).await;
+        // Another caller may have refreshed the token while this one waited for the lock.
+        let (store, token) = self.load_token(key).await?;
+        if !self.needs_refresh(&token) {
+            return Ok(token);
+        }
+
+        Ok(self.refresh_and_save(store, key, &token).await?.token)
+    }
+
+    /// Returns `true` if `token` has a refresh token and expires within the leeway set with
+    /// `with_refresh_leeway`.
+    pub fn needs_refresh(&self, token: &Token) -> bool {
+        token.refresh_token().is_some() && token.expires_within(self.refresh_leeway)
+    }
+
+    async fn load_token(&self, key: &str) -> Result<(&dyn TokenStore, Token), Box<dyn Error>> {
+        let store = self
+            .token_store
+            .as_deref()
+            .ok_or("No token store configured")?;
+        let token = store
+            .load(key)
+            .await?
+            .ok_or_else(|| format!("No token stored under {key}"))?;
+
+        Ok((store, token))
+    }
+
+    async fn refresh_and_save(
+        &self,
+        store: &dyn TokenStore,
+        key: &str,
+        token: &Token,
+    ) -> Result<RefreshedToken, Box<dyn Error>> {
+        let refreshed = self.refresh_token(token).await?;
+        store.save(key, &refreshed.token).await?;
+
+        Ok(refreshed)
+    }
+}
+
+impl RefreshLocks {
+    /// Waits for and takes the refresh lock of `key`.
+    async fn lock<'a>(&'a self, key: &'a str) -> RefreshGuard<'a> {
+        let lock = self
+            .0
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+
+        RefreshGuard {
+            locks: self,
+            key,
+            guard: lock.lock_owned().await,
+        }
+    }
+}
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        let mut locks = self.locks.0.lock().unwrap();
+        // Only the map and this guard refer to the lock when nobody else is waiting for it.
+        if Arc::strong_count(OwnedMutexGuard::mutex(&self.guard)) == 2 {
+            locks.remove(self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt;
+
+    use super::*;
+    use crate::Scopes;
+    use crate::test_server::{TestServer, token_response, with_token_server};
+
+    #[derive(Default)]
+    struct MemoryTokens(Mutex<HashMap<String, Token>>);
+
+    impl TokenStore for MemoryTokens {
+        fn load<'a>(
+            &'a self,
+            key: &'a str,
+        ) -> BoxFuture<'a, Result<Option<Token>, Box<dyn Error>>> {
+            async move { Ok(self.0.lock().unwrap().get(key).cloned()) }.boxed()
+        }
+
+        fn save<'a>(
+            &'a self,
+            key: &'a str,
+            token: &'a Token,
+        ) -> BoxFuture<'a, Result<(), Box<dyn Error>>> {
+            async move {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .insert(key.to_string(), token.clone());
+                Ok(())
+            }
+            .boxed()
+        }
+
+        fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), Box<dyn Error>>> {
+            async move {
+                self.0.lock().unwrap().remove(key);
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_concurrently_requested_tokens_once() {
+        let server =
+            TestServer::start(vec![token_response("access-1"), token_response("access-2")]).await;
+        let store = MemoryTokens::default();
+        let expired = Token::new(
+            "access-0".to_string(),
+            "Bearer".to_string(),
+            Some(Duration::ZERO),
+            Some("refresh".to_string()),
+            Scopes::default(),
+        );
+        store.0.lock().unwrap().insert("user".to_string(), expired);
+        let google = Google::new(
+            "client-id".to_string(),
+            "secret".to_string(),
+            "https://app.example.com/callback".to_string(),
+        )
+        .with_token_store(store);
+        let google = with_token_server(google, &server);
+        let clone = google.clone();
+
+        let (first, second) = futures_util::join!(
+            google.get_fresh_token("user"),
+            clone.get_fresh_token("user")
+        );
+
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(first.unwrap().access_token(), "access-1");
+        assert_eq!(second.unwrap().access_token(), "access-1");
+        assert!(google.refresh_locks.0.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,367 @@
+//! Safe Browsing Lookup API v4, for checking URLs against Google's threat lists.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::Google;
+
+const SAFE_BROWSING_API: &str = "https://safebrowsing.googleapis.com/v4";
+
+/// The maximum number of URLs per lookup request.
+const MAX_LOOKUP_URLS: usize = 500;
+/// The platforms every lookup checks.
+const PLATFORM_TYPES: [&str; 1] = ["ANY_PLATFORM"];
+/// The number of results a `LookupCache` keeps unless changed with
+/// `LookupCache::with_max_entries`.
+const DEFAULT_MAX_CACHE_ENTRIES: usize = 10_000;
+
+/// A client for the Safe Browsing Lookup API, authorized with an API key.
+#[derive(Clone, Copy)]
+pub struct SafeBrowsing<'a> {
+    google: &'a Google,
+    api_key: &'a str,
+    cache: Option<&'a LookupCache>,
+}
+
+/// A cache of lookup results, shared between lookups to avoid repeated requests for the
+/// same URLs.
+///
+/// Results are cached per URL and per set of threat types and platforms looked up, so a
+/// URL found clean on one threat list is still checked against the others. Matches are
+/// cached for the duration the API specifies with each match. URLs without matches are
+/// cached for the negative cache duration given to `LookupCache::new`.
+///
+/// The cache holds at most 10,000 results by default. When it is full, expired results
+/// are dropped first, then those expiring soonest.
+pub struct LookupCache {
+    negative_duration: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<CacheKey, CacheEntry>>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    url: String,
+    /// The threat types looked up, sorted and deduplicated.
+    threat_types: Vec<ThreatType>,
+    platform_types: &'static [&'static str],
+}
+
+struct CacheEntry {
+    expires_at: Instant,
+    matches: Vec<ThreatMatch>,
+}
+
+/// A kind of threat.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ThreatType {
+    Malware,
+    SocialEngineering,
+    UnwantedSoftware,
+    PotentiallyHarmfulApplication,
+    /// Any type not known to this crate.
+    #[serde(other)]
+    ThreatTypeUnspecified,
+}
+
+/// A URL found on a threat list.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreatMatch {
+    pub threat_type: ThreatType,
+    /// The platform the threat targets, e.g. `WINDOWS` or `ANY_PLATFORM`.
+    pub platform_type: String,
+    pub threat: ThreatEntry,
+    /// How long the match may be cached, e.g. `300s`.
+    #[serde(default)]
+    pub cache_duration: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ThreatEntry {
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct FindResponse {
+    #[serde(default)]
+    matches: Vec<ThreatMatch>,
+}
+
+impl LookupCache {
+    /// Creates an empty cache that remembers URLs without matches for
+    /// `negative_duration`.
+    pub fn new(negative_duration: Duration) -> LookupCache {
+        LookupCache {
+            negative_duration,
+            max_entries: DEFAULT_MAX_CACHE_ENTRIES,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keeps at most `max_entries` results.
+    pub fn with_max_entries(mut self, max_entries: usize) -> LookupCache {
+        self.max_entries = max_entries;
+        self
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Vec<ThreatMatch>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.matches.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: CacheKey, matches: Vec<ThreatMatch>) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let duration = matches
+            .iter()
+            .filter_map(|threat| threat.cache_duration.as_deref().and_then(parse_duration))
+            .min()
+            .unwrap_or(self.negative_duration);
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, entry| entry.expires_at > now);
+        }
+        while entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            let Some(soonest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&soonest);
+        }
+        entries.insert(
+            key,
+            CacheEntry {
+                expires_at: Instant::now() + duration,
+                matches,
+            },
+        );
+    }
+}
+
+impl CacheKey {
+    fn new(url: &str, threat_types: &[ThreatType]) -> CacheKey {
+        let mut threat_types = threat_types.to_vec();
+        threat_types.sort_unstable();
+        threat_types.dedup();
+
+        CacheKey {
+            url: url.to_string(),
+            threat_types,
+            platform_types: &PLATFORM_TYPES,
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Safe Browsing client that authorizes its requests with `api_key`.
+    pub fn safe_browsing<'a>(&'a self, api_key: &'a str) -> SafeBrowsing<'a> {
+        SafeBrowsing {
+            google: self,
+            api_key,
+            cache: None,
+        }
+    }
+}
+
+impl<'a> SafeBrowsing<'a> {
+    /// Answers lookups from `cache` where possible and stores new results in it.
+    pub fn cache(mut self, cache: &'a LookupCache) -> SafeBrowsing<'a> {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Checks URLs against the given threat lists, returning the matches. URLs without a
+    /// match are considered safe.
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - The URLs to check, e.g. links submitted by users.
+    /// * `threat_types` - The threat lists to check against.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a request fails.
+    pub async fn find_threats(
+        &self,
+        urls: &[&str],
+        threat_types: &[ThreatType],
+    ) -> Result<Vec<ThreatMatch>, Box<dyn Error>> {
+        let mut matches = Vec::new();
+        let mut uncached = Vec::new();
+        for url in urls {
+            let cached = self
+                .cache
+                .and_then(|cache| cache.get(&CacheKey::new(url, threat_types)));
+            match cached {
+                Some(cached) => matches.extend(cached),
+                None => uncached.push(*url),
+            }
+        }
+
+        for batch in uncached.chunks(MAX_LOOKUP_URLS) {
+            let found = self.lookup(batch, threat_types).await?;
+            if let Some(cache) = self.cache {
+                for url in batch {
+                    let url_matches = found
+                        .iter()
+                        .filter(|threat| threat.threat.url == *url)
+                        .cloned()
+                        .collect();
+                    cache.insert(CacheKey::new(url, threat_types), url_matches);
+                }
+            }
+            matches.extend(found);
+        }
+
+        Ok(matches)
+    }
+
+    async fn lookup(
+        &self,
+        urls: &[&str],
+        threat_types: &[ThreatType],
+    ) -> Result<Vec<ThreatMatch>, Box<dyn Error>> {
+        let entries: Vec<_> = urls.iter().map(|url| json!({ "url": url })).collect();
+        let request = self
+            .google
+            .http
+            .post(format!("{SAFE_BROWSING_API}/threatMatches:find"))
+            .query(&[("key", self.api_key)])
+            .json(&json!({
+                "client": {
+                    "clientId": env!("CARGO_PKG_NAME"),
+                    "clientVersion": env!("CARGO_PKG_VERSION"),
+                },
+                "threatInfo": {
+                    "threatTypes": threat_types,
+                    "platformTypes": PLATFORM_TYPES,
+                    "threatEntryTypes": ["URL"],
+                    "threatEntries": entries,
+                },
+            }));
+        let response: FindResponse = self.google.send_json(request).await?;
+
+        Ok(response.matches)
+    }
+}
+
+/// Parses a protobuf JSON duration such as `300s` or `1.5s`.
+fn parse_duration(duration: &str) -> Option<Duration> {
+    duration
+        .strip_suffix('s')?
+        .parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threat(url: &str, threat_type: ThreatType) -> ThreatMatch {
+        ThreatMatch {
+            threat_type,
+            platform_type: "ANY_PLATFORM".to_string(),
+            threat: ThreatEntry {
+                url: url.to_string(),
+            },
+            cache_duration: Some("300s".to_string()),
+        }
+    }
+
+    #[test]
+    fn keys_results_by_threat_types() {
+        let cache = LookupCache::new(Duration::from_secs(60));
+        let url = "http://example.com/";
+        cache.insert(CacheKey::new(url, &[ThreatType::Malware]), Vec::new());
+
+        assert!(
+            cache
+                .get(&CacheKey::new(url, &[ThreatType::SocialEngineering]))
+                .is_none()
+        );
+        assert!(
+            cache
+                .get(&CacheKey::new(
+                    url,
+                    &[ThreatType::Malware, ThreatType::SocialEngineering]
+                ))
+                .is_none()
+        );
+        assert_eq!(
+            cache
+                .get(&CacheKey::new(
+                    url,
+                    &[ThreatType::Malware, ThreatType::Malware]
+                ))
+                .map(|matches| matches.len()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn ignores_threat_type_order() {
+        let cache = LookupCache::new(Duration::from_secs(60));
+        let url = "http://evil.example/";
+        let types = [ThreatType::SocialEngineering, ThreatType::Malware];
+        cache.insert(
+            CacheKey::new(url, &types),
+            vec![threat(url, ThreatType::Malware)],
+        );
+
+        let reversed = [ThreatType::Malware, ThreatType::SocialEngineering];
+        assert_eq!(cache.get(&CacheKey::new(url, &reversed)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn evicts_expired_entries_when_full() {
+        let cache = LookupCache::new(Duration::ZERO).with_max_entries(2);
+        let types = [ThreatType::Malware];
+        cache.insert(CacheKey::new("http://a.example/", &types), Vec::new());
+        cache.insert(CacheKey::new("http://b.example/", &types), Vec::new());
+        cache.insert(
+            CacheKey::new("http://c.example/", &types),
+            vec![threat("http://c.example/", ThreatType::Malware)],
+        );
+
+        let entries = cache.entries.lock().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries.contains_key(&CacheKey::new("http://c.example/", &types)));
+    }
+
+    #[test]
+    fn evicts_entries_expiring_soonest_when_full() {
+        let cache = LookupCache::new(Duration::from_secs(60)).with_max_entries(2);
+        let types = [ThreatType::Malware];
+        cache.insert(CacheKey::new("http://a.example/", &types), Vec::new());
+        cache.insert(
+            CacheKey::new("http://b.example/", &types),
+            vec![threat("http://b.example/", ThreatType::Malware)],
+        );
+        cache.insert(CacheKey::new("http://c.example/", &types), Vec::new());
+
+        let entries = cache.entries.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(!entries.contains_key(&CacheKey::new("http://a.example/", &types)));
+    }
+}
@@ -0,0 +1,134 @@
+//! Salvo integration: handlers for the sign-in flow keeping its state and the signed-in
+//! user in the session.
+//!
+//! The handlers expect the `Google` client in the depot, e.g. injected with `affix_state`,
+//! and a `SessionHandler` in front of them:
+//!
+//! ```no_run
+//! # fn client() -> async_google_auth::Google { unimplemented!() }
+//! use async_google_auth::salvo::{google_user, require_user, router};
+//! use salvo::prelude::*;
+//! use salvo::session::{CookieStore, SessionHandler};
+//!
+//! #[handler]
+//! fn profile(depot: &mut Depot) -> String {
+//!     format!("Hello {}", google_user(depot).map_or("", |user| &user.email))
+//! }
+//!
+//! let secret = b"a secret of at least 64 bytes, e.g. loaded from the environment....";
+//! let app = Router::new()
+//!     .hoop(SessionHandler::builder(CookieStore::new(), secret).build().unwrap())
+//!     .hoop(affix_state::inject(client()))
+//!     .push(Router::with_path("auth").push(router()))
+//!     .push(Router::with_path("profile").hoop(require_user).get(profile));
+//! ```
+//!
+//! With the app's callback URL pointing to `/auth/callback`, `/auth/login` signs the user
+//! in and redirects to `/`.
+
+use ::salvo::http::{Method, StatusError};
+use ::salvo::prelude::*;
+use ::salvo::session::SessionDepotExt;
+
+use crate::web::AFTER_LOGIN_PATH;
+use crate::{AuthCallback, Google, UserInfo};
+
+/// The session key holding the state of a pending authorization.
+const STATE_KEY: &str = "google_oauth_state";
+/// The session key holding the signed-in user.
+const USER_KEY: &str = "google_user";
+
+/// Returns a router serving `login` at `login` and `callback` at `callback`.
+pub fn router() -> Router {
+    Router::new()
+        .push(Router::with_path("login").get(login))
+        .push(Router::with_path("callback").get(callback).post(callback))
+}
+
+/// Redirects the user to Google's consent screen, storing the state in the session.
+#[handler]
+pub async fn login(depot: &mut Depot, res: &mut Response) -> Result<(), StatusError> {
+    let authorization = google(depot)?
+        .authorization()
+        .build()
+        .map_err(|err| StatusError::forbidden().brief(err.to_string()))?;
+    session(depot)?
+        .insert(STATE_KEY, &authorization.state)
+        .map_err(|err| StatusError::internal_server_error().brief(err.to_string()))?;
+
+    res.render(Redirect::found(authorization.url));
+    Ok(())
+}
+
+/// Handles the redirect back from Google, storing the user in the session and redirecting
+/// to `/`.
+#[handler]
+pub async fn callback(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<(), StatusError> {
+    let unauthorized =
+        |err: Box<dyn std::error::Error>| StatusError::unauthorized().brief(err.to_string());
+    let params = if req.method() == Method::POST {
+        let body = req
+            .payload()
+            .await
+            .map_err(|err| StatusError::bad_request().brief(err.to_string()))?;
+        AuthCallback::from_form(body).map_err(unauthorized)?
+    } else {
+        AuthCallback::from_query(req.uri().query().unwrap_or_default())
+    };
+
+    let google = google(depot)?.clone();
+    let state = session(depot)?.get::<String>(STATE_KEY);
+    let (_, userinfo) = google
+        .complete_login(params, state.as_deref())
+        .await
+        .map_err(unauthorized)?;
+
+    let session = session(depot)?;
+    session.remove(STATE_KEY);
+    session
+        .insert(USER_KEY, &userinfo)
+        .map_err(|err| StatusError::internal_server_error().brief(err.to_string()))?;
+
+    res.render(Redirect::found(AFTER_LOGIN_PATH));
+    Ok(())
+}
+
+/// Rejects requests without a signed-in user with `401 Unauthorized`, and otherwise makes
+/// the user available to later handlers through `google_user`.
+#[handler]
+pub async fn require_user(depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+    let user = depot
+        .session()
+        .and_then(|session| session.get::<UserInfo>(USER_KEY));
+
+    match user {
+        Some(user) => {
+            depot.inject(user);
+        }
+        None => {
+            res.render(StatusError::unauthorized());
+            ctrl.skip_rest();
+        }
+    }
+}
+
+/// Returns the user made available by `require_user`.
+pub fn google_user(depot: &Depot) -> Option<&UserInfo> {
+    depot.obtain::<UserInfo>().ok()
+}
+
+fn google(depot: &Depot) -> Result<&Google, StatusError> {
+    depot
+        .obtain::<Google>()
+        .map_err(|_| StatusError::internal_server_error().brief("Google client is not injected"))
+}
+
+fn session(depot: &mut Depot) -> Result<&mut ::salvo::session::Session, StatusError> {
+    depot
+        .session_mut()
+        .ok_or_else(|| StatusError::internal_server_error().brief("No session handler"))
+}
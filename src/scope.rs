@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Scopes that Google rewrites to their full URL form in token responses.
+const SCOPE_ALIASES: [(&str, &str); 2] = [
+    ("email", "https://www.googleapis.com/auth/userinfo.email"),
+    (
+        "profile",
+        "https://www.googleapis.com/auth/userinfo.profile",
+    ),
+];
+
+/// A set of OAuth2 scopes, as granted by the user on the consent screen.
+///
+/// Google lets users uncheck individual scopes during consent, so the granted set can be
+/// smaller than the requested one. Lookups treat the short `email` and `profile` scopes as
+/// equal to the `userinfo.*` URLs Google reports them as.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scopes(BTreeSet<String>);
+
+impl Scopes {
+    /// Creates a scope set from any list of scope strings.
+    pub fn new<I, S>(scopes: I) -> Scopes
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Scopes(scopes.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns `true` if `scope` is part of the set.
+    pub fn contains(&self, scope: &str) -> bool {
+        let scope = normalize(scope);
+        self.0.iter().any(|granted| normalize(granted) == scope)
+    }
+
+    /// Returns the scopes from `required` that are not part of the set.
+    pub fn missing<'a>(&self, required: &[&'a str]) -> Vec<&'a str> {
+        required
+            .iter()
+            .copied()
+            .filter(|scope| !self.contains(scope))
+            .collect()
+    }
+
+    /// Returns an iterator over the scopes in the set.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Returns the number of scopes in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the set holds no scopes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromStr for Scopes {
+    type Err = std::convert::Infallible;
+
+    /// Parses the space-delimited `scope` value of a token response.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Scopes::new(value.split_whitespace()))
+    }
+}
+
+impl fmt::Display for Scopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scopes: Vec<&str> = self.iter().collect();
+        f.write_str(&scopes.join(" "))
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Scopes {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Scopes::new(iter)
+    }
+}
+
+fn normalize(scope: &str) -> &str {
+    SCOPE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == scope)
+        .map(|(_, url)| *url)
+        .unwrap_or(scope)
+}
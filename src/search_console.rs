@@ -0,0 +1,230 @@
+//! Search Console API, for search analytics and sitemaps of verified sites.
+
+use std::error::Error;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::encode_path;
+use crate::de::option_number_from_string;
+use crate::{Google, Token};
+
+/// Scope granting read and write access to Search Console data.
+pub const SCOPE_WEBMASTERS: &str = "https://www.googleapis.com/auth/webmasters";
+/// Scope granting read-only access to Search Console data.
+pub const SCOPE_WEBMASTERS_READONLY: &str = "https://www.googleapis.com/auth/webmasters.readonly";
+
+const SEARCH_CONSOLE_API: &str = "https://searchconsole.googleapis.com/webmasters/v3";
+
+/// A client for the Search Console API.
+#[derive(Clone, Copy)]
+pub struct SearchConsole<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A dimension to group search analytics rows by.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchDimension {
+    Query,
+    Page,
+    Date,
+    Country,
+    Device,
+    SearchAppearance,
+}
+
+/// Options for a search analytics query.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchAnalyticsQuery {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// The dimensions whose values make up `SearchAnalyticsRow::keys`, in this order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dimensions: Vec<SearchDimension>,
+    /// `web`, `image`, `video`, `news`, `discover` or `googleNews`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub search_type: Option<String>,
+    /// The number of rows, at most 25000.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_limit: Option<u32>,
+    /// The index of the first row, for paging through large results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_row: Option<u32>,
+}
+
+/// Search performance for one combination of dimension values.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchAnalyticsRow {
+    /// The dimension values, in the order of `SearchAnalyticsQuery::dimensions`.
+    pub keys: Vec<String>,
+    pub clicks: f64,
+    pub impressions: f64,
+    /// The click-through rate, between 0 and 1.
+    pub ctr: f64,
+    /// The average position in search results, 1 being the top.
+    pub position: f64,
+}
+
+/// A sitemap submitted for a site.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Sitemap {
+    /// The URL of the sitemap.
+    pub path: String,
+    pub last_submitted: Option<DateTime<Utc>>,
+    pub last_downloaded: Option<DateTime<Utc>>,
+    /// Whether the sitemap has not been processed yet.
+    pub is_pending: bool,
+    pub is_sitemaps_index: bool,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub warnings: Option<i64>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub errors: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct SearchAnalyticsResponse {
+    #[serde(default)]
+    rows: Vec<SearchAnalyticsRow>,
+}
+
+#[derive(Deserialize)]
+struct SitemapsResponse {
+    #[serde(default)]
+    sitemap: Vec<Sitemap>,
+}
+
+impl SearchAnalyticsQuery {
+    /// Creates a query for the inclusive date range, without dimensions.
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> SearchAnalyticsQuery {
+        SearchAnalyticsQuery {
+            start_date,
+            end_date,
+            dimensions: Vec::new(),
+            search_type: None,
+            row_limit: None,
+            start_row: None,
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Search Console API client that authorizes its requests with `token`.
+    pub fn search_console<'a>(&'a self, token: &'a Token) -> SearchConsole<'a> {
+        SearchConsole {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl SearchConsole<'_> {
+    /// Queries clicks, impressions, click-through rate and position of a site.
+    ///
+    /// # Arguments
+    ///
+    /// * `site_url` - The property, e.g. `https://www.example.com/` or
+    ///   `sc-domain:example.com`.
+    /// * `query` - The date range, dimensions and paging options.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the user has no
+    /// access to the property.
+    pub async fn query_search_analytics(
+        &self,
+        site_url: &str,
+        query: &SearchAnalyticsQuery,
+    ) -> Result<Vec<SearchAnalyticsRow>, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{SEARCH_CONSOLE_API}/sites/{}/searchAnalytics/query",
+                encode_path(site_url)
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(query);
+        let response: SearchAnalyticsResponse = self.google.send_json(request).await?;
+
+        Ok(response.rows)
+    }
+
+    /// Lists the sitemaps submitted for a site.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_sitemaps(&self, site_url: &str) -> Result<Vec<Sitemap>, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!(
+                "{SEARCH_CONSOLE_API}/sites/{}/sitemaps",
+                encode_path(site_url)
+            ))
+            .bearer_auth(self.token.access_token());
+        let response: SitemapsResponse = self.google.send_json(request).await?;
+
+        Ok(response.sitemap)
+    }
+
+    /// Submits a sitemap for a site, or resubmits it after changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `site_url` - The property the sitemap belongs to.
+    /// * `feedpath` - The URL of the sitemap, e.g. `https://www.example.com/sitemap.xml`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn submit_sitemap(
+        &self,
+        site_url: &str,
+        feedpath: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .put(sitemap_url(site_url, feedpath))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Removes a sitemap from a site.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn delete_sitemap(
+        &self,
+        site_url: &str,
+        feedpath: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .delete(sitemap_url(site_url, feedpath))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+}
+
+fn sitemap_url(site_url: &str, feedpath: &str) -> String {
+    format!(
+        "{SEARCH_CONSOLE_API}/sites/{}/sitemaps/{}",
+        encode_path(site_url),
+        encode_path(feedpath)
+    )
+}
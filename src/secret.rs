@@ -0,0 +1,41 @@
+use serde::Serializer;
+
+/// How secrets such as access tokens and private keys are held: a `secrecy::SecretString`
+/// with the `secrecy` feature, which is zeroized on drop, or a plain `String` otherwise.
+#[cfg(feature = "secrecy")]
+pub(crate) type Secret = secrecy::SecretString;
+#[cfg(not(feature = "secrecy"))]
+pub(crate) type Secret = String;
+
+/// Wraps a value received from Google as a secret.
+pub(crate) fn secret(value: String) -> Secret {
+    #[cfg(feature = "secrecy")]
+    return value.into();
+    #[cfg(not(feature = "secrecy"))]
+    return value;
+}
+
+/// Returns the value of a secret, to send it to Google.
+pub(crate) fn expose(secret: &Secret) -> &str {
+    #[cfg(feature = "secrecy")]
+    return secrecy::ExposeSecret::expose_secret(secret);
+    #[cfg(not(feature = "secrecy"))]
+    return secret;
+}
+
+/// Serializes a secret as a plain string, for `#[serde(serialize_with)]`. `SecretString`
+/// refuses to be serialized on its own.
+pub(crate) fn serialize<S: Serializer>(secret: &Secret, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(expose(secret))
+}
+
+/// Serializes an optional secret like `serialize`.
+pub(crate) fn serialize_option<S: Serializer>(
+    secret: &Option<Secret>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match secret {
+        Some(secret) => serializer.serialize_some(expose(secret)),
+        None => serializer.serialize_none(),
+    }
+}
@@ -0,0 +1,288 @@
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use chrono::Utc;
+use ring::rand::SystemRandom;
+use ring::signature::{RSA_PKCS1_SHA256, RsaKeyPair};
+use serde::Deserialize;
+use serde_json::json;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::secret::{Secret, expose};
+use crate::{Google, REDACTED, Scopes, Token};
+
+/// The environment variable pointing to the application default credentials key file.
+const CREDENTIALS_ENV: &str = "GOOGLE_APPLICATION_CREDENTIALS";
+/// The metadata server endpoint issuing tokens for the attached service account.
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// The lifetime requested for service account access tokens, the maximum Google allows.
+const ASSERTION_LIFETIME: i64 = 3600;
+
+/// The credentials of a Google Cloud service account, as found in the JSON key file
+/// downloaded from the Cloud console.
+///
+/// Its `Debug` output redacts the private key, which is zeroized when the key is dropped.
+#[derive(Deserialize)]
+pub struct ServiceAccountKey {
+    client_email: String,
+    private_key: Secret,
+    #[serde(default)]
+    private_key_id: Option<String>,
+    #[serde(default)]
+    project_id: Option<String>,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+impl ServiceAccountKey {
+    /// Parses a service account JSON key.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the JSON is not a service account key or its
+    /// private key is not a valid RSA key.
+    pub fn from_json(json: &str) -> Result<ServiceAccountKey, Box<dyn Error>> {
+        let key: ServiceAccountKey = serde_json::from_str(json)?;
+        key.key_pair()?;
+
+        Ok(key)
+    }
+
+    /// Reads and parses a service account JSON key file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<ServiceAccountKey, Box<dyn Error>> {
+        ServiceAccountKey::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// The email address identifying the service account.
+    pub fn client_email(&self) -> &str {
+        &self.client_email
+    }
+
+    /// The id of the private key, used as the `kid` of signed JWTs.
+    pub fn private_key_id(&self) -> Option<&str> {
+        self.private_key_id.as_deref()
+    }
+
+    /// The Google Cloud project the service account belongs to.
+    pub fn project_id(&self) -> Option<&str> {
+        self.project_id.as_deref()
+    }
+
+    /// The OAuth2 token endpoint to exchange signed assertions at.
+    pub fn token_uri(&self) -> &str {
+        &self.token_uri
+    }
+
+    /// Signs `message` with the private key using RSASSA-PKCS1-v1_5 with SHA-256.
+    pub(crate) fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let key_pair = self.key_pair()?;
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message,
+                &mut signature,
+            )
+            .map_err(|_| "Failed to sign with the service account key")?;
+
+        Ok(signature)
+    }
+
+    fn key_pair(&self) -> Result<RsaKeyPair, Box<dyn Error>> {
+        let pem: String = expose(&self.private_key)
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let der = STANDARD.decode(pem.trim())?;
+
+        RsaKeyPair::from_pkcs8(&der)
+            .map_err(|err| format!("Invalid service account private key: {err}").into())
+    }
+}
+
+impl fmt::Debug for ServiceAccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceAccountKey")
+            .field("client_email", &self.client_email)
+            .field("private_key", &REDACTED)
+            .field("private_key_id", &self.private_key_id)
+            .field("project_id", &self.project_id)
+            .field("token_uri", &self.token_uri)
+            .finish()
+    }
+}
+
+impl Zeroize for ServiceAccountKey {
+    fn zeroize(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+impl Drop for ServiceAccountKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for ServiceAccountKey {}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default = "default_token_type")]
+    token_type: String,
+    expires_in: Option<u64>,
+}
+
+impl Google {
+    /// Obtains an access token for a service account, using the OAuth2 JWT bearer grant.
+    ///
+    /// The returned `Token` works with every API client of this crate, just like a token
+    /// obtained through the user authorization flow. It has no refresh token; request a
+    /// new one before it expires after an hour.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The service account key to sign the assertion with.
+    /// * `scopes` - The scopes to request, e.g.
+    ///   `https://www.googleapis.com/auth/devstorage.read_write`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if signing fails or Google rejects the assertion.
+    pub async fn get_service_account_token(
+        &self,
+        key: &ServiceAccountKey,
+        scopes: &[&str],
+    ) -> Result<Token, Box<dyn Error>> {
+        self.service_account_token(key, None, scopes).await
+    }
+
+    /// Obtains an access token acting on behalf of a user of a Google Workspace domain,
+    /// using domain-wide delegation.
+    ///
+    /// The service account's client id must be granted the requested scopes in the Admin
+    /// console of the domain. This is required by APIs such as the Admin SDK, which only
+    /// accept tokens of domain administrators.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The service account key to sign the assertion with.
+    /// * `subject` - The email address of the user to impersonate.
+    /// * `scopes` - The scopes to request.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if signing fails or Google rejects the assertion,
+    /// e.g. with `unauthorized_client` when delegation was not granted.
+    pub async fn get_delegated_token(
+        &self,
+        key: &ServiceAccountKey,
+        subject: &str,
+        scopes: &[&str],
+    ) -> Result<Token, Box<dyn Error>> {
+        self.service_account_token(key, Some(subject), scopes).await
+    }
+
+    async fn service_account_token(
+        &self,
+        key: &ServiceAccountKey,
+        subject: Option<&str>,
+        scopes: &[&str],
+    ) -> Result<Token, Box<dyn Error>> {
+        self.check_scopes(scopes)?;
+        let assertion = key.assertion(subject, scopes)?;
+        let request = self.http.post(key.token_uri()).form(&[
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", &assertion),
+        ]);
+        let response: TokenResponse = self.send_json(request).await?;
+
+        Ok(Token::new(
+            response.access_token,
+            response.token_type,
+            response.expires_in.map(Duration::from_secs),
+            None,
+            Scopes::new(scopes.iter().copied()),
+        ))
+    }
+
+    /// Obtains an access token from the application default credentials (ADC).
+    ///
+    /// The service account key file named by `GOOGLE_APPLICATION_CREDENTIALS` is used when
+    /// set. Otherwise the token is requested from the metadata server of the Google Cloud
+    /// environment the application runs in, e.g. Compute Engine, Cloud Run or GKE.
+    ///
+    /// # Arguments
+    ///
+    /// * `scopes` - The scopes to request.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the key file cannot be read, or if no metadata
+    /// server is reachable outside of Google Cloud.
+    pub async fn get_default_token(&self, scopes: &[&str]) -> Result<Token, Box<dyn Error>> {
+        if let Ok(path) = std::env::var(CREDENTIALS_ENV) {
+            let key = ServiceAccountKey::from_file(path)?;
+            return self.get_service_account_token(&key, scopes).await;
+        }
+
+        self.check_scopes(scopes)?;
+        let request = self
+            .http
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .query(&[("scopes", scopes.join(","))]);
+        let response: TokenResponse = self.send_json(request).await?;
+
+        Ok(Token::new(
+            response.access_token,
+            response.token_type,
+            response.expires_in.map(Duration::from_secs),
+            None,
+            Scopes::new(scopes.iter().copied()),
+        ))
+    }
+}
+
+impl ServiceAccountKey {
+    /// Builds the signed JWT assertion exchanged for an access token.
+    fn assertion(&self, subject: Option<&str>, scopes: &[&str]) -> Result<String, Box<dyn Error>> {
+        let now = Utc::now().timestamp();
+        let header = json!({ "alg": "RS256", "typ": "JWT", "kid": self.private_key_id });
+        let mut claims = json!({
+            "iss": self.client_email,
+            "scope": scopes.join(" "),
+            "aud": self.token_uri,
+            "iat": now,
+            "exp": now + ASSERTION_LIFETIME,
+        });
+        if let Some(subject) = subject {
+            claims["sub"] = subject.into();
+        }
+
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = self.sign(message.as_bytes())?;
+
+        Ok(format!("{message}.{}", URL_SAFE_NO_PAD.encode(signature)))
+    }
+}
+
+fn default_token_type() -> String {
+    "Bearer".to_string()
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
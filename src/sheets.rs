@@ -0,0 +1,300 @@
+//! Google Sheets API.
+
+use std::error::Error;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::api::encode_path;
+use crate::{Google, Token};
+
+const SHEETS_API: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+
+/// Scope granting read-only access to spreadsheets.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/spreadsheets.readonly";
+
+/// Scope granting read and write access to spreadsheets.
+pub const SCOPE_SPREADSHEETS: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+/// A client for the Sheets API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Sheets<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// The values of a range of cells.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ValueRange {
+    /// The range in A1 notation, e.g. `Sheet1!A1:D10`.
+    pub range: String,
+    /// `ROWS` or `COLUMNS`.
+    pub major_dimension: String,
+    /// The cell values. Trailing empty rows and cells are omitted by the API.
+    pub values: Vec<Vec<Value>>,
+}
+
+/// How values are rendered in the response.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ValueRenderOption {
+    /// Values as displayed in the UI, always strings, e.g. `$1.23`.
+    #[default]
+    FormattedValue,
+    /// Raw values: numbers stay numbers, e.g. `1.23`.
+    UnformattedValue,
+    /// Formulas instead of their results.
+    Formula,
+}
+
+/// How written values are interpreted.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ValueInputOption {
+    /// Values are stored as-is.
+    Raw,
+    /// Values are parsed as if typed into the UI, so `=SUM(A1:A3)` becomes a formula and
+    /// `2024-01-01` a date.
+    #[default]
+    UserEntered,
+}
+
+/// How appended values are added to the sheet.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InsertDataOption {
+    /// Values overwrite whatever follows the table.
+    #[default]
+    Overwrite,
+    /// New rows are inserted for the values, shifting existing content down.
+    InsertRows,
+}
+
+/// The result of writing values.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateValuesResponse {
+    pub spreadsheet_id: String,
+    pub updated_range: String,
+    pub updated_rows: u32,
+    pub updated_columns: u32,
+    pub updated_cells: u32,
+}
+
+/// The result of appending values.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppendValuesResponse {
+    pub spreadsheet_id: String,
+    /// The range of the table the values were appended to, before the append.
+    pub table_range: Option<String>,
+    pub updates: UpdateValuesResponse,
+}
+
+/// The result of clearing values.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClearValuesResponse {
+    pub spreadsheet_id: String,
+    pub cleared_range: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchGetResponse {
+    #[serde(default)]
+    value_ranges: Vec<ValueRange>,
+}
+
+impl ValueRange {
+    /// Returns the values as strings, one `Vec` per row (or column). Numbers and booleans
+    /// are formatted; empty cells become empty strings.
+    pub fn to_strings(&self) -> Vec<Vec<String>> {
+        self.values
+            .iter()
+            .map(|row| row.iter().map(cell_to_string).collect())
+            .collect()
+    }
+
+    /// Deserializes every row after the first into a `T`, using the first row as the
+    /// field names.
+    ///
+    /// With `ValueRenderOption::FormattedValue` all cells are strings, so numeric fields of
+    /// `T` require `ValueRenderOption::UnformattedValue`. Missing cells are `null`, which
+    /// `Option` fields accept.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a row does not deserialize into `T`.
+    pub fn deserialize_rows<T: DeserializeOwned>(&self) -> Result<Vec<T>, Box<dyn Error>> {
+        let Some((header, rows)) = self.values.split_first() else {
+            return Ok(Vec::new());
+        };
+        let header: Vec<String> = header.iter().map(cell_to_string).collect();
+
+        rows.iter()
+            .map(|row| {
+                let record: Map<String, Value> = header
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| (name.clone(), row.get(i).cloned().unwrap_or(Value::Null)))
+                    .collect();
+
+                serde_json::from_value(Value::Object(record)).map_err(|err| err.into())
+            })
+            .collect()
+    }
+}
+
+impl Google {
+    /// Returns a Sheets API client that authorizes its requests with `token`.
+    pub fn sheets<'a>(&'a self, token: &'a Token) -> Sheets<'a> {
+        Sheets {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl Sheets<'_> {
+    /// Reads the values of a range.
+    ///
+    /// # Arguments
+    ///
+    /// * `spreadsheet_id` - The id from the spreadsheet URL.
+    /// * `range` - The range in A1 notation, e.g. `Sheet1!A1:D` or a named range.
+    /// * `render` - How values are rendered.
+    pub async fn get_values(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        render: ValueRenderOption,
+    ) -> Result<ValueRange, Box<dyn Error>> {
+        let range = encode_path(range);
+        let request = self
+            .google
+            .http
+            .get(format!("{SHEETS_API}/{spreadsheet_id}/values/{range}"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("valueRenderOption", render)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Reads the values of several ranges in a single request, in the order of `ranges`.
+    pub async fn batch_get_values(
+        &self,
+        spreadsheet_id: &str,
+        ranges: &[&str],
+        render: ValueRenderOption,
+    ) -> Result<Vec<ValueRange>, Box<dyn Error>> {
+        let mut params: Vec<(&str, Value)> = ranges
+            .iter()
+            .map(|range| ("ranges", Value::from(*range)))
+            .collect();
+        params.push(("valueRenderOption", serde_json::to_value(render)?));
+
+        let request = self
+            .google
+            .http
+            .get(format!("{SHEETS_API}/{spreadsheet_id}/values:batchGet"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        let response: BatchGetResponse = self.google.send_json(request).await?;
+
+        Ok(response.value_ranges)
+    }
+
+    /// Writes values into a range, replacing its current content.
+    ///
+    /// # Arguments
+    ///
+    /// * `spreadsheet_id` - The id from the spreadsheet URL.
+    /// * `range` - The range in A1 notation to write to, e.g. `Sheet1!A2`.
+    /// * `rows` - The rows to write; anything serializing to a JSON array works, e.g.
+    ///   `vec![("Alice", 30), ("Bob", 25)]` or `Vec<Vec<serde_json::Value>>`.
+    /// * `input` - How the values are interpreted.
+    pub async fn update_values<R: Serialize>(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        rows: &[R],
+        input: ValueInputOption,
+    ) -> Result<UpdateValuesResponse, Box<dyn Error>> {
+        let range_path = encode_path(range);
+        let request = self
+            .google
+            .http
+            .put(format!("{SHEETS_API}/{spreadsheet_id}/values/{range_path}"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("valueInputOption", input)])
+            .json(&json!({ "range": range, "majorDimension": "ROWS", "values": rows }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Appends rows after the last row of the table found in `range`, the typical way of
+    /// using a sheet as a lightweight database.
+    ///
+    /// # Arguments
+    ///
+    /// * `spreadsheet_id` - The id from the spreadsheet URL.
+    /// * `range` - A range in A1 notation used to find the table, e.g. `Sheet1!A:D`.
+    /// * `rows` - The rows to append, as for `update_values`.
+    /// * `input` - How the values are interpreted.
+    /// * `insert` - Whether to overwrite cells after the table or insert new rows.
+    pub async fn append_values<R: Serialize>(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+        rows: &[R],
+        input: ValueInputOption,
+        insert: InsertDataOption,
+    ) -> Result<AppendValuesResponse, Box<dyn Error>> {
+        let range_path = encode_path(range);
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{SHEETS_API}/{spreadsheet_id}/values/{range_path}:append"
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&[
+                ("valueInputOption", serde_json::to_value(input)?),
+                ("insertDataOption", serde_json::to_value(insert)?),
+            ])
+            .json(&json!({ "range": range, "majorDimension": "ROWS", "values": rows }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Clears the values of a range, keeping formatting and validation rules.
+    pub async fn clear_values(
+        &self,
+        spreadsheet_id: &str,
+        range: &str,
+    ) -> Result<ClearValuesResponse, Box<dyn Error>> {
+        let range = encode_path(range);
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{SHEETS_API}/{spreadsheet_id}/values/{range}:clear"
+            ))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({}));
+
+        self.google.send_json(request).await
+    }
+}
+
+fn cell_to_string(cell: &Value) -> String {
+    match cell {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,227 @@
+//! Google Slides API.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::{Google, Token};
+
+const SLIDES_API: &str = "https://slides.googleapis.com/v1/presentations";
+
+/// Scope granting read-only access to presentations.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/presentations.readonly";
+
+/// Scope granting read and write access to presentations.
+pub const SCOPE_PRESENTATIONS: &str = "https://www.googleapis.com/auth/presentations";
+
+/// A client for the Slides API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Slides<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A presentation and its slides.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Presentation {
+    pub presentation_id: String,
+    pub title: String,
+    pub revision_id: Option<String>,
+    pub slides: Vec<Slide>,
+    pub page_size: Option<Value>,
+}
+
+/// A single slide.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Slide {
+    pub object_id: String,
+    pub page_elements: Vec<PageElement>,
+}
+
+/// An element placed on a slide. Only shapes are typed; other kinds are kept as raw JSON.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PageElement {
+    pub object_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub shape: Option<Shape>,
+    pub image: Option<Value>,
+    pub table: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Shape {
+    pub shape_type: String,
+    pub text: Option<TextContent>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextContent {
+    pub text_elements: Vec<TextElement>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextElement {
+    pub text_run: Option<TextRun>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextRun {
+    pub content: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstringMatchCriteria {
+    pub text: String,
+    pub match_case: bool,
+}
+
+/// How a replacement image is fitted into the replaced shape or image.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImageReplaceMethod {
+    /// Scale the image to fit inside the bounds, keeping its aspect ratio.
+    #[default]
+    CenterInside,
+    /// Scale the image to fill the bounds, cropping it to keep its aspect ratio.
+    CenterCrop,
+}
+
+/// A single edit applied by `Slides::batch_update`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Request {
+    /// Replaces every occurrence of a text, e.g. a `{{title}}` placeholder.
+    ReplaceAllText {
+        contains_text: SubstringMatchCriteria,
+        replace_text: String,
+        /// Limit the replacement to these slides, or all slides if empty.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        page_object_ids: Vec<String>,
+    },
+    /// Replaces every shape containing a text, e.g. a `{{logo}}` placeholder box, with an
+    /// image fetched from a public URL.
+    ReplaceAllShapesWithImage {
+        contains_text: SubstringMatchCriteria,
+        image_url: String,
+        image_replace_method: ImageReplaceMethod,
+    },
+    /// Replaces an existing image, keeping its position and size.
+    ReplaceImage {
+        image_object_id: String,
+        url: String,
+        image_replace_method: ImageReplaceMethod,
+    },
+    /// Duplicates a slide or element, e.g. to repeat a template slide per record.
+    DuplicateObject { object_id: String },
+    /// Deletes a slide or element.
+    DeleteObject { object_id: String },
+}
+
+/// The result of `Slides::batch_update`, with one reply per request.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BatchUpdateResponse {
+    pub presentation_id: String,
+    /// Replies in request order, e.g. `{"replaceAllText": {"occurrencesChanged": 2}}`.
+    pub replies: Vec<Value>,
+}
+
+impl Slide {
+    /// Returns the text of all shapes on the slide.
+    pub fn text(&self) -> String {
+        self.page_elements
+            .iter()
+            .filter_map(|element| element.shape.as_ref()?.text.as_ref())
+            .flat_map(|text| &text.text_elements)
+            .filter_map(|element| element.text_run.as_ref())
+            .map(|run| run.content.as_str())
+            .collect()
+    }
+}
+
+impl Request {
+    /// Replaces every case-sensitive occurrence of `placeholder` with `value`.
+    pub fn replace_all_text(placeholder: impl Into<String>, value: impl Into<String>) -> Request {
+        Request::ReplaceAllText {
+            contains_text: SubstringMatchCriteria {
+                text: placeholder.into(),
+                match_case: true,
+            },
+            replace_text: value.into(),
+            page_object_ids: Vec::new(),
+        }
+    }
+
+    /// Replaces every shape containing `placeholder` with the image at `image_url`, which
+    /// must be publicly accessible, under 50 MB and in PNG, JPEG or GIF format.
+    pub fn replace_shapes_with_image(
+        placeholder: impl Into<String>,
+        image_url: impl Into<String>,
+    ) -> Request {
+        Request::ReplaceAllShapesWithImage {
+            contains_text: SubstringMatchCriteria {
+                text: placeholder.into(),
+                match_case: true,
+            },
+            image_url: image_url.into(),
+            image_replace_method: ImageReplaceMethod::CenterInside,
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Slides API client that authorizes its requests with `token`.
+    pub fn slides<'a>(&'a self, token: &'a Token) -> Slides<'a> {
+        Slides {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl Slides<'_> {
+    /// Fetches a presentation with all its slides.
+    pub async fn get_presentation(
+        &self,
+        presentation_id: &str,
+    ) -> Result<Presentation, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{SLIDES_API}/{presentation_id}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    /// Applies `requests` to a presentation atomically: if one request fails, none is
+    /// applied.
+    ///
+    /// For generating decks from a template, copy the template with `Drive::copy_file`
+    /// and apply `Request::replace_all_text` and `Request::replace_shapes_with_image` to
+    /// the copy.
+    pub async fn batch_update(
+        &self,
+        presentation_id: &str,
+        requests: &[Request],
+    ) -> Result<BatchUpdateResponse, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{SLIDES_API}/{presentation_id}:batchUpdate"))
+            .bearer_auth(self.token.access_token())
+            .json(&json!({ "requests": requests }));
+
+        self.google.send_json(request).await
+    }
+}
@@ -0,0 +1,254 @@
+//! Cloud Speech-to-Text API, for transcribing audio.
+
+use std::error::Error;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::{Auth, encode_path};
+use crate::vision::Status;
+use crate::{Google, Token};
+
+/// Scope granting access to Google Cloud APIs, including Speech-to-Text.
+pub const SCOPE_CLOUD_PLATFORM: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+const SPEECH_API: &str = "https://speech.googleapis.com/v1";
+
+/// A client for the Speech-to-Text API.
+#[derive(Clone, Copy)]
+pub struct Speech<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// The audio to transcribe.
+#[derive(Debug, Clone)]
+pub enum Audio {
+    /// The encoded audio, at most one minute long for synchronous recognition.
+    Bytes(Vec<u8>),
+    /// A Cloud Storage URI, `gs://bucket/object`, required for audio longer than 10 MB.
+    Uri(String),
+}
+
+/// How the audio is encoded and how it should be recognized.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecognitionConfig {
+    /// The audio encoding, e.g. `LINEAR16`, `FLAC` or `OGG_OPUS`. May be omitted for
+    /// FLAC and WAV, whose headers carry it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate_hertz: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_channel_count: Option<u32>,
+    /// The BCP-47 language of the audio, e.g. `en-US`.
+    pub language_code: String,
+    /// Up to three further languages the audio may be in.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alternative_language_codes: Vec<String>,
+    /// The maximum number of alternatives per result, between 1 and 30.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_alternatives: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub enable_automatic_punctuation: bool,
+    /// Whether `WordInfo` entries with start and end times are returned.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub enable_word_time_offsets: bool,
+    /// The recognition model, e.g. `latest_long`, `phone_call` or `video`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// The transcription of an audio file.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecognizeResponse {
+    /// Consecutive portions of the audio, in order.
+    pub results: Vec<SpeechRecognitionResult>,
+    /// The billed audio duration, e.g. `15s`.
+    pub total_billed_time: Option<String>,
+}
+
+/// The transcription of a portion of the audio.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeechRecognitionResult {
+    /// Possible transcriptions, the most likely first.
+    pub alternatives: Vec<SpeechRecognitionAlternative>,
+    pub channel_tag: Option<i32>,
+    /// The detected language, when alternative languages were given.
+    pub language_code: Option<String>,
+    pub result_end_time: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeechRecognitionAlternative {
+    pub transcript: String,
+    /// The confidence between 0 and 1, only set for the first alternative.
+    pub confidence: f32,
+    pub words: Vec<WordInfo>,
+}
+
+/// A recognized word and its position in the audio, e.g. `1.300s`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WordInfo {
+    pub word: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// A long-running operation, polled with `Speech::get_operation`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Operation {
+    pub name: String,
+    pub done: bool,
+    /// Progress information, e.g. `progressPercent`.
+    pub metadata: Option<Value>,
+    /// Set when the operation failed.
+    pub error: Option<Status>,
+    /// The result, set when the operation succeeded.
+    pub response: Option<Value>,
+}
+
+impl RecognizeResponse {
+    /// The most likely transcription of the whole audio, joining the first alternative of
+    /// every result.
+    pub fn transcript(&self) -> String {
+        self.results
+            .iter()
+            .filter_map(|result| result.alternatives.first())
+            .map(|alternative| alternative.transcript.trim())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Audio {
+    fn to_json(&self) -> Value {
+        match self {
+            Audio::Bytes(bytes) => json!({ "content": STANDARD.encode(bytes) }),
+            Audio::Uri(uri) => json!({ "uri": uri }),
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Speech-to-Text API client that authorizes its requests with `token`.
+    pub fn speech<'a>(&'a self, token: &'a Token) -> Speech<'a> {
+        Speech {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a Speech-to-Text API client that authorizes its requests with an API key.
+    pub fn speech_with_key<'a>(&'a self, api_key: &'a str) -> Speech<'a> {
+        Speech {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl Speech<'_> {
+    /// Transcribes up to one minute of audio synchronously.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the audio is
+    /// too long for synchronous recognition.
+    pub async fn recognize(
+        &self,
+        config: &RecognitionConfig,
+        audio: &Audio,
+    ) -> Result<RecognizeResponse, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{SPEECH_API}/speech:recognize")),
+            )
+            .json(&json!({ "config": config, "audio": audio.to_json() }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Starts transcribing up to 480 minutes of audio, returning the operation to poll.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the operation cannot be started.
+    pub async fn long_running_recognize(
+        &self,
+        config: &RecognitionConfig,
+        audio: &Audio,
+    ) -> Result<Operation, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{SPEECH_API}/speech:longrunningrecognize")),
+            )
+            .json(&json!({ "config": config, "audio": audio.to_json() }));
+
+        self.google.send_json(request).await
+    }
+
+    /// Fetches the current state of a long-running operation.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_operation(&self, name: &str) -> Result<Operation, Box<dyn Error>> {
+        let request = self.auth.apply(
+            self.google
+                .http
+                .get(format!("{SPEECH_API}/operations/{}", encode_path(name))),
+        );
+
+        self.google.send_json(request).await
+    }
+
+    /// Polls a long-running recognition until it is done and returns its transcription.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the operation returned by `long_running_recognize`.
+    /// * `poll_interval` - How long to wait between polls. Recognition takes roughly half
+    ///   the audio duration, so a few seconds is usually appropriate.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if polling fails or the recognition failed.
+    pub async fn wait_for_recognition(
+        &self,
+        name: &str,
+        poll_interval: Duration,
+    ) -> Result<RecognizeResponse, Box<dyn Error>> {
+        loop {
+            let operation = self.get_operation(name).await?;
+            if operation.done {
+                if let Some(status) = operation.error {
+                    return Err(format!(
+                        "Speech recognition failed ({}): {}",
+                        status.code, status.message
+                    )
+                    .into());
+                }
+                return Ok(serde_json::from_value(
+                    operation.response.unwrap_or_default(),
+                )?);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
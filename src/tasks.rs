@@ -0,0 +1,317 @@
+//! Google Tasks API.
+
+use std::error::Error;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{Page, paginate};
+use crate::{Google, Token};
+
+const TASKS_API: &str = "https://tasks.googleapis.com/tasks/v1";
+
+/// Scope granting read-only access to tasks.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/tasks.readonly";
+
+/// Scope granting read and write access to tasks.
+pub const SCOPE_TASKS: &str = "https://www.googleapis.com/auth/tasks";
+
+/// A client for the Tasks API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct Tasks<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A list of tasks.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskList {
+    pub id: String,
+    pub title: String,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// A task.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub notes: Option<String>,
+    /// `needsAction` or `completed`.
+    pub status: String,
+    /// The due date. Only the date part is used by Google Tasks.
+    pub due: Option<DateTime<Utc>>,
+    pub completed: Option<DateTime<Utc>>,
+    /// The parent task, for subtasks.
+    pub parent: Option<String>,
+    /// A string sorting the task among its siblings.
+    pub position: String,
+    pub deleted: bool,
+    pub hidden: bool,
+    pub updated: Option<DateTime<Utc>>,
+    pub web_view_link: Option<String>,
+}
+
+/// A task to be created with `Tasks::insert_task`, or the fields to change with
+/// `Tasks::patch_task`. Unset fields are left out of the request.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NewTask {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// `needsAction` or `completed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Utc>>,
+}
+
+/// Options for listing tasks.
+#[derive(Debug, Clone)]
+pub struct TaskQuery {
+    pub show_completed: bool,
+    /// Include completed tasks cleared from the list.
+    pub show_hidden: bool,
+    pub show_deleted: bool,
+    pub due_min: Option<DateTime<Utc>>,
+    pub due_max: Option<DateTime<Utc>>,
+    /// Only tasks modified after this time, for incremental sync.
+    pub updated_min: Option<DateTime<Utc>>,
+    /// The number of tasks per page, at most 100.
+    pub max_results: Option<u32>,
+}
+
+/// A page of task lists returned by `tasklists.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskLists {
+    pub items: Vec<TaskList>,
+    pub next_page_token: Option<String>,
+}
+
+/// A page of tasks returned by `tasks.list`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskPage {
+    pub items: Vec<Task>,
+    pub next_page_token: Option<String>,
+}
+
+impl Default for TaskQuery {
+    fn default() -> Self {
+        TaskQuery {
+            show_completed: true,
+            show_hidden: false,
+            show_deleted: false,
+            due_min: None,
+            due_max: None,
+            updated_min: None,
+            max_results: None,
+        }
+    }
+}
+
+impl Page for TaskLists {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Page for TaskPage {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Google {
+    /// Returns a Tasks API client that authorizes its requests with `token`.
+    pub fn tasks<'a>(&'a self, token: &'a Token) -> Tasks<'a> {
+        Tasks {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl<'a> Tasks<'a> {
+    /// Lists a single page of the user's task lists.
+    pub async fn list_task_lists(
+        &self,
+        page_token: Option<&str>,
+    ) -> Result<TaskLists, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{TASKS_API}/users/@me/lists"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of the user's task lists.
+    pub fn task_lists(self) -> impl Stream<Item = Result<TaskLists, Box<dyn Error>>> + 'a {
+        paginate(move |page_token| async move { self.list_task_lists(page_token.as_deref()).await })
+    }
+
+    /// Lists a single page of tasks.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_list` - The id of the task list, `@default` for the user's default list.
+    /// * `query` - Filters on completion, due date and modification time.
+    /// * `page_token` - The `next_page_token` of the previous page, `None` for the first.
+    pub async fn list_tasks(
+        &self,
+        task_list: &str,
+        query: &TaskQuery,
+        page_token: Option<&str>,
+    ) -> Result<TaskPage, Box<dyn Error>> {
+        let mut params = vec![
+            ("showCompleted", query.show_completed.to_string()),
+            ("showHidden", query.show_hidden.to_string()),
+            ("showDeleted", query.show_deleted.to_string()),
+        ];
+        for (name, time) in [
+            ("dueMin", query.due_min),
+            ("dueMax", query.due_max),
+            ("updatedMin", query.updated_min),
+        ] {
+            if let Some(time) = time {
+                params.push((name, time.to_rfc3339_opts(SecondsFormat::Secs, true)));
+            }
+        }
+        if let Some(max_results) = query.max_results {
+            params.push(("maxResults", max_results.to_string()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token.to_string()));
+        }
+
+        let request = self
+            .google
+            .http
+            .get(format!("{TASKS_API}/lists/{task_list}/tasks"))
+            .bearer_auth(self.token.access_token())
+            .query(&params);
+
+        self.google.send_json(request).await
+    }
+
+    /// Streams every page of tasks matching `query`.
+    pub fn tasks<'s>(
+        self,
+        task_list: &'s str,
+        query: &'s TaskQuery,
+    ) -> impl Stream<Item = Result<TaskPage, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_tasks(task_list, query, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Creates a task.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_list` - The id of the task list, `@default` for the user's default list.
+    /// * `task` - The task to create.
+    /// * `parent` - The parent task, to create a subtask.
+    /// * `previous` - The sibling to insert the task after, or first if `None`.
+    pub async fn insert_task(
+        &self,
+        task_list: &str,
+        task: &NewTask,
+        parent: Option<&str>,
+        previous: Option<&str>,
+    ) -> Result<Task, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!("{TASKS_API}/lists/{task_list}/tasks"))
+            .bearer_auth(self.token.access_token())
+            .query(&[("parent", parent), ("previous", previous)])
+            .json(task);
+
+        self.google.send_json(request).await
+    }
+
+    /// Updates the fields of a task that are set in `task`.
+    pub async fn patch_task(
+        &self,
+        task_list: &str,
+        task_id: &str,
+        task: &NewTask,
+    ) -> Result<Task, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .patch(format!("{TASKS_API}/lists/{task_list}/tasks/{task_id}"))
+            .bearer_auth(self.token.access_token())
+            .json(task);
+
+        self.google.send_json(request).await
+    }
+
+    /// Moves a task within its list, changing its parent and position.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_list` - The id of the task list.
+    /// * `task_id` - The task to move.
+    /// * `parent` - The new parent task, or `None` to move it to the top level.
+    /// * `previous` - The sibling to move the task after, or first if `None`.
+    pub async fn move_task(
+        &self,
+        task_list: &str,
+        task_id: &str,
+        parent: Option<&str>,
+        previous: Option<&str>,
+    ) -> Result<Task, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .post(format!(
+                "{TASKS_API}/lists/{task_list}/tasks/{task_id}/move"
+            ))
+            .bearer_auth(self.token.access_token())
+            .query(&[("parent", parent), ("previous", previous)]);
+
+        self.google.send_json(request).await
+    }
+
+    /// Marks a task as completed.
+    pub async fn complete_task(
+        &self,
+        task_list: &str,
+        task_id: &str,
+    ) -> Result<Task, Box<dyn Error>> {
+        let task = NewTask {
+            status: Some("completed".to_string()),
+            ..NewTask::default()
+        };
+
+        self.patch_task(task_list, task_id, &task).await
+    }
+
+    /// Deletes a task.
+    pub async fn delete_task(&self, task_list: &str, task_id: &str) -> Result<(), Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .delete(format!("{TASKS_API}/lists/{task_list}/tasks/{task_id}"))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send(request).await?;
+
+        Ok(())
+    }
+}
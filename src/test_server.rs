@@ -0,0 +1,81 @@
+//! A local HTTP server for tests, answering each connection with a canned response.
+
+use std::sync::{Arc, Mutex};
+
+use oauth2::TokenUrl;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::Google;
+
+/// A server answering one connection per canned response, in order, and recording the
+/// requests it received.
+pub(crate) struct TestServer {
+    /// The base URL of the server, e.g. `http://127.0.0.1:4321`.
+    pub(crate) url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    /// Starts a server answering the connections it accepts with `responses`, one each.
+    pub(crate) async fn start(responses: Vec<String>) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let read = socket.read(&mut request).await.unwrap();
+                received
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&request[..read]).to_lowercase());
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        TestServer { url, requests }
+    }
+
+    /// The requests received so far, lowercased.
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Builds a response with `status`, e.g. `200 OK`, that closes the connection.
+pub(crate) fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}\r\n"))
+        .collect();
+
+    format!(
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Builds a token endpoint response issuing `access_token`, without a `scope`.
+pub(crate) fn token_response(access_token: &str) -> String {
+    let body = serde_json::json!({
+        "access_token": access_token,
+        "token_type": "Bearer",
+        "expires_in": 3600,
+    });
+
+    response(
+        "200 OK",
+        &[("Content-Type", "application/json")],
+        &body.to_string(),
+    )
+}
+
+/// Points the token endpoint of `google` at `server`.
+pub(crate) fn with_token_server(mut google: Google, server: &TestServer) -> Google {
+    let token_url = TokenUrl::new(format!("{}/token", server.url)).unwrap();
+    google.client = Arc::new((*google.client).clone().set_token_uri(token_url));
+    google
+}
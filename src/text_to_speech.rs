@@ -0,0 +1,193 @@
+//! Cloud Text-to-Speech API, for synthesizing speech from text or SSML.
+
+use std::error::Error;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::Auth;
+use crate::{Google, Token};
+
+const TEXT_TO_SPEECH_API: &str = "https://texttospeech.googleapis.com/v1";
+
+/// A client for the Text-to-Speech API.
+#[derive(Clone, Copy)]
+pub struct TextToSpeech<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// The content to synthesize.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum SynthesisInput {
+    /// Plain text.
+    Text(String),
+    /// An SSML document, e.g. `<speak>Hello <break time="1s"/> world</speak>`.
+    Ssml(String),
+}
+
+/// The voice to synthesize with. Unset fields are chosen by the service.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceSelection {
+    /// The BCP-47 language, e.g. `en-US`.
+    pub language_code: String,
+    /// A voice name from `list_voices`, e.g. `en-US-Neural2-C`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssml_gender: Option<SsmlGender>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SsmlGender {
+    #[default]
+    #[serde(rename = "SSML_VOICE_GENDER_UNSPECIFIED")]
+    Unspecified,
+    Male,
+    Female,
+    Neutral,
+}
+
+/// The format of the synthesized audio.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioConfig {
+    pub audio_encoding: AudioEncoding,
+    /// The speed relative to the voice's normal rate, between 0.25 and 4.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaking_rate: Option<f64>,
+    /// The pitch shift in semitones, between -20.0 and 20.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<f64>,
+    /// The volume gain in dB, between -96.0 and 16.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_gain_db: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate_hertz: Option<u32>,
+    /// Audio profiles to optimize for, e.g. `headphone-class-device`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub effects_profile_id: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AudioEncoding {
+    /// MP3 at 32 kbps.
+    #[default]
+    Mp3,
+    /// Uncompressed 16-bit PCM with a WAV header.
+    Linear16,
+    /// Opus in an Ogg container.
+    OggOpus,
+    /// 8-bit G.711 μ-law with a WAV header.
+    Mulaw,
+    /// 8-bit G.711 A-law with a WAV header.
+    Alaw,
+}
+
+/// A voice supported by the API.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Voice {
+    pub language_codes: Vec<String>,
+    pub name: String,
+    pub ssml_gender: SsmlGender,
+    pub natural_sample_rate_hertz: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SynthesizeResponse {
+    audio_content: String,
+}
+
+#[derive(Deserialize)]
+struct VoicesResponse {
+    #[serde(default)]
+    voices: Vec<Voice>,
+}
+
+impl Google {
+    /// Returns a Text-to-Speech API client that authorizes its requests with `token`,
+    /// which needs the `cloud-platform` scope.
+    pub fn text_to_speech<'a>(&'a self, token: &'a Token) -> TextToSpeech<'a> {
+        TextToSpeech {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a Text-to-Speech API client that authorizes its requests with an API key.
+    pub fn text_to_speech_with_key<'a>(&'a self, api_key: &'a str) -> TextToSpeech<'a> {
+        TextToSpeech {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl TextToSpeech<'_> {
+    /// Synthesizes speech, returning the encoded audio.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The text or SSML to speak.
+    /// * `voice` - The voice to speak with.
+    /// * `audio_config` - The format of the returned audio.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the SSML is
+    /// invalid or no voice matches the selection.
+    pub async fn synthesize(
+        &self,
+        input: &SynthesisInput,
+        voice: &VoiceSelection,
+        audio_config: &AudioConfig,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{TEXT_TO_SPEECH_API}/text:synthesize")),
+            )
+            .json(&json!({
+                "input": input,
+                "voice": voice,
+                "audioConfig": audio_config,
+            }));
+        let response: SynthesizeResponse = self.google.send_json(request).await?;
+
+        Ok(STANDARD.decode(response.audio_content)?)
+    }
+
+    /// Lists the available voices.
+    ///
+    /// # Arguments
+    ///
+    /// * `language_code` - Only return voices supporting this language, e.g. `en-US`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn list_voices(
+        &self,
+        language_code: Option<&str>,
+    ) -> Result<Vec<Voice>, Box<dyn Error>> {
+        let mut request = self
+            .auth
+            .apply(self.google.http.get(format!("{TEXT_TO_SPEECH_API}/voices")));
+        if let Some(language_code) = language_code {
+            request = request.query(&[("languageCode", language_code)]);
+        }
+        let response: VoicesResponse = self.google.send_json(request).await?;
+
+        Ok(response.voices)
+    }
+}
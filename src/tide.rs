@@ -0,0 +1,113 @@
+//! Tide integration: endpoints for the sign-in flow and a `GoogleAuth` middleware exposing
+//! the signed-in user on the request extensions.
+//!
+//! The endpoints are served by a nested server holding the `Google` client as its state:
+//!
+//! ```no_run
+//! # fn client() -> async_google_auth::Google { unimplemented!() }
+//! use async_google_auth::UserInfo;
+//! use async_google_auth::tide::{GoogleAuth, server};
+//!
+//! let google = client();
+//! let mut app = tide::new();
+//! app.at("/auth").nest(server(google.clone()));
+//! app.at("/profile")
+//!     .with(GoogleAuth::new(google))
+//!     .get(|req: tide::Request<()>| async move {
+//!         let user = req.ext::<UserInfo>().unwrap();
+//!         Ok(format!("Hello {}", user.email))
+//!     });
+//! ```
+//!
+//! With the app's callback URL pointing to `/auth/callback`, `/auth/login` signs the user
+//! in and redirects to `/`. The user's ID token is then kept in a cookie, so the session
+//! lasts as long as the token, about an hour.
+//!
+//! Requests to Google are sent with `reqwest`, which needs a Tokio runtime: on async-std,
+//! enable its `tokio1` feature.
+
+use ::tide::http::headers::{AUTHORIZATION, COOKIE, LOCATION, SET_COOKIE};
+use ::tide::http::{Method, StatusCode};
+use ::tide::utils::async_trait;
+use ::tide::{Error, Middleware, Next, Request, Response, Result, Server};
+
+use crate::web::AFTER_LOGIN_PATH;
+use crate::{AuthCallback, Google, UserInfo};
+
+/// Rejects requests without a valid ID token, in the `Authorization: Bearer` header or the
+/// cookie set by `callback`, with `401 Unauthorized`, and otherwise inserts the user's
+/// `UserInfo` into the request extensions.
+#[derive(Debug, Clone)]
+pub struct GoogleAuth {
+    google: Google,
+}
+
+/// Returns a server serving `login` at `/login` and `callback` at `/callback`, to be
+/// nested into the app.
+pub fn server(google: Google) -> Server<Google> {
+    let mut server = Server::with_state(google);
+    server.at("/login").get(login);
+    server.at("/callback").get(callback).post(callback);
+
+    server
+}
+
+/// Redirects the user to Google's consent screen.
+pub async fn login(request: Request<Google>) -> Result {
+    let (url, cookie) = request
+        .state()
+        .start_login()
+        .map_err(|err| Error::from_str(StatusCode::Forbidden, err.to_string()))?;
+
+    let mut response = Response::new(StatusCode::Found);
+    response.insert_header(LOCATION, url);
+    response.insert_header(SET_COOKIE, cookie);
+    Ok(response)
+}
+
+/// Handles the redirect back from Google, signing the user in and redirecting to `/`.
+pub async fn callback(mut request: Request<Google>) -> Result {
+    let unauthorized = |err: Box<dyn std::error::Error>| {
+        Error::from_str(StatusCode::Unauthorized, err.to_string())
+    };
+    let callback = if request.method() == Method::Post {
+        AuthCallback::from_form(&request.body_bytes().await?).map_err(unauthorized)?
+    } else {
+        AuthCallback::from_query(request.url().query().unwrap_or_default())
+    };
+    let cookies = request.header(COOKIE).map(|cookies| cookies.as_str());
+    let (_, [id_token_cookie, state_cookie]) = request
+        .state()
+        .finish_login(callback, cookies)
+        .await
+        .map_err(unauthorized)?;
+
+    let mut response = Response::new(StatusCode::Found);
+    response.insert_header(LOCATION, AFTER_LOGIN_PATH);
+    response.append_header(SET_COOKIE, id_token_cookie);
+    response.append_header(SET_COOKIE, state_cookie);
+    Ok(response)
+}
+
+impl GoogleAuth {
+    /// Creates the middleware, verifying ID tokens with `google`.
+    pub fn new(google: Google) -> GoogleAuth {
+        GoogleAuth { google }
+    }
+}
+
+#[async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for GoogleAuth {
+    async fn handle(&self, mut request: Request<State>, next: Next<'_, State>) -> Result {
+        let authorization = request.header(AUTHORIZATION).map(|value| value.as_str());
+        let cookies = request.header(COOKIE).map(|cookies| cookies.as_str());
+        let userinfo = self
+            .google
+            .authenticate_request(authorization, cookies)
+            .await
+            .map_err(|err| Error::from_str(StatusCode::Unauthorized, err.to_string()))?;
+        request.set_ext::<UserInfo>(userinfo);
+
+        Ok(next.run(request).await)
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use oauth2::basic::BasicTokenType;
+use oauth2::{ExtraTokenFields, StandardTokenResponse, TokenResponse};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "secrecy")]
+use secrecy::SecretString;
+
+use crate::REDACTED;
+use crate::scope::Scopes;
+use crate::secret::{Secret, expose, secret};
+
+/// The token endpoint response, including the OIDC ID token Google returns when the
+/// `openid` scope was requested.
+pub type GoogleTokenResponse = StandardTokenResponse<IdTokenFields, BasicTokenType>;
+
+/// The fields Google adds to the standard OAuth2 token response.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct IdTokenFields {
+    id_token: Option<String>,
+}
+
+impl IdTokenFields {
+    /// The OIDC ID token, only present when the `openid` scope was granted.
+    pub fn id_token(&self) -> Option<&str> {
+        self.id_token.as_deref()
+    }
+}
+
+impl ExtraTokenFields for IdTokenFields {}
+
+/// The tokens returned by Google after a successful authorization code exchange.
+///
+/// Its `Debug` output redacts the access, refresh and ID tokens, so it can be logged. The
+/// tokens are zeroized when the `Token` is dropped.
+///
+/// It can be serialized with `serde` to persist it; `TokenStore` backends should prefer the
+/// versioned format of `Token::to_persisted`. The tokens are written in plain text, so the
+/// serialized form should be encrypted at rest. The relative `expires_in` is not
+/// serialized; the absolute `expires_at` is.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Token {
+    #[serde(serialize_with = "crate::secret::serialize")]
+    access_token: Secret,
+    token_type: String,
+    #[serde(skip)]
+    expires_in: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<DateTime<Utc>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::secret::serialize_option"
+    )]
+    refresh_token: Option<Secret>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::secret::serialize_option"
+    )]
+    id_token: Option<Secret>,
+    #[serde(default)]
+    scopes: Scopes,
+}
+
+impl Token {
+    /// Builds a `Token` from an `oauth2` token response. `requested` is used as the granted
+    /// scope set when Google omits the `scope` field, which per RFC 6749 means the request
+    /// was granted as-is.
+    pub(crate) fn from_response(response: &GoogleTokenResponse, requested: &Scopes) -> Token {
+        let scopes = match response.scopes() {
+            Some(scopes) => scopes.iter().map(|scope| scope.to_string()).collect(),
+            None => requested.clone(),
+        };
+
+        Token {
+            access_token: secret(response.access_token().secret().clone()),
+            token_type: response.token_type().as_ref().to_string(),
+            expires_in: response.expires_in(),
+            expires_at: expires_at(response.expires_in()),
+            refresh_token: response
+                .refresh_token()
+                .map(|token| secret(token.secret().clone())),
+            id_token: response.extra_fields().id_token.clone().map(secret),
+            scopes,
+        }
+    }
+
+    /// Builds the `Token` returned by refreshing `previous`, together with whether Google
+    /// rotated the refresh token. Google usually omits the refresh token from refresh
+    /// responses, in which case the previous one stays valid and is carried over.
+    pub(crate) fn from_refresh_response(
+        response: &GoogleTokenResponse,
+        previous: &Token,
+    ) -> (Token, bool) {
+        let mut token = Token::from_response(response, &previous.scopes);
+        let rotated = match (&token.refresh_token, &previous.refresh_token) {
+            (Some(new), Some(old)) => expose(new) != expose(old),
+            (Some(_), None) => true,
+            (None, _) => {
+                token.refresh_token = previous.refresh_token.clone();
+                false
+            }
+        };
+
+        (token, rotated)
+    }
+
+    /// Builds a `Token` from the fields of a token endpoint response.
+    pub(crate) fn new(
+        access_token: String,
+        token_type: String,
+        expires_in: Option<Duration>,
+        refresh_token: Option<String>,
+        scopes: Scopes,
+    ) -> Token {
+        Token {
+            access_token: secret(access_token),
+            token_type,
+            expires_in,
+            expires_at: expires_at(expires_in),
+            refresh_token: refresh_token.map(secret),
+            id_token: None,
+            scopes,
+        }
+    }
+
+    /// The access token used to authorize requests against Google APIs.
+    pub fn access_token(&self) -> &str {
+        expose(&self.access_token)
+    }
+
+    /// The token type, which is `Bearer` for Google.
+    pub fn token_type(&self) -> &str {
+        &self.token_type
+    }
+
+    /// The lifetime of the access token, as reported at exchange time, or `None` for a
+    /// deserialized token. Use `expires_at` or `remaining` to know how long it is still
+    /// valid.
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_in
+    }
+
+    /// The instant the access token expires at, computed when the token was received.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Returns `true` if the access token has expired. Tokens of unknown lifetime are
+    /// assumed not to expire.
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(Duration::ZERO)
+    }
+
+    /// The time left until the access token expires, zero once it has expired.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| (expires_at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// The refresh token, only present when offline access was requested.
+    pub fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_ref().map(expose)
+    }
+
+    /// The OIDC ID token, only present when the `openid` scope was granted.
+    pub fn id_token(&self) -> Option<&str> {
+        self.id_token.as_ref().map(expose)
+    }
+
+    /// The access token as a `SecretString`, to pass it on without exposing it.
+    #[cfg(feature = "secrecy")]
+    pub fn access_token_secret(&self) -> &SecretString {
+        &self.access_token
+    }
+
+    /// The refresh token as a `SecretString`, e.g. to store it encrypted.
+    #[cfg(feature = "secrecy")]
+    pub fn refresh_token_secret(&self) -> Option<&SecretString> {
+        self.refresh_token.as_ref()
+    }
+
+    /// The OIDC ID token as a `SecretString`.
+    #[cfg(feature = "secrecy")]
+    pub fn id_token_secret(&self) -> Option<&SecretString> {
+        self.id_token.as_ref()
+    }
+
+    /// The scopes the user actually granted on the consent screen.
+    pub fn scopes(&self) -> &Scopes {
+        &self.scopes
+    }
+
+    /// Returns `true` if the access token expires within `leeway` from now. Tokens of
+    /// unknown lifetime are assumed not to expire.
+    pub(crate) fn expires_within(&self, leeway: Duration) -> bool {
+        let leeway = chrono::Duration::from_std(leeway).unwrap_or(chrono::Duration::MAX);
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - Utc::now() <= leeway)
+    }
+
+    /// Returns `true` if the user granted `scope`.
+    ///
+    /// Users can uncheck individual scopes on the consent screen, so this should be checked
+    /// before calling an API that depends on an optional scope.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.contains(scope)
+    }
+}
+
+impl From<&GoogleTokenResponse> for Token {
+    /// Converts a token response obtained through `Google::oauth_client`. Its scopes are
+    /// empty when Google omits the `scope` field.
+    fn from(response: &GoogleTokenResponse) -> Token {
+        Token::from_response(response, &Scopes::default())
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &REDACTED)
+            .field("token_type", &self.token_type)
+            .field("expires_in", &self.expires_in)
+            .field("expires_at", &self.expires_at)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| REDACTED),
+            )
+            .field("id_token", &self.id_token.as_ref().map(|_| REDACTED))
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+impl Zeroize for Token {
+    fn zeroize(&mut self) {
+        self.access_token.zeroize();
+        self.refresh_token.zeroize();
+        self.id_token.zeroize();
+    }
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Token {}
+
+/// The instant a token received now expires at.
+fn expires_at(expires_in: Option<Duration>) -> Option<DateTime<Utc>> {
+    expires_in
+        .and_then(|expires_in| chrono::Duration::from_std(expires_in).ok())
+        .map(|expires_in| Utc::now() + expires_in)
+}
@@ -0,0 +1,241 @@
+//! Cloud Translation API, both the basic v2 and the project-scoped v3 endpoints.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::{Auth, encode_path};
+use crate::{Google, Token};
+
+/// Scope granting access to the Cloud Translation API.
+pub const SCOPE_CLOUD_TRANSLATION: &str = "https://www.googleapis.com/auth/cloud-translation";
+
+const TRANSLATE_V2_API: &str = "https://translation.googleapis.com/language/translate/v2";
+const TRANSLATE_V3_API: &str = "https://translation.googleapis.com/v3";
+
+/// A client for the Cloud Translation API.
+#[derive(Clone, Copy)]
+pub struct Translate<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// The format of the text to translate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextFormat {
+    #[default]
+    Text,
+    /// HTML, whose markup is preserved in the translation.
+    Html,
+}
+
+/// A translated text.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Translation {
+    pub translated_text: String,
+    /// The detected language of the source text, when no source language was given.
+    #[serde(alias = "detectedLanguageCode")]
+    pub detected_source_language: Option<String>,
+}
+
+/// A language detected in a text.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DetectedLanguage {
+    /// The BCP-47 language code, e.g. `en` or `zh-CN`.
+    #[serde(alias = "languageCode")]
+    pub language: String,
+    /// The confidence between 0 and 1.
+    pub confidence: f32,
+}
+
+#[derive(Deserialize)]
+struct V2Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct V2Translations {
+    #[serde(default)]
+    translations: Vec<Translation>,
+}
+
+#[derive(Deserialize)]
+struct V2Detections {
+    #[serde(default)]
+    detections: Vec<Vec<DetectedLanguage>>,
+}
+
+#[derive(Deserialize)]
+struct V3Translations {
+    #[serde(default)]
+    translations: Vec<Translation>,
+}
+
+#[derive(Deserialize)]
+struct V3Detections {
+    #[serde(default)]
+    languages: Vec<DetectedLanguage>,
+}
+
+impl TextFormat {
+    fn v2_name(self) -> &'static str {
+        match self {
+            TextFormat::Text => "text",
+            TextFormat::Html => "html",
+        }
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            TextFormat::Text => "text/plain",
+            TextFormat::Html => "text/html",
+        }
+    }
+}
+
+impl Google {
+    /// Returns a Cloud Translation API client that authorizes its requests with `token`.
+    pub fn translate<'a>(&'a self, token: &'a Token) -> Translate<'a> {
+        Translate {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a Cloud Translation API client that authorizes its requests with an API key.
+    /// API keys are only accepted by the v2 methods.
+    pub fn translate_with_key<'a>(&'a self, api_key: &'a str) -> Translate<'a> {
+        Translate {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl Translate<'_> {
+    /// Translates texts with the basic v2 API, returning one translation per text.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts to translate.
+    /// * `target` - The language to translate into, e.g. `de`.
+    /// * `source` - The language of the texts, `None` to detect it.
+    /// * `format` - Whether the texts are plain text or HTML.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. for an unsupported
+    /// language.
+    pub async fn translate(
+        &self,
+        texts: &[&str],
+        target: &str,
+        source: Option<&str>,
+        format: TextFormat,
+    ) -> Result<Vec<Translation>, Box<dyn Error>> {
+        let mut body = json!({ "q": texts, "target": target, "format": format.v2_name() });
+        if let Some(source) = source {
+            body["source"] = json!(source);
+        }
+
+        let request = self
+            .auth
+            .apply(self.google.http.post(TRANSLATE_V2_API))
+            .json(&body);
+        let response: V2Response<V2Translations> = self.google.send_json(request).await?;
+
+        Ok(response.data.translations)
+    }
+
+    /// Detects the language of texts with the basic v2 API, returning the most likely
+    /// language of each text.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn detect_language(
+        &self,
+        texts: &[&str],
+    ) -> Result<Vec<DetectedLanguage>, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(self.google.http.post(format!("{TRANSLATE_V2_API}/detect")))
+            .json(&json!({ "q": texts }));
+        let response: V2Response<V2Detections> = self.google.send_json(request).await?;
+
+        Ok(response
+            .data
+            .detections
+            .into_iter()
+            .map(|detections| detections.into_iter().next().unwrap_or_default())
+            .collect())
+    }
+
+    /// Translates texts with the advanced v3 API, returning one translation per text.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_id` - The project the request is billed to.
+    /// * `texts` - The texts to translate.
+    /// * `target` - The language to translate into, e.g. `de`.
+    /// * `source` - The language of the texts, `None` to detect it.
+    /// * `format` - Whether the texts are plain text or HTML.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn translate_text(
+        &self,
+        project_id: &str,
+        texts: &[&str],
+        target: &str,
+        source: Option<&str>,
+        format: TextFormat,
+    ) -> Result<Vec<Translation>, Box<dyn Error>> {
+        let mut body = json!({
+            "contents": texts,
+            "targetLanguageCode": target,
+            "mimeType": format.mime_type(),
+        });
+        if let Some(source) = source {
+            body["sourceLanguageCode"] = json!(source);
+        }
+
+        let request = self
+            .auth
+            .apply(self.google.http.post(format!(
+                "{TRANSLATE_V3_API}/projects/{}/locations/global:translateText",
+                encode_path(project_id)
+            )))
+            .json(&body);
+        let response: V3Translations = self.google.send_json(request).await?;
+
+        Ok(response.translations)
+    }
+
+    /// Detects the language of a text with the advanced v3 API, returning the candidate
+    /// languages, the most likely first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn detect_text_language(
+        &self,
+        project_id: &str,
+        text: &str,
+    ) -> Result<Vec<DetectedLanguage>, Box<dyn Error>> {
+        let request = self
+            .auth
+            .apply(self.google.http.post(format!(
+                "{TRANSLATE_V3_API}/projects/{}/locations/global:detectLanguage",
+                encode_path(project_id)
+            )))
+            .json(&json!({ "content": text }));
+        let response: V3Detections = self.google.send_json(request).await?;
+
+        Ok(response.languages)
+    }
+}
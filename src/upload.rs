@@ -0,0 +1,325 @@
+//! The resumable upload protocol shared by Drive, YouTube and other upload endpoints.
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use bytes::Bytes;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, RANGE};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::GoogleError;
+use crate::{Google, REDACTED};
+
+/// Chunk sizes, apart from the last chunk, must be a multiple of this.
+pub const CHUNK_GRANULARITY: usize = 256 * 1024;
+
+/// The chunk size used by `UploadSession::upload`, 8 MiB.
+pub const DEFAULT_CHUNK_SIZE: usize = 32 * CHUNK_GRANULARITY;
+
+/// The `308 Resume Incomplete` status returned while an upload is unfinished.
+const RESUME_INCOMPLETE: u16 = 308;
+
+/// A resumable upload in progress, producing a `T` once all bytes have been received.
+///
+/// The session URI stays valid for about a week. Persist `uri()` together with the total
+/// size to continue an interrupted upload later through `UploadSession::resume`.
+///
+/// Chunks are sent like any other request of the client, so its timeout, retries,
+/// correlation id and `Accept-Language` apply to each of them.
+pub struct UploadSession<T> {
+    google: Google,
+    uri: String,
+    size: Option<u64>,
+    offset: u64,
+    resource: PhantomData<fn() -> T>,
+}
+
+/// The state of an upload after sending a chunk.
+#[derive(Debug)]
+pub enum UploadStatus<T> {
+    /// The server persisted the given number of bytes and expects more.
+    InProgress(u64),
+    /// The upload is finished and the resource was created.
+    Complete(T),
+}
+
+impl<T: DeserializeOwned> UploadSession<T> {
+    /// Initiates a session from a prepared `uploadType=resumable` request and reads the
+    /// session URI from the `Location` header.
+    #[cfg(any(feature = "drive", feature = "storage", feature = "youtube"))]
+    pub(crate) async fn start(
+        google: &Google,
+        request: RequestBuilder,
+        size: Option<u64>,
+    ) -> Result<UploadSession<T>, Box<dyn Error>> {
+        let response = google.send(request).await?;
+        let uri = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or("Upload session response has no Location header")?;
+
+        Ok(UploadSession::resume(google, uri.to_string(), size))
+    }
+
+    /// Recreates a session from a previously persisted session URI. Call `status` to find
+    /// the offset to continue from.
+    ///
+    /// # Arguments
+    ///
+    /// * `google` - The client to send the chunks with.
+    /// * `uri` - The session URI returned by `UploadSession::uri`.
+    /// * `size` - The total size of the upload, if known.
+    pub fn resume(google: &Google, uri: String, size: Option<u64>) -> UploadSession<T> {
+        UploadSession {
+            google: google.clone(),
+            uri,
+            size,
+            offset: 0,
+            resource: PhantomData,
+        }
+    }
+
+    /// The session URI, which authorizes uploads to this session on its own.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// The number of bytes the server has confirmed so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The total size of the upload, if known.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Asks the server how many bytes it has persisted, e.g. after an interrupted transfer,
+    /// and moves `offset` there.
+    pub async fn status(&mut self) -> Result<UploadStatus<T>, Box<dyn Error>> {
+        let total = self.size.map_or("*".to_string(), |size| size.to_string());
+        let request = self
+            .google
+            .http
+            .put(&self.uri)
+            .header(CONTENT_RANGE, format!("bytes */{total}"))
+            .header(CONTENT_LENGTH, 0);
+
+        self.send(request).await
+    }
+
+    /// Uploads the next chunk, starting at `offset`.
+    ///
+    /// All chunks except the last must be a multiple of `CHUNK_GRANULARITY` bytes. The
+    /// server may persist fewer bytes than sent, in which case the returned offset is
+    /// smaller than `offset + chunk.len()` and the remainder has to be sent again.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The bytes to upload, e.g. a `Vec<u8>` or a `Bytes` slice.
+    /// * `last` - Whether this is the final chunk, which fixes the total size when it was
+    ///   not known up front.
+    pub async fn upload_chunk(
+        &mut self,
+        chunk: impl Into<Bytes>,
+        last: bool,
+    ) -> Result<UploadStatus<T>, Box<dyn Error>> {
+        let chunk = chunk.into();
+        let end = self.offset + chunk.len() as u64;
+        if last {
+            self.size.get_or_insert(end);
+        }
+        let total = self.size.map_or("*".to_string(), |size| size.to_string());
+        let range = if chunk.is_empty() {
+            format!("bytes */{total}")
+        } else {
+            format!("bytes {}-{}/{total}", self.offset, end - 1)
+        };
+
+        let request = self
+            .google
+            .http
+            .put(&self.uri)
+            .header(CONTENT_RANGE, range)
+            .header(CONTENT_LENGTH, chunk.len())
+            .body(chunk);
+
+        self.send(request).await
+    }
+
+    /// Uploads everything `reader` yields from the current `offset` on, in chunks of
+    /// `chunk_size` bytes rounded up to a multiple of `CHUNK_GRANULARITY`.
+    ///
+    /// When resuming, `reader` must be positioned at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if reading or a request fails, or if the server
+    /// reports an offset outside the chunk just sent, e.g. because it lost bytes it had
+    /// confirmed before. Call `status` and resume from the returned offset in that case.
+    pub async fn upload<R: AsyncRead + Unpin>(
+        &mut self,
+        reader: &mut R,
+        chunk_size: usize,
+    ) -> Result<T, Box<dyn Error>> {
+        self.upload_with_progress(reader, chunk_size, |_, _| {})
+            .await
+    }
+
+    /// Like `upload`, calling `on_progress` with the number of bytes persisted so far and
+    /// the total size, if known, after every chunk.
+    pub async fn upload_with_progress<R, F>(
+        &mut self,
+        reader: &mut R,
+        chunk_size: usize,
+        mut on_progress: F,
+    ) -> Result<T, Box<dyn Error>>
+    where
+        R: AsyncRead + Unpin,
+        F: FnMut(u64, Option<u64>),
+    {
+        let chunk_size = chunk_size.max(1).div_ceil(CHUNK_GRANULARITY) * CHUNK_GRANULARITY;
+        let mut buffer = Vec::with_capacity(chunk_size);
+        let mut eof = false;
+
+        loop {
+            while !eof && buffer.len() < chunk_size {
+                let read = (&mut *reader)
+                    .take((chunk_size - buffer.len()) as u64)
+                    .read_to_end(&mut buffer)
+                    .await?;
+                eof = read == 0;
+            }
+
+            let start = self.offset;
+            let chunk = Bytes::from(std::mem::take(&mut buffer));
+            let status = self.upload_chunk(chunk.clone(), eof).await?;
+            on_progress(self.offset, self.size);
+
+            match status {
+                UploadStatus::Complete(resource) => return Ok(resource),
+                UploadStatus::InProgress(offset) => {
+                    let persisted = persisted_len(start, offset, chunk.len())?;
+                    buffer.extend_from_slice(&chunk[persisted..]);
+                }
+            }
+        }
+    }
+
+    async fn send(&mut self, request: RequestBuilder) -> Result<UploadStatus<T>, Box<dyn Error>> {
+        let response = self
+            .google
+            .send_accepting(request, |status| {
+                status.is_success() || status.as_u16() == RESUME_INCOMPLETE
+            })
+            .await?;
+
+        self.handle(response).await
+    }
+
+    async fn handle(&mut self, response: Response) -> Result<UploadStatus<T>, Box<dyn Error>> {
+        match response.status() {
+            status if status.as_u16() == RESUME_INCOMPLETE => {
+                self.offset = response
+                    .headers()
+                    .get(RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|range| range.rsplit('-').next())
+                    .and_then(|end| end.parse::<u64>().ok())
+                    .map_or(0, |end| end + 1);
+
+                Ok(UploadStatus::InProgress(self.offset))
+            }
+            StatusCode::OK | StatusCode::CREATED => {
+                self.offset = self.size.unwrap_or(self.offset);
+
+                Ok(UploadStatus::Complete(response.json::<T>().await?))
+            }
+            _ => Err(GoogleError::from_response(response).await.into()),
+        }
+    }
+}
+
+impl<T> fmt::Debug for UploadSession<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadSession")
+            .field("google", &self.google)
+            .field("uri", &REDACTED)
+            .field("size", &self.size)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// The number of bytes of a chunk of `sent` bytes starting at `start` that the server
+/// persisted, given the `offset` it reported afterwards.
+fn persisted_len(start: u64, offset: u64, sent: usize) -> Result<usize, Box<dyn Error>> {
+    offset
+        .checked_sub(start)
+        .and_then(|persisted| usize::try_from(persisted).ok())
+        .filter(|&persisted| persisted <= sent)
+        .ok_or_else(|| {
+            format!("Upload server reported offset {offset} outside the chunk sent at {start}")
+                .into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestServer, response};
+
+    #[tokio::test]
+    async fn sends_chunks_through_the_shared_request_path() {
+        let server = TestServer::start(vec![
+            response("503 Service Unavailable", &[("Retry-After", "0")], ""),
+            response("308 Resume Incomplete", &[("Range", "bytes=0-3")], ""),
+        ])
+        .await;
+        let uri = format!("{}/upload", server.url);
+
+        let google = Google::new_public("appid".to_string(), "http://localhost".to_string())
+            .with_max_retries(1)
+            .with_correlation_id("upload-1");
+        let mut session = UploadSession::<serde_json::Value>::resume(&google, uri, None);
+        let status = session
+            .upload_chunk(b"abcdefgh".to_vec(), false)
+            .await
+            .unwrap();
+
+        assert!(matches!(status, UploadStatus::InProgress(4)));
+        assert_eq!(session.offset(), 4);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            assert!(request.contains("x-request-id: upload-1"));
+            assert!(request.contains("content-range: bytes 0-7/*"));
+        }
+    }
+
+    #[test]
+    fn redacts_the_session_uri() {
+        let google = Google::new_public("appid".to_string(), "http://localhost".to_string());
+        let uri = "https://www.googleapis.com/upload/drive/v3/files?upload_id=secret";
+        let session = UploadSession::<serde_json::Value>::resume(&google, uri.to_string(), None);
+
+        assert!(!format!("{session:?}").contains("upload_id"));
+    }
+
+    #[test]
+    fn accepts_offsets_within_the_chunk() {
+        assert_eq!(persisted_len(100, 100, 50).unwrap(), 0);
+        assert_eq!(persisted_len(100, 120, 50).unwrap(), 20);
+        assert_eq!(persisted_len(100, 150, 50).unwrap(), 50);
+    }
+
+    #[test]
+    fn rejects_offsets_outside_the_chunk() {
+        assert!(persisted_len(100, 99, 50).is_err());
+        assert!(persisted_len(100, 151, 50).is_err());
+    }
+}
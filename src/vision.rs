@@ -0,0 +1,260 @@
+//! Cloud Vision API, for image labelling, text recognition and content moderation.
+
+use std::error::Error;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::api::Auth;
+use crate::{Google, Token};
+
+/// Scope granting access to the Cloud Vision API.
+pub const SCOPE_CLOUD_VISION: &str = "https://www.googleapis.com/auth/cloud-vision";
+
+const VISION_API: &str = "https://vision.googleapis.com/v1";
+
+/// A client for the Cloud Vision API.
+#[derive(Clone, Copy)]
+pub struct Vision<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// The image to annotate.
+#[derive(Debug, Clone)]
+pub enum Image {
+    /// The encoded image, e.g. the content of a JPEG or PNG file.
+    Bytes(Vec<u8>),
+    /// A Cloud Storage URI (`gs://bucket/object`) or a publicly reachable HTTP(S) URL.
+    Uri(String),
+}
+
+/// An annotation request for a single image, built with `AnnotateRequest::new` and the
+/// chained feature setters.
+#[derive(Debug, Clone)]
+pub struct AnnotateRequest {
+    image: Image,
+    features: Vec<Value>,
+    language_hints: Vec<String>,
+}
+
+/// The annotations found on an image. Only the requested features are populated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnnotateResponse {
+    pub label_annotations: Vec<EntityAnnotation>,
+    /// The detected text: the first entry covers the whole image, followed by one entry
+    /// per word.
+    pub text_annotations: Vec<EntityAnnotation>,
+    /// The detected text structured into pages, blocks, paragraphs and words.
+    pub full_text_annotation: Option<TextAnnotation>,
+    pub safe_search_annotation: Option<SafeSearchAnnotation>,
+    /// Set when this image failed while others of the batch succeeded.
+    pub error: Option<Status>,
+}
+
+/// A detected entity, such as a label or a piece of text.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntityAnnotation {
+    /// The Knowledge Graph entity id of labels.
+    pub mid: Option<String>,
+    /// The language of detected text.
+    pub locale: Option<String>,
+    pub description: String,
+    /// The confidence between 0 and 1.
+    pub score: f32,
+    pub topicality: f32,
+    pub bounding_poly: Option<BoundingPoly>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BoundingPoly {
+    pub vertices: Vec<Vertex>,
+}
+
+/// A point in pixel coordinates. Zero coordinates are omitted by the API.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Vertex {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// The result of document text detection.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextAnnotation {
+    pub text: String,
+    /// The detected pages, kept as raw JSON.
+    pub pages: Vec<Value>,
+}
+
+/// How likely an image contains each category of sensitive content.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SafeSearchAnnotation {
+    pub adult: Likelihood,
+    pub spoof: Likelihood,
+    pub medical: Likelihood,
+    pub violence: Likelihood,
+    pub racy: Likelihood,
+}
+
+/// A likelihood bucket, ordered from `Unknown` to `VeryLikely` so thresholds can be
+/// compared, e.g. `annotation.adult >= Likelihood::Likely`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Likelihood {
+    #[default]
+    Unknown,
+    VeryUnlikely,
+    Unlikely,
+    Possible,
+    Likely,
+    VeryLikely,
+}
+
+/// The error of a single image in a batch.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Status {
+    pub code: i32,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    #[serde(default)]
+    responses: Vec<AnnotateResponse>,
+}
+
+impl AnnotateRequest {
+    /// Creates a request for `image` without any features.
+    pub fn new(image: Image) -> AnnotateRequest {
+        AnnotateRequest {
+            image,
+            features: Vec::new(),
+            language_hints: Vec::new(),
+        }
+    }
+
+    /// Requests up to `max_results` labels describing the image.
+    pub fn labels(self, max_results: u32) -> AnnotateRequest {
+        self.feature("LABEL_DETECTION", Some(max_results))
+    }
+
+    /// Requests OCR optimized for sparse text, such as signs or photos.
+    pub fn text(self) -> AnnotateRequest {
+        self.feature("TEXT_DETECTION", None)
+    }
+
+    /// Requests OCR optimized for dense text, such as scanned documents.
+    pub fn document_text(self) -> AnnotateRequest {
+        self.feature("DOCUMENT_TEXT_DETECTION", None)
+    }
+
+    /// Requests the likelihood of adult, violent or otherwise sensitive content.
+    pub fn safe_search(self) -> AnnotateRequest {
+        self.feature("SAFE_SEARCH_DETECTION", None)
+    }
+
+    /// Sets the languages expected in the image as BCP-47 codes, which improves OCR of
+    /// languages that are hard to detect automatically.
+    pub fn language_hints<S: Into<String>>(
+        mut self,
+        languages: impl IntoIterator<Item = S>,
+    ) -> AnnotateRequest {
+        self.language_hints = languages.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn feature(mut self, feature: &str, max_results: Option<u32>) -> AnnotateRequest {
+        let mut feature = json!({ "type": feature });
+        if let Some(max_results) = max_results {
+            feature["maxResults"] = json!(max_results);
+        }
+        self.features.push(feature);
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let image = match &self.image {
+            Image::Bytes(bytes) => json!({ "content": STANDARD.encode(bytes) }),
+            Image::Uri(uri) => json!({ "source": { "imageUri": uri } }),
+        };
+        let mut request = json!({ "image": image, "features": self.features });
+        if !self.language_hints.is_empty() {
+            request["imageContext"] = json!({ "languageHints": self.language_hints });
+        }
+        request
+    }
+}
+
+impl Google {
+    /// Returns a Cloud Vision API client that authorizes its requests with `token`.
+    pub fn vision<'a>(&'a self, token: &'a Token) -> Vision<'a> {
+        Vision {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a Cloud Vision API client that authorizes its requests with an API key.
+    pub fn vision_with_key<'a>(&'a self, api_key: &'a str) -> Vision<'a> {
+        Vision {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl Vision<'_> {
+    /// Annotates a batch of up to 16 images, returning one response per request.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails. Failures of individual images
+    /// are reported in `AnnotateResponse::error` instead.
+    pub async fn annotate(
+        &self,
+        requests: &[AnnotateRequest],
+    ) -> Result<Vec<AnnotateResponse>, Box<dyn Error>> {
+        let requests: Vec<Value> = requests.iter().map(AnnotateRequest::to_json).collect();
+        let request = self
+            .auth
+            .apply(
+                self.google
+                    .http
+                    .post(format!("{VISION_API}/images:annotate")),
+            )
+            .json(&json!({ "requests": requests }));
+        let response: BatchResponse = self.google.send_json(request).await?;
+
+        Ok(response.responses)
+    }
+
+    /// Annotates a single image.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails or the image cannot be
+    /// processed, e.g. because its URL is not reachable.
+    pub async fn annotate_image(
+        &self,
+        request: &AnnotateRequest,
+    ) -> Result<AnnotateResponse, Box<dyn Error>> {
+        let mut responses = self.annotate(std::slice::from_ref(request)).await?;
+        let response = responses.pop().ok_or("Vision API returned no response")?;
+
+        match &response.error {
+            Some(status) => {
+                Err(format!("Vision API error {}: {}", status.code, status.message).into())
+            }
+            None => Ok(response),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Google Wallet API, for issuing passes such as loyalty cards and event tickets.
+
+use std::error::Error;
+
+use reqwest::Method;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::api::encode_path;
+use crate::{Google, Token};
+
+const WALLET_API: &str = "https://walletobjects.googleapis.com/walletobjects/v1";
+
+/// Scope granting access to manage the passes of an issuer account.
+pub const SCOPE_WALLET_OBJECT_ISSUER: &str = "https://www.googleapis.com/auth/wallet_object.issuer";
+
+/// A client for the Google Wallet API, usually authorized with a service account added to
+/// the issuer account.
+///
+/// Pass classes hold what is shared by all passes of a program or event, while pass objects
+/// are the individual passes held by users. Both have large, type-specific schemas, so they
+/// are accepted and returned as any serde type, e.g. `serde_json::Value`.
+#[derive(Clone, Copy)]
+pub struct Wallet<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// The type of a pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassType {
+    Loyalty,
+    EventTicket,
+    Offer,
+    GiftCard,
+    Flight,
+    Transit,
+    Generic,
+}
+
+impl PassType {
+    fn class_resource(self) -> &'static str {
+        match self {
+            PassType::Loyalty => "loyaltyClass",
+            PassType::EventTicket => "eventTicketClass",
+            PassType::Offer => "offerClass",
+            PassType::GiftCard => "giftCardClass",
+            PassType::Flight => "flightClass",
+            PassType::Transit => "transitClass",
+            PassType::Generic => "genericClass",
+        }
+    }
+
+    fn object_resource(self) -> &'static str {
+        match self {
+            PassType::Loyalty => "loyaltyObject",
+            PassType::EventTicket => "eventTicketObject",
+            PassType::Offer => "offerObject",
+            PassType::GiftCard => "giftCardObject",
+            PassType::Flight => "flightObject",
+            PassType::Transit => "transitObject",
+            PassType::Generic => "genericObject",
+        }
+    }
+}
+
+/// Builds the id of a pass class or object, `{issuer_id}.{suffix}`.
+pub fn resource_id(issuer_id: &str, suffix: &str) -> String {
+    format!("{issuer_id}.{suffix}")
+}
+
+impl Google {
+    /// Returns a Google Wallet API client that authorizes its requests with `token`.
+    pub fn wallet<'a>(&'a self, token: &'a Token) -> Wallet<'a> {
+        Wallet {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl Wallet<'_> {
+    /// Creates a pass class. Its `id` must be set, see `resource_id`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. with a conflict when a
+    /// class with the same id already exists.
+    pub async fn insert_class<T: Serialize + DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        class: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        self.write(Method::POST, pass_type.class_resource(), None, class)
+            .await
+    }
+
+    /// Gets a pass class by its id.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_class<T: DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        id: &str,
+    ) -> Result<T, Box<dyn Error>> {
+        self.get(pass_type.class_resource(), id).await
+    }
+
+    /// Replaces a pass class; fields missing from `class` are cleared.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn update_class<T: Serialize + DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        id: &str,
+        class: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        self.write(Method::PUT, pass_type.class_resource(), Some(id), class)
+            .await
+    }
+
+    /// Updates the fields of a pass class present in `class`, keeping the others.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn patch_class<T: Serialize + DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        id: &str,
+        class: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        self.write(Method::PATCH, pass_type.class_resource(), Some(id), class)
+            .await
+    }
+
+    /// Creates a pass object. Its `id` must be set, and its `classId` must refer to an
+    /// existing class of the same type.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. with a conflict when an
+    /// object with the same id already exists.
+    pub async fn insert_object<T: Serialize + DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        object: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        self.write(Method::POST, pass_type.object_resource(), None, object)
+            .await
+    }
+
+    /// Gets a pass object by its id.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn get_object<T: DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        id: &str,
+    ) -> Result<T, Box<dyn Error>> {
+        self.get(pass_type.object_resource(), id).await
+    }
+
+    /// Replaces a pass object; fields missing from `object` are cleared.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn update_object<T: Serialize + DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        id: &str,
+        object: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        self.write(Method::PUT, pass_type.object_resource(), Some(id), object)
+            .await
+    }
+
+    /// Updates the fields of a pass object present in `object`, keeping the others, e.g.
+    /// to change a loyalty balance or expire a ticket by setting `state`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails.
+    pub async fn patch_object<T: Serialize + DeserializeOwned>(
+        &self,
+        pass_type: PassType,
+        id: &str,
+        object: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        self.write(Method::PATCH, pass_type.object_resource(), Some(id), object)
+            .await
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        id: &str,
+    ) -> Result<T, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{WALLET_API}/{resource}/{}", encode_path(id)))
+            .bearer_auth(self.token.access_token());
+
+        self.google.send_json(request).await
+    }
+
+    async fn write<T: Serialize + DeserializeOwned>(
+        &self,
+        method: Method,
+        resource: &str,
+        id: Option<&str>,
+        body: &T,
+    ) -> Result<T, Box<dyn Error>> {
+        let url = match id {
+            Some(id) => format!("{WALLET_API}/{resource}/{}", encode_path(id)),
+            None => format!("{WALLET_API}/{resource}"),
+        };
+        let request = self
+            .google
+            .http
+            .request(method, url)
+            .bearer_auth(self.token.access_token())
+            .json(body);
+
+        self.google.send_json(request).await
+    }
+}
@@ -0,0 +1,156 @@
+//! Browser helpers for single-page apps compiled to WebAssembly, e.g. with Leptos or Yew,
+//! signing the user in with the authorization code flow and PKCE.
+//!
+//! `start_login` keeps the state and PKCE verifier in `sessionStorage` while the user is on
+//! Google's consent screen. Back on the redirect URI, the code is exchanged either by the
+//! app itself, for a public client created with `Google::new_public`, or by the backend,
+//! which receives a `PkceCallback` and calls `Google::get_token_with_verifier`:
+//!
+//! ```no_run
+//! use async_google_auth::{Google, Token, wasm};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let google = Google::new_public(
+//!     "client-id".to_string(),
+//!     "https://app.example.com/callback".to_string(),
+//! );
+//!
+//! // When the user clicks "Sign in with Google":
+//! wasm::start_login(&google)?;
+//!
+//! // On the callback page, either exchange the code in the browser:
+//! let token = wasm::complete_login(&google).await?;
+//! // or have the backend exchange it and return its own session:
+//! let session: serde_json::Value = wasm::complete_login_with_backend("/api/login").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Timeouts and retries, set with `Google::with_timeout` and `Google::with_max_retries`,
+//! rely on Tokio timers and are not supported in the browser.
+
+use std::error::Error;
+
+use oauth2::url::Url;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use web_sys::wasm_bindgen::JsValue;
+use web_sys::{Storage, Window};
+
+use crate::{AuthCallback, Google, PkceCallback, Token};
+
+/// The `sessionStorage` key holding the state of a pending authorization.
+const STATE_KEY: &str = "google_oauth_state";
+/// The `sessionStorage` key holding the PKCE verifier of a pending authorization.
+const VERIFIER_KEY: &str = "google_pkce_verifier";
+
+/// Redirects the browser to Google's consent screen with a PKCE challenge, storing the
+/// state and verifier in `sessionStorage`.
+///
+/// # Errors
+///
+/// This function returns an error if the authorization URL cannot be built or
+/// `sessionStorage` is unavailable, e.g. outside a browser window.
+pub fn start_login(google: &Google) -> Result<(), Box<dyn Error>> {
+    let authorization = google.authorization().pkce().build()?;
+    let verifier = authorization
+        .pkce_verifier
+        .ok_or("Authorization has no PKCE verifier")?;
+
+    let window = window()?;
+    let storage = session_storage(&window)?;
+    storage
+        .set_item(STATE_KEY, &authorization.state)
+        .map_err(js_error)?;
+    storage
+        .set_item(VERIFIER_KEY, &verifier)
+        .map_err(js_error)?;
+
+    window
+        .location()
+        .set_href(&authorization.url)
+        .map_err(js_error)
+}
+
+/// Reads the callback from the query of the current page, checks its state and returns
+/// the authorization code with its PKCE verifier.
+///
+/// The stored state and verifier are removed, so a callback is only accepted once.
+///
+/// # Errors
+///
+/// This function returns an error if no sign-in was started in this tab, if the state
+/// does not match, or if authorization failed, see `AuthCallback::verify`.
+pub fn take_callback() -> Result<PkceCallback, Box<dyn Error>> {
+    let window = window()?;
+    let storage = session_storage(&window)?;
+    let state = storage.get_item(STATE_KEY).map_err(js_error)?;
+    let verifier = storage.get_item(VERIFIER_KEY).map_err(js_error)?;
+    storage.remove_item(STATE_KEY).map_err(js_error)?;
+    storage.remove_item(VERIFIER_KEY).map_err(js_error)?;
+
+    let query = window.location().search().map_err(js_error)?;
+    let code = AuthCallback::from_query(&query)
+        .verify(state.as_deref().ok_or("Missing sign-in state")?)?;
+
+    Ok(PkceCallback {
+        code,
+        verifier: verifier.ok_or("Missing PKCE verifier")?,
+    })
+}
+
+/// Completes the sign-in in the browser, exchanging the code of the current page for a
+/// `Token` with the client's id only.
+///
+/// # Errors
+///
+/// This function returns an error as described for `take_callback`, or if the exchange
+/// fails, e.g. because Google requires a client secret for the client.
+pub async fn complete_login(google: &Google) -> Result<Token, Box<dyn Error>> {
+    let PkceCallback { code, verifier } = take_callback()?;
+
+    google.get_token_with_verifier(code, verifier).await
+}
+
+/// Completes the sign-in through the backend: posts the code of the current page and its
+/// verifier as a JSON `PkceCallback` to `url` and returns the backend's JSON response.
+///
+/// # Arguments
+///
+/// * `url` - The backend endpoint, resolved against the current page, e.g. `/api/login`.
+///
+/// # Errors
+///
+/// This function returns an error as described for `take_callback`, or if the backend
+/// responds with an error status or a body that does not deserialize into `T`.
+pub async fn complete_login_with_backend<T: DeserializeOwned>(
+    url: &str,
+) -> Result<T, Box<dyn Error>> {
+    let callback = take_callback()?;
+    let page = window()?.location().href().map_err(js_error)?;
+    let url = Url::parse(&page)?.join(url)?;
+
+    let response = Client::new()
+        .post(url)
+        .json(&callback)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(response.json::<T>().await?)
+}
+
+fn window() -> Result<Window, Box<dyn Error>> {
+    web_sys::window().ok_or_else(|| "No browser window".into())
+}
+
+fn session_storage(window: &Window) -> Result<Storage, Box<dyn Error>> {
+    window
+        .session_storage()
+        .map_err(js_error)?
+        .ok_or_else(|| "sessionStorage is unavailable".into())
+}
+
+fn js_error(err: JsValue) -> Box<dyn Error> {
+    format!("{err:?}").into()
+}
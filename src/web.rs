@@ -0,0 +1,106 @@
+//! The sign-in flow shared by the web framework integrations: the state protecting the
+//! callback, and for cookie-based integrations, the ID token cookie identifying the user
+//! afterwards.
+
+use std::error::Error;
+
+#[cfg(any(feature = "poem", feature = "tide"))]
+use crate::ResponseMode;
+use crate::{AuthCallback, Google, Token, UserInfo};
+
+/// The cookie holding the state of a pending authorization.
+#[cfg(any(feature = "poem", feature = "tide"))]
+const STATE_COOKIE: &str = "google_oauth_state";
+/// The cookie holding the ID token of the signed-in user.
+#[cfg(any(feature = "poem", feature = "tide"))]
+const ID_TOKEN_COOKIE: &str = "google_id_token";
+/// How long a pending authorization may take, in seconds.
+#[cfg(any(feature = "poem", feature = "tide"))]
+const STATE_MAX_AGE: u64 = 600;
+
+/// The path the integrations redirect to once the user signed in.
+pub(crate) const AFTER_LOGIN_PATH: &str = "/";
+
+impl Google {
+    /// Checks the callback's state against the state stored when the flow started and
+    /// exchanges the code.
+    pub(crate) async fn complete_login(
+        &self,
+        callback: AuthCallback,
+        state: Option<&str>,
+    ) -> Result<(Token, UserInfo), Box<dyn Error>> {
+        let code = callback.verify(state.ok_or("Missing sign-in state")?)?;
+
+        self.authenticate(code).await
+    }
+
+    /// Starts the sign-in flow, returning the authorization URL to redirect to and the
+    /// `Set-Cookie` value carrying its state.
+    #[cfg(any(feature = "poem", feature = "tide"))]
+    pub(crate) fn start_login(&self) -> Result<(String, String), Box<dyn Error>> {
+        let authorization = self.authorization().build()?;
+        let cookie = self.cookie(STATE_COOKIE, &authorization.state, STATE_MAX_AGE);
+
+        Ok((authorization.url, cookie))
+    }
+
+    /// Completes the sign-in flow: checks the callback's state against the state cookie
+    /// found in `cookies`, the request's `Cookie` header, and exchanges the code. Returns
+    /// the user and the `Set-Cookie` values storing their ID token and clearing the state.
+    #[cfg(any(feature = "poem", feature = "tide"))]
+    pub(crate) async fn finish_login(
+        &self,
+        callback: AuthCallback,
+        cookies: Option<&str>,
+    ) -> Result<(UserInfo, [String; 2]), Box<dyn Error>> {
+        let state = cookies.and_then(|cookies| cookie(cookies, STATE_COOKIE));
+        let (token, userinfo) = self.complete_login(callback, state).await?;
+
+        let id_token = token.id_token().ok_or("Token has no ID token")?;
+        let max_age = token.remaining().map_or(0, |remaining| remaining.as_secs());
+        let cookies = [
+            self.cookie(ID_TOKEN_COOKIE, id_token, max_age),
+            self.cookie(STATE_COOKIE, "", 0),
+        ];
+
+        Ok((userinfo, cookies))
+    }
+
+    /// Verifies the ID token of a request, taken from its `Authorization: Bearer` header
+    /// or the ID token cookie set by `finish_login`.
+    #[cfg(any(feature = "poem", feature = "tide"))]
+    pub(crate) async fn authenticate_request(
+        &self,
+        authorization: Option<&str>,
+        cookies: Option<&str>,
+    ) -> Result<UserInfo, Box<dyn Error>> {
+        let id_token = authorization
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .or_else(|| cookies.and_then(|cookies| cookie(cookies, ID_TOKEN_COOKIE)))
+            .ok_or("Request carries no ID token")?;
+
+        self.verify_id_token(id_token).await
+    }
+
+    /// Builds a `Set-Cookie` value. Callbacks delivered with `ResponseMode::FormPost` are
+    /// cross-site POST requests, which only carry `SameSite=None` cookies.
+    #[cfg(any(feature = "poem", feature = "tide"))]
+    fn cookie(&self, name: &str, value: &str, max_age: u64) -> String {
+        let same_site = match self.response_mode {
+            ResponseMode::Query => "Lax",
+            ResponseMode::FormPost => "None",
+        };
+
+        format!("{name}={value}; Path=/; Max-Age={max_age}; HttpOnly; Secure; SameSite={same_site}")
+    }
+}
+
+/// Returns the value of the cookie `name` from a `Cookie` header.
+#[cfg(any(feature = "poem", feature = "tide"))]
+fn cookie<'a>(cookies: &'a str, name: &str) -> Option<&'a str> {
+    cookies
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value)
+}
@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{Google, UserInfo};
+
+/// The error returned boxed when a user does not belong to the expected Google Workspace
+/// domain or customer, by the sign-in methods of a client restricted with
+/// `Google::with_hosted_domains`, and by `Directory::verify_customer`.
+///
+/// It can be inspected by downcasting with `err.downcast_ref::<WorkspaceMismatch>()`, e.g.
+/// to tell the user to sign in with their work account.
+#[derive(Debug, Clone)]
+pub struct WorkspaceMismatch {
+    /// The claim that did not match: `hd` or `customer_id`.
+    pub claim: &'static str,
+    /// The value the user's claim was expected to have, or one of them.
+    pub expected: String,
+    /// The user's value, `None` for a consumer account outside any Workspace or when it is
+    /// unknown.
+    pub actual: Option<String>,
+}
+
+impl UserInfo {
+    /// The primary domain of the user's Google Workspace organization, from the `hd` claim,
+    /// or `None` for a consumer account such as a `gmail.com` address.
+    ///
+    /// Unlike the domain of `email`, which any account can have, `hd` is only set for
+    /// accounts managed by the Workspace organization owning the domain.
+    pub fn hosted_domain(&self) -> Option<&str> {
+        self.extra.get("hd").and_then(|hd| hd.as_str())
+    }
+}
+
+impl Google {
+    /// Checks the `hd` claim of `userinfo` against the domains set with
+    /// `with_hosted_domains`.
+    pub(crate) fn check_hosted_domain(&self, userinfo: &UserInfo) -> Result<(), Box<dyn Error>> {
+        self.check_hd_claim(userinfo.hosted_domain())
+    }
+
+    /// Checks the `hd` claim of a raw userinfo response like `check_hosted_domain`.
+    pub(crate) fn check_hosted_domain_raw(&self, userinfo: &Value) -> Result<(), Box<dyn Error>> {
+        self.check_hd_claim(userinfo.get("hd").and_then(|hd| hd.as_str()))
+    }
+
+    fn check_hd_claim(&self, hosted_domain: Option<&str>) -> Result<(), Box<dyn Error>> {
+        let Some(domains) = &self.hosted_domains else {
+            return Ok(());
+        };
+        if hosted_domain.is_some_and(|hd| domains.iter().any(|domain| domain == hd)) {
+            return Ok(());
+        }
+
+        Err(WorkspaceMismatch {
+            claim: "hd",
+            expected: domains.join(", "),
+            actual: hosted_domain.map(str::to_string),
+        }
+        .into())
+    }
+
+    /// The `hd` parameter hinting the domain on the consent screen: the domain itself when
+    /// a single one is allowed, `*` for any Workspace account otherwise.
+    pub(crate) fn hosted_domain_hint(&self) -> Option<&str> {
+        match self.hosted_domains.as_deref()? {
+            [domain] => Some(domain),
+            _ => Some("*"),
+        }
+    }
+}
+
+impl fmt::Display for WorkspaceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "User does not belong to the Workspace with {} {}",
+            self.claim, self.expected
+        )?;
+        if let Some(actual) = &self.actual {
+            write!(f, " but to {actual}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for WorkspaceMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn google(domains: &[&str]) -> Google {
+        Google::new(
+            "client-id".to_string(),
+            "secret".to_string(),
+            "https://app.example.com/callback".to_string(),
+        )
+        .with_hosted_domains(domains)
+    }
+
+    fn userinfo(hd: Option<&str>) -> Value {
+        let mut claims = json!({
+            "sub": "1234",
+            "name": "Ada",
+            "picture": "",
+            "email": "ada@example.com",
+            "email_verified": true,
+        });
+        if let Some(hd) = hd {
+            claims["hd"] = json!(hd);
+        }
+        claims
+    }
+
+    fn check(google: &Google, hd: Option<&str>) -> Result<(), Box<dyn Error>> {
+        let raw = userinfo(hd);
+        let userinfo: UserInfo = serde_json::from_value(raw.clone()).unwrap();
+        let typed = google.check_hosted_domain(&userinfo);
+        assert_eq!(typed.is_ok(), google.check_hosted_domain_raw(&raw).is_ok());
+        typed
+    }
+
+    #[test]
+    fn accepts_allowed_domains() {
+        let google = google(&["example.com", "example.org"]);
+        assert!(check(&google, Some("example.org")).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_domains_and_consumer_accounts() {
+        let google = google(&["example.com"]);
+
+        let err = check(&google, Some("evil.com")).unwrap_err();
+        let mismatch = err.downcast_ref::<WorkspaceMismatch>().unwrap();
+        assert_eq!(mismatch.actual.as_deref(), Some("evil.com"));
+
+        let err = check(&google, None).unwrap_err();
+        assert!(
+            err.downcast_ref::<WorkspaceMismatch>()
+                .unwrap()
+                .actual
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn empty_domains_lift_the_restriction() {
+        let google = google(&[]);
+        assert!(check(&google, None).is_ok());
+        assert_eq!(google.hosted_domain_hint(), None);
+    }
+
+    #[test]
+    fn hints_the_domain() {
+        assert_eq!(
+            google(&["example.com"]).hosted_domain_hint(),
+            Some("example.com")
+        );
+        assert_eq!(google(&["a.com", "b.com"]).hosted_domain_hint(), Some("*"));
+
+        let url = google(&["example.com"]).get_redirect_url();
+        assert!(url.contains("hd=example.com"), "{url}");
+    }
+}
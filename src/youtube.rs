@@ -0,0 +1,399 @@
+//! YouTube Data API.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::{Auth, Page, paginate};
+use crate::de::option_number_from_string;
+use crate::upload::UploadSession;
+use crate::{Google, Token};
+
+const YOUTUBE_API: &str = "https://www.googleapis.com/youtube/v3";
+const YOUTUBE_UPLOAD_API: &str = "https://www.googleapis.com/upload/youtube/v3";
+
+/// Scope granting read-only access to the user's YouTube account.
+pub const SCOPE_READONLY: &str = "https://www.googleapis.com/auth/youtube.readonly";
+
+/// Scope required to upload videos.
+pub const SCOPE_UPLOAD: &str = "https://www.googleapis.com/auth/youtube.upload";
+
+/// A client for the YouTube Data API, authorized with a user's access token or, for public
+/// data only, an API key.
+#[derive(Clone, Copy)]
+pub struct YouTube<'a> {
+    google: &'a Google,
+    auth: Auth<'a>,
+}
+
+/// A page of resources returned by a YouTube `list` method.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListResponse<T> {
+    pub items: Vec<T>,
+    pub next_page_token: Option<String>,
+    pub prev_page_token: Option<String>,
+    pub page_info: PageInfo,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PageInfo {
+    pub total_results: u32,
+    pub results_per_page: u32,
+}
+
+/// A thumbnail image, keyed by size (`default`, `medium`, `high`, ...) in `Thumbnails`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Thumbnail {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+pub type Thumbnails = HashMap<String, Thumbnail>;
+
+/// A YouTube channel. Only the requested parts are populated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Channel {
+    pub id: String,
+    pub snippet: Option<ChannelSnippet>,
+    pub statistics: Option<ChannelStatistics>,
+    pub content_details: Option<ChannelContentDetails>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelSnippet {
+    pub title: String,
+    pub description: String,
+    /// The channel handle, e.g. `@GoogleDevelopers`.
+    pub custom_url: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub thumbnails: Thumbnails,
+    pub country: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelStatistics {
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub view_count: Option<u64>,
+    /// Rounded down to three significant figures, and `None` when hidden.
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub subscriber_count: Option<u64>,
+    pub hidden_subscriber_count: bool,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub video_count: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelContentDetails {
+    pub related_playlists: RelatedPlaylists,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RelatedPlaylists {
+    /// The playlist holding all uploaded videos, for use with `list_playlist_items`.
+    pub uploads: Option<String>,
+    pub likes: Option<String>,
+}
+
+/// An entry of a playlist.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaylistItem {
+    pub id: String,
+    pub snippet: Option<PlaylistItemSnippet>,
+    pub content_details: Option<PlaylistItemContentDetails>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaylistItemSnippet {
+    pub title: String,
+    pub description: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub channel_id: String,
+    pub playlist_id: String,
+    pub position: u32,
+    pub thumbnails: Thumbnails,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaylistItemContentDetails {
+    pub video_id: String,
+    pub video_published_at: Option<DateTime<Utc>>,
+}
+
+/// A video. Only the requested parts are populated.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Video {
+    pub id: String,
+    pub snippet: Option<VideoSnippet>,
+    pub statistics: Option<VideoStatistics>,
+    pub content_details: Option<VideoContentDetails>,
+    pub status: Option<VideoStatus>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoStatus {
+    /// `uploaded`, `processed`, `failed`, `rejected` or `deleted`.
+    pub upload_status: String,
+    pub privacy_status: String,
+    pub made_for_kids: Option<bool>,
+}
+
+/// The visibility of a video.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PrivacyStatus {
+    Public,
+    Unlisted,
+    #[default]
+    Private,
+}
+
+/// The metadata of a video to be uploaded with `YouTube::create_upload_session`.
+#[derive(Debug, Clone, Default)]
+pub struct NewVideo {
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// The video category, e.g. `22` for People & Blogs.
+    pub category_id: Option<String>,
+    pub privacy_status: PrivacyStatus,
+    /// Whether the video is made for kids, which YouTube requires to be declared.
+    pub made_for_kids: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoSnippet {
+    pub title: String,
+    pub description: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub channel_id: String,
+    pub channel_title: String,
+    pub tags: Vec<String>,
+    pub category_id: Option<String>,
+    pub thumbnails: Thumbnails,
+    /// `none`, `upcoming` or `live`.
+    pub live_broadcast_content: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoStatistics {
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub view_count: Option<u64>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub like_count: Option<u64>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub comment_count: Option<u64>,
+    #[serde(deserialize_with = "option_number_from_string")]
+    pub favorite_count: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoContentDetails {
+    /// The length as an ISO 8601 duration, e.g. `PT4M13S`.
+    pub duration: String,
+    /// `hd` or `sd`.
+    pub definition: String,
+    pub caption: Option<String>,
+}
+
+/// Which channels `YouTube::list_channels` returns.
+#[derive(Debug, Clone, Copy)]
+pub enum ChannelSelector<'a> {
+    /// The authenticated user's channel. Requires an access token.
+    Mine,
+    /// Channels by id.
+    Ids(&'a [&'a str]),
+    /// A channel by handle, e.g. `@GoogleDevelopers`.
+    Handle(&'a str),
+}
+
+impl<T> Default for ListResponse<T> {
+    fn default() -> Self {
+        ListResponse {
+            items: Vec::new(),
+            next_page_token: None,
+            prev_page_token: None,
+            page_info: PageInfo::default(),
+        }
+    }
+}
+
+impl<T> Page for ListResponse<T> {
+    fn next_page_token(&self) -> Option<&str> {
+        self.next_page_token.as_deref()
+    }
+}
+
+impl Google {
+    /// Returns a YouTube Data API client that authorizes its requests with `token`.
+    pub fn youtube<'a>(&'a self, token: &'a Token) -> YouTube<'a> {
+        YouTube {
+            google: self,
+            auth: Auth::Bearer(token.access_token()),
+        }
+    }
+
+    /// Returns a YouTube Data API client that authorizes its requests with an API key,
+    /// limited to public data.
+    pub fn youtube_with_key<'a>(&'a self, api_key: &'a str) -> YouTube<'a> {
+        YouTube {
+            google: self,
+            auth: Auth::Key(api_key),
+        }
+    }
+}
+
+impl<'a> YouTube<'a> {
+    /// Lists channels.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - Which channels to return.
+    /// * `parts` - The resource parts to return, e.g. `["snippet", "statistics",
+    ///   "contentDetails"]`. Each part costs quota.
+    pub async fn list_channels(
+        &self,
+        selector: ChannelSelector<'_>,
+        parts: &[&str],
+    ) -> Result<ListResponse<Channel>, Box<dyn Error>> {
+        let filter = match selector {
+            ChannelSelector::Mine => ("mine", "true".to_string()),
+            ChannelSelector::Ids(ids) => ("id", ids.join(",")),
+            ChannelSelector::Handle(handle) => ("forHandle", handle.to_string()),
+        };
+
+        self.list("channels", parts, &[filter], None).await
+    }
+
+    /// Lists a single page of the items of a playlist, e.g. a channel's uploads playlist.
+    pub async fn list_playlist_items(
+        &self,
+        playlist_id: &str,
+        parts: &[&str],
+        page_token: Option<&str>,
+    ) -> Result<ListResponse<PlaylistItem>, Box<dyn Error>> {
+        let params = [
+            ("playlistId", playlist_id.to_string()),
+            ("maxResults", "50".to_string()),
+        ];
+
+        self.list("playlistItems", parts, &params, page_token).await
+    }
+
+    /// Streams every page of the items of a playlist.
+    pub fn playlist_items<'s>(
+        self,
+        playlist_id: &'s str,
+        parts: &'s [&'s str],
+    ) -> impl Stream<Item = Result<ListResponse<PlaylistItem>, Box<dyn Error>>> + 's
+    where
+        'a: 's,
+    {
+        paginate(move |page_token| async move {
+            self.list_playlist_items(playlist_id, parts, page_token.as_deref())
+                .await
+        })
+    }
+
+    /// Fetches videos by id.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - Up to 50 video ids.
+    /// * `parts` - The resource parts to return, e.g. `["snippet", "statistics"]`.
+    pub async fn list_videos(
+        &self,
+        ids: &[&str],
+        parts: &[&str],
+    ) -> Result<ListResponse<Video>, Box<dyn Error>> {
+        self.list("videos", parts, &[("id", ids.join(","))], None)
+            .await
+    }
+
+    /// Starts a resumable upload session for a new video.
+    ///
+    /// Upload the file through the returned session, e.g. with
+    /// `UploadSession::upload_with_progress` to report progress. Requires an access token
+    /// with `SCOPE_UPLOAD`; videos uploaded by unverified API projects are locked as
+    /// private.
+    ///
+    /// # Arguments
+    ///
+    /// * `video` - The title, description and privacy of the video.
+    /// * `content_type` - The MIME type of the file, e.g. `video/mp4`.
+    /// * `size` - The file size in bytes, if known up front.
+    pub async fn create_upload_session(
+        &self,
+        video: &NewVideo,
+        content_type: &str,
+        size: Option<u64>,
+    ) -> Result<UploadSession<Video>, Box<dyn Error>> {
+        let mut snippet = json!({
+            "title": video.title,
+            "description": video.description,
+            "tags": video.tags,
+        });
+        if let Some(category_id) = &video.category_id {
+            snippet["categoryId"] = json!(category_id);
+        }
+        let body = json!({
+            "snippet": snippet,
+            "status": {
+                "privacyStatus": video.privacy_status,
+                "selfDeclaredMadeForKids": video.made_for_kids,
+            },
+        });
+
+        let mut request = self
+            .google
+            .http
+            .post(format!("{YOUTUBE_UPLOAD_API}/videos"))
+            .query(&[("uploadType", "resumable"), ("part", "snippet,status")])
+            .header("X-Upload-Content-Type", content_type)
+            .json(&body);
+        if let Some(size) = size {
+            request = request.header("X-Upload-Content-Length", size);
+        }
+
+        UploadSession::start(self.google, self.auth.apply(request), size).await
+    }
+
+    async fn list<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        parts: &[&str],
+        params: &[(&str, String)],
+        page_token: Option<&str>,
+    ) -> Result<ListResponse<T>, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{YOUTUBE_API}/{resource}"))
+            .query(&[("part", parts.join(","))])
+            .query(params)
+            .query(&[("pageToken", page_token)]);
+
+        self.google.send_json(self.auth.apply(request)).await
+    }
+}
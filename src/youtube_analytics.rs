@@ -0,0 +1,245 @@
+//! YouTube Analytics API.
+
+use std::error::Error;
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Google, Token};
+
+const YOUTUBE_ANALYTICS_API: &str = "https://youtubeanalytics.googleapis.com/v2";
+
+/// Scope granting read-only access to YouTube Analytics reports.
+pub const SCOPE_ANALYTICS_READONLY: &str = "https://www.googleapis.com/auth/yt-analytics.readonly";
+
+/// A client for the YouTube Analytics API, authorized with a user's access token.
+#[derive(Clone, Copy)]
+pub struct YouTubeAnalytics<'a> {
+    google: &'a Google,
+    token: &'a Token,
+}
+
+/// A metric of a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Metric {
+    Views,
+    EstimatedMinutesWatched,
+    AverageViewDuration,
+    AverageViewPercentage,
+    Likes,
+    Dislikes,
+    Comments,
+    Shares,
+    SubscribersGained,
+    SubscribersLost,
+    /// Any other metric, by its API name.
+    Other(String),
+}
+
+/// A dimension to group report rows by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dimension {
+    Day,
+    Month,
+    Video,
+    Playlist,
+    Country,
+    DeviceType,
+    OperatingSystem,
+    TrafficSourceType,
+    /// Any other dimension, by its API name.
+    Other(String),
+}
+
+/// A report request, built with `ReportQuery::new` and the chained setters.
+#[derive(Debug, Clone)]
+pub struct ReportQuery {
+    ids: String,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    metrics: Vec<Metric>,
+    dimensions: Vec<Dimension>,
+    filters: Vec<String>,
+    sort: Vec<String>,
+    max_results: Option<u32>,
+}
+
+/// A report, as a table of rows whose columns are described by `column_headers`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Report {
+    pub column_headers: Vec<ColumnHeader>,
+    /// One value per column header; dimensions first, then metrics.
+    pub rows: Vec<Vec<Value>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ColumnHeader {
+    pub name: String,
+    /// `DIMENSION` or `METRIC`.
+    pub column_type: String,
+    /// `STRING`, `INTEGER` or `FLOAT`.
+    pub data_type: String,
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metric::Views => "views",
+            Metric::EstimatedMinutesWatched => "estimatedMinutesWatched",
+            Metric::AverageViewDuration => "averageViewDuration",
+            Metric::AverageViewPercentage => "averageViewPercentage",
+            Metric::Likes => "likes",
+            Metric::Dislikes => "dislikes",
+            Metric::Comments => "comments",
+            Metric::Shares => "shares",
+            Metric::SubscribersGained => "subscribersGained",
+            Metric::SubscribersLost => "subscribersLost",
+            Metric::Other(name) => name,
+        })
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dimension::Day => "day",
+            Dimension::Month => "month",
+            Dimension::Video => "video",
+            Dimension::Playlist => "playlist",
+            Dimension::Country => "country",
+            Dimension::DeviceType => "deviceType",
+            Dimension::OperatingSystem => "operatingSystem",
+            Dimension::TrafficSourceType => "insightTrafficSourceType",
+            Dimension::Other(name) => name,
+        })
+    }
+}
+
+impl ReportQuery {
+    /// Creates a query for the authenticated user's channel (`channel==MINE`) covering the
+    /// inclusive date range.
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> ReportQuery {
+        ReportQuery {
+            ids: "channel==MINE".to_string(),
+            start_date,
+            end_date,
+            metrics: Vec::new(),
+            dimensions: Vec::new(),
+            filters: Vec::new(),
+            sort: Vec::new(),
+            max_results: None,
+        }
+    }
+
+    /// Reports on another channel or content owner, e.g. `contentOwner==OWNER_NAME`.
+    pub fn ids(mut self, ids: impl Into<String>) -> ReportQuery {
+        self.ids = ids.into();
+        self
+    }
+
+    /// Adds a metric column.
+    pub fn metric(mut self, metric: Metric) -> ReportQuery {
+        self.metrics.push(metric);
+        self
+    }
+
+    /// Groups rows by a dimension.
+    pub fn dimension(mut self, dimension: Dimension) -> ReportQuery {
+        self.dimensions.push(dimension);
+        self
+    }
+
+    /// Restricts rows to a dimension value, e.g. `Dimension::Country` and `US`.
+    pub fn filter(mut self, dimension: Dimension, value: impl fmt::Display) -> ReportQuery {
+        self.filters.push(format!("{dimension}=={value}"));
+        self
+    }
+
+    /// Sorts rows by a metric or dimension, descending when `descending` is set.
+    pub fn sort(mut self, column: impl fmt::Display, descending: bool) -> ReportQuery {
+        let prefix = if descending { "-" } else { "" };
+        self.sort.push(format!("{prefix}{column}"));
+        self
+    }
+
+    /// Limits the number of rows.
+    pub fn max_results(mut self, max_results: u32) -> ReportQuery {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let metrics: Vec<String> = self.metrics.iter().map(ToString::to_string).collect();
+        let dimensions: Vec<String> = self.dimensions.iter().map(ToString::to_string).collect();
+
+        let mut params = vec![
+            ("ids", self.ids.clone()),
+            ("startDate", self.start_date.to_string()),
+            ("endDate", self.end_date.to_string()),
+            ("metrics", metrics.join(",")),
+        ];
+        if !dimensions.is_empty() {
+            params.push(("dimensions", dimensions.join(",")));
+        }
+        if !self.filters.is_empty() {
+            params.push(("filters", self.filters.join(";")));
+        }
+        if !self.sort.is_empty() {
+            params.push(("sort", self.sort.join(",")));
+        }
+        if let Some(max_results) = self.max_results {
+            params.push(("maxResults", max_results.to_string()));
+        }
+        params
+    }
+}
+
+impl Report {
+    /// Returns the index of the column named `name`, e.g. `views` or `day`.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.column_headers
+            .iter()
+            .position(|header| header.name == name)
+    }
+
+    /// Returns the values of the column named `name`, one per row.
+    pub fn values(&self, name: &str) -> Vec<&Value> {
+        match self.column(name) {
+            Some(index) => self.rows.iter().filter_map(|row| row.get(index)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Google {
+    /// Returns a YouTube Analytics API client that authorizes its requests with `token`.
+    pub fn youtube_analytics<'a>(&'a self, token: &'a Token) -> YouTubeAnalytics<'a> {
+        YouTubeAnalytics {
+            google: self,
+            token,
+        }
+    }
+}
+
+impl YouTubeAnalytics<'_> {
+    /// Runs a report query.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the request fails, e.g. because the combination
+    /// of metrics and dimensions is not a supported report.
+    pub async fn query(&self, query: &ReportQuery) -> Result<Report, Box<dyn Error>> {
+        let request = self
+            .google
+            .http
+            .get(format!("{YOUTUBE_ANALYTICS_API}/reports"))
+            .bearer_auth(self.token.access_token())
+            .query(&query.params());
+
+        self.google.send_json(request).await
+    }
+}